@@ -0,0 +1,152 @@
+use ::std::fmt::Display;
+use ::std::fmt::Formatter;
+use ::std::fmt::Result as FmtResult;
+
+///
+/// A single authentication challenge, from a `WWW-Authenticate` response header.
+///
+/// These are read using [`TestResponse::www_authenticate()`](crate::TestResponse::www_authenticate()).
+///
+/// Scheme and parameter names are matched case-insensitively.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthChallenge {
+    scheme: String,
+    token68: Option<String>,
+    params: Vec<(String, String)>,
+}
+
+impl AuthChallenge {
+    pub(crate) fn new(
+        scheme: String,
+        token68: Option<String>,
+        params: Vec<(String, String)>,
+    ) -> Self {
+        Self {
+            scheme,
+            token68,
+            params,
+        }
+    }
+
+    /// The authentication scheme, such as `Basic` or `Bearer`.
+    #[must_use]
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// Returns true if this challenge is for the scheme given.
+    #[must_use]
+    pub fn is_scheme(&self, scheme: &str) -> bool {
+        self.scheme.eq_ignore_ascii_case(scheme)
+    }
+
+    /// The `token68` sent in place of parameters, if this challenge has one.
+    #[must_use]
+    pub fn token68(&self) -> Option<&str> {
+        self.token68.as_deref()
+    }
+
+    /// All of the parameters, in the order they were sent.
+    #[must_use]
+    pub fn params(&self) -> &[(String, String)] {
+        &self.params
+    }
+
+    /// The value of the parameter given, if it was sent.
+    #[must_use]
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param_name, _)| param_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The `realm` parameter, if it was sent.
+    #[must_use]
+    pub fn realm(&self) -> Option<&str> {
+        self.param("realm")
+    }
+
+    /// The `error` parameter, if it was sent.
+    /// This is used by `Bearer` challenges to say why the token was rejected.
+    #[must_use]
+    pub fn error(&self) -> Option<&str> {
+        self.param("error")
+    }
+
+    /// The `error_description` parameter, if it was sent.
+    #[must_use]
+    pub fn error_description(&self) -> Option<&str> {
+        self.param("error_description")
+    }
+}
+
+impl Display for AuthChallenge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.scheme)?;
+
+        if let Some(token68) = &self.token68 {
+            write!(f, " {token68}")?;
+        }
+
+        for (i, (name, value)) in self.params.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+            write!(f, "{separator}{name}=\"{escaped}\"")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_param {
+    use super::*;
+
+    #[test]
+    fn it_should_find_params_ignoring_case() {
+        let challenge = AuthChallenge::new(
+            "Bearer".to_string(),
+            None,
+            vec![
+                ("Realm".to_string(), "example".to_string()),
+                ("error".to_string(), "invalid_token".to_string()),
+            ],
+        );
+
+        assert_eq!(challenge.realm(), Some("example"));
+        assert_eq!(challenge.error(), Some("invalid_token"));
+        assert_eq!(challenge.error_description(), None);
+    }
+}
+
+#[cfg(test)]
+mod test_display {
+    use super::*;
+
+    #[test]
+    fn it_should_display_params_quoted() {
+        let challenge = AuthChallenge::new(
+            "Bearer".to_string(),
+            None,
+            vec![
+                ("realm".to_string(), "example".to_string()),
+                ("error_description".to_string(), "the \"token\"".to_string()),
+            ],
+        );
+
+        assert_eq!(
+            challenge.to_string(),
+            r#"Bearer realm="example", error_description="the \"token\"""#
+        );
+    }
+
+    #[test]
+    fn it_should_display_token68() {
+        let challenge =
+            AuthChallenge::new("Negotiate".to_string(), Some("abc==".to_string()), vec![]);
+
+        assert_eq!(challenge.to_string(), "Negotiate abc==");
+    }
+}
@@ -0,0 +1,645 @@
+use ::anyhow::Context;
+use ::http::header;
+use ::http::HeaderName;
+use ::http::HeaderValue;
+use ::http::Method;
+use ::serde_json::Value;
+use ::std::collections::BTreeSet;
+use ::std::fmt::Debug;
+use ::std::fs::read;
+use ::std::future::Future;
+use ::std::future::IntoFuture;
+use ::std::path::Path;
+use ::std::pin::Pin;
+use ::url::Url;
+
+use crate::internals::ExpectedState;
+use crate::internals::Har;
+use crate::internals::HarEntry;
+use crate::internals::HarRequest;
+use crate::HarDifference;
+use crate::HarReplayReport;
+use crate::TestRequest;
+use crate::TestResponse;
+use crate::TestServer;
+
+/// Headers which are not sent when replaying,
+/// as they describe the original connection rather than the request.
+const SKIPPED_REQUEST_HEADERS: [&str; 11] = [
+    "accept-encoding",
+    "connection",
+    "content-length",
+    "content-type",
+    "host",
+    "keep-alive",
+    "proxy-connection",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+const MISSING_VALUE: &str = "(missing)";
+
+///
+/// Replays the requests recorded in a HAR file against the [`TestServer`](crate::TestServer),
+/// and compares each response against the one recorded.
+///
+/// This is created by calling [`TestServer::replay_har()`](crate::TestServer::replay_har()),
+/// and is run by awaiting it. This returns a [`HarReplayReport`](crate::HarReplayReport) of all differences found.
+///
+/// The status code and body of each response are always compared.
+/// Headers are only compared when chosen using [`HarReplay::compare_header()`](crate::HarReplay::compare_header()).
+/// Json bodies are compared value by value,
+/// where volatile values (such as timestamps and IDs) can be skipped using
+/// [`HarReplay::ignore_json_field()`](crate::HarReplay::ignore_json_field())
+/// and [`HarReplay::ignore_json_pointer()`](crate::HarReplay::ignore_json_pointer()).
+///
+/// ```rust,no_run
+/// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+/// #
+/// use ::axum::Router;
+/// use ::axum_test::TestServer;
+/// use ::http::header;
+///
+/// let app = Router::new();
+/// let server = TestServer::new(app)?;
+///
+/// server
+///     .replay_har(&"tests/recordings/checkout.har")
+///     .compare_header(header::CONTENT_TYPE)
+///     .ignore_json_field(&"created_at")
+///     .ignore_json_pointer(&"/order/id")
+///     .await
+///     .assert_match();
+/// #
+/// # Ok(())
+/// # }
+/// ```
+///
+#[must_use = "the HAR file is only replayed when awaited"]
+pub struct HarReplay<'a> {
+    server: &'a TestServer,
+    entries: Vec<HarEntry>,
+    compared_headers: Vec<HeaderName>,
+    ignored_json_fields: Vec<String>,
+    ignored_json_pointers: Vec<String>,
+}
+
+impl<'a> HarReplay<'a> {
+    pub(crate) fn new(server: &'a TestServer, path: &Path) -> Self {
+        let debug_path = path.display();
+        let har_bytes = read(path)
+            .with_context(|| format!("Reading HAR file, from {debug_path}"))
+            .unwrap();
+        let har = ::serde_json::from_slice::<Har>(&har_bytes)
+            .with_context(|| format!("Parsing HAR file, from {debug_path}"))
+            .unwrap();
+
+        Self {
+            server,
+            entries: har.log.entries,
+            compared_headers: Vec::new(),
+            ignored_json_fields: Vec::new(),
+            ignored_json_pointers: Vec::new(),
+        }
+    }
+
+    /// Compares the header given, between the recorded and received responses.
+    pub fn compare_header(mut self, header_name: HeaderName) -> Self {
+        self.compared_headers.push(header_name);
+        self
+    }
+
+    /// Skips comparing any field in a Json body with this name,
+    /// at any depth.
+    pub fn ignore_json_field(mut self, field_name: &str) -> Self {
+        self.ignored_json_fields.push(field_name.to_string());
+        self
+    }
+
+    /// Skips comparing the value at this [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901),
+    /// i.e. `/user/created_at` or `/items/0/id`.
+    pub fn ignore_json_pointer(mut self, pointer: &str) -> Self {
+        self.ignored_json_pointers.push(pointer.to_string());
+        self
+    }
+
+    async fn replay(self) -> HarReplayReport {
+        let mut differences = Vec::new();
+
+        for (entry_index, entry) in self.entries.iter().enumerate() {
+            let request = self.build_request(&entry.request);
+            let request_label =
+                format!("{} {}", entry.request.method, request_path(&entry.request));
+            let response = request.await;
+
+            let mut push_difference = |field: String, expected: String, received: String| {
+                differences.push(HarDifference::new(
+                    entry_index,
+                    request_label.clone(),
+                    field,
+                    expected,
+                    received,
+                ));
+            };
+
+            let expected_status = entry.response.status;
+            let received_status = response.status_code().as_u16();
+            if expected_status != received_status {
+                push_difference(
+                    "status".to_string(),
+                    expected_status.to_string(),
+                    received_status.to_string(),
+                );
+            }
+
+            for header_name in &self.compared_headers {
+                let expected_header = entry
+                    .response
+                    .headers
+                    .iter()
+                    .find(|har_header| header_name.as_str().eq_ignore_ascii_case(&har_header.name))
+                    .map(|har_header| har_header.value.clone());
+                let received_header = response.maybe_header(header_name).map(|header_value| {
+                    String::from_utf8_lossy(header_value.as_bytes()).into_owned()
+                });
+
+                if expected_header != received_header {
+                    push_difference(
+                        format!("header {header_name}"),
+                        expected_header.unwrap_or_else(|| MISSING_VALUE.to_string()),
+                        received_header.unwrap_or_else(|| MISSING_VALUE.to_string()),
+                    );
+                }
+            }
+
+            // Some tools leave out the body, so it can't be compared.
+            if entry.response.content.text.is_some() {
+                for (pointer, expected, received) in self.diff_body(entry, &response) {
+                    let field = match pointer.is_empty() {
+                        true => "body".to_string(),
+                        false => format!("body {pointer}"),
+                    };
+
+                    push_difference(field, expected, received);
+                }
+            }
+        }
+
+        HarReplayReport::new(self.entries.len(), differences)
+    }
+
+    fn build_request(&self, har_request: &HarRequest) -> TestRequest {
+        let method = Method::from_bytes(har_request.method.as_bytes())
+            .with_context(|| format!("Reading method from HAR entry, {}", har_request.method))
+            .unwrap();
+
+        // Replayed requests can return any status, so the server's default expectation is not used.
+        let mut config = self
+            .server
+            .test_request_config(method, &request_path(har_request));
+        config.expected_state = ExpectedState::None;
+        let mut request = self.server.request_with_config(config);
+
+        if let Ok(url) = Url::parse(&har_request.url) {
+            let query_params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+            if !query_params.is_empty() {
+                request = request.add_query_params(query_params);
+            }
+        }
+
+        let mut maybe_content_type = None;
+        for har_header in &har_request.headers {
+            let header_name = har_header.name.to_ascii_lowercase();
+            if header_name == header::CONTENT_TYPE.as_str() {
+                maybe_content_type = Some(har_header.value.clone());
+            }
+
+            if header_name.starts_with(':')
+                || SKIPPED_REQUEST_HEADERS.contains(&header_name.as_str())
+            {
+                continue;
+            }
+
+            let name = HeaderName::from_bytes(header_name.as_bytes());
+            let value = HeaderValue::from_str(&har_header.value);
+            if let (Ok(name), Ok(value)) = (name, value) {
+                request = request.add_header(name, value);
+            }
+        }
+
+        if let Some(post_data) = &har_request.post_data {
+            request = request.bytes(post_data.text.clone().into());
+
+            if maybe_content_type.is_none() && !post_data.mime_type.is_empty() {
+                maybe_content_type = Some(post_data.mime_type.clone());
+            }
+        }
+
+        if let Some(content_type) = maybe_content_type {
+            request = request.content_type(&content_type);
+        }
+
+        request
+    }
+
+    /// Returns the differences in the body, as the JSON Pointer to each difference,
+    /// followed by the expected and received values.
+    fn diff_body(
+        &self,
+        entry: &HarEntry,
+        response: &TestResponse,
+    ) -> Vec<(String, String, String)> {
+        let expected_bytes = entry.response.content.body_bytes();
+        let received_bytes = response.as_bytes();
+
+        let expected_json = ::serde_json::from_slice::<Value>(&expected_bytes);
+        let received_json = ::serde_json::from_slice::<Value>(received_bytes);
+        if let (Ok(expected_json), Ok(received_json)) = (expected_json, received_json) {
+            let mut differences = Vec::new();
+            self.diff_json(
+                String::new(),
+                Some(&expected_json),
+                Some(&received_json),
+                &mut differences,
+            );
+
+            return differences;
+        }
+
+        if expected_bytes[..] == received_bytes[..] {
+            return Vec::new();
+        }
+
+        vec![(
+            String::new(),
+            String::from_utf8_lossy(&expected_bytes).into_owned(),
+            String::from_utf8_lossy(received_bytes).into_owned(),
+        )]
+    }
+
+    fn diff_json(
+        &self,
+        pointer: String,
+        maybe_expected: Option<&Value>,
+        maybe_received: Option<&Value>,
+        differences: &mut Vec<(String, String, String)>,
+    ) {
+        if self.ignored_json_pointers.contains(&pointer) {
+            return;
+        }
+
+        match (maybe_expected, maybe_received) {
+            (Some(Value::Object(expected)), Some(Value::Object(received))) => {
+                let keys: BTreeSet<&String> = expected.keys().chain(received.keys()).collect();
+
+                for key in keys {
+                    if self.ignored_json_fields.contains(key) {
+                        continue;
+                    }
+
+                    let child_pointer = format!("{pointer}/{}", escape_json_pointer(key));
+                    self.diff_json(
+                        child_pointer,
+                        expected.get(key),
+                        received.get(key),
+                        differences,
+                    );
+                }
+            }
+            (Some(Value::Array(expected)), Some(Value::Array(received))) => {
+                let len = expected.len().max(received.len());
+
+                for index in 0..len {
+                    let child_pointer = format!("{pointer}/{index}");
+                    self.diff_json(
+                        child_pointer,
+                        expected.get(index),
+                        received.get(index),
+                        differences,
+                    );
+                }
+            }
+            (maybe_expected, maybe_received) if maybe_expected != maybe_received => {
+                differences.push((
+                    pointer,
+                    json_to_string(maybe_expected),
+                    json_to_string(maybe_received),
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'a> IntoFuture for HarReplay<'a> {
+    type Output = HarReplayReport;
+    type IntoFuture = Pin<Box<dyn Future<Output = HarReplayReport> + 'a>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.replay())
+    }
+}
+
+impl<'a> Debug for HarReplay<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HarReplay")
+            .field("entries", &self.entries.len())
+            .field("compared_headers", &self.compared_headers)
+            .field("ignored_json_fields", &self.ignored_json_fields)
+            .field("ignored_json_pointers", &self.ignored_json_pointers)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The path to send the request to, ignoring the host it was recorded against.
+fn request_path(har_request: &HarRequest) -> String {
+    match Url::parse(&har_request.url) {
+        Ok(url) => url.path().to_string(),
+        Err(_) => har_request.url.clone(),
+    }
+}
+
+fn escape_json_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn json_to_string(maybe_value: Option<&Value>) -> String {
+    match maybe_value {
+        Some(value) => value.to_string(),
+        None => MISSING_VALUE.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test_replay {
+    use ::axum::extract::Path as AxumPath;
+    use ::axum::extract::Query;
+    use ::axum::extract::State;
+    use ::axum::http::StatusCode;
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Json;
+    use ::axum::Router;
+    use ::http::header;
+    use ::serde::Deserialize;
+    use ::serde_json::json;
+    use ::serde_json::Value;
+    use ::std::env::temp_dir;
+    use ::std::fs::write;
+    use ::std::path::PathBuf;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+
+    #[derive(Deserialize)]
+    struct Greeting {
+        name: String,
+    }
+
+    async fn get_user(
+        State(user_name): State<&'static str>,
+        AxumPath(id): AxumPath<u32>,
+    ) -> Result<Json<Value>, StatusCode> {
+        if id == 1 {
+            Ok(Json(json!({ "id": id, "name": user_name })))
+        } else {
+            Err(StatusCode::NOT_FOUND)
+        }
+    }
+
+    async fn post_echo(Json(body): Json<Value>) -> Json<Value> {
+        Json(body)
+    }
+
+    async fn get_greeting(Query(greeting): Query<Greeting>) -> String {
+        format!("hello {}", greeting.name)
+    }
+
+    fn record_har(file_name: &str) -> PathBuf {
+        let har_path = temp_dir().join(file_name);
+        let app = Router::new()
+            .route("/users/:id", get(get_user))
+            .route("/echo", post(post_echo))
+            .with_state("Joe");
+        let config = TestServerConfig::builder().record_har(&har_path).build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let runtime = ::tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            server.get(&"/users/1").await;
+            server
+                .post(&"/echo")
+                .json(&json!({ "hello": "world" }))
+                .await;
+        });
+        ::std::mem::drop(server);
+
+        har_path
+    }
+
+    #[test]
+    fn it_should_match_recording_from_same_app() {
+        let har_path = record_har("axum-test-replay-same-app.har");
+        let runtime = ::tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let app = Router::new()
+                .route("/users/:id", get(get_user))
+                .route("/echo", post(post_echo))
+                .with_state("Joe");
+            let server = TestServer::new(app).expect("Should create test server");
+            let report = server.replay_har(&har_path).await;
+
+            assert_eq!(report.entries_replayed(), 2);
+            report.assert_match();
+        });
+    }
+
+    #[test]
+    fn it_should_report_differences_in_body() {
+        let har_path = record_har("axum-test-replay-changed-app.har");
+        let runtime = ::tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let app = Router::new()
+                .route("/users/:id", get(get_user))
+                .route("/echo", post(post_echo))
+                .with_state("Kate");
+            let server = TestServer::new(app).expect("Should create test server");
+            let report = server.replay_har(&har_path).await;
+
+            assert_eq!(report.differences().len(), 1);
+            let difference = &report.differences()[0];
+            assert_eq!(difference.entry_index(), 0);
+            assert_eq!(difference.request(), "GET /users/1");
+            assert_eq!(difference.field(), "body /name");
+            assert_eq!(difference.expected(), r#""Joe""#);
+            assert_eq!(difference.received(), r#""Kate""#);
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Entry 0, GET /users/1, body /name expected \"Joe\", received \"Kate\""
+    )]
+    fn it_should_panic_listing_differences_when_asserting_match() {
+        let har_path = record_har("axum-test-replay-assert-match.har");
+        let runtime = ::tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let app = Router::new()
+                .route("/users/:id", get(get_user))
+                .route("/echo", post(post_echo))
+                .with_state("Kate");
+            let server = TestServer::new(app).expect("Should create test server");
+            server.replay_har(&har_path).await.assert_match();
+        });
+    }
+
+    fn write_browser_har(file_name: &str) -> PathBuf {
+        let har = json!({
+            "log": {
+                "version": "1.2",
+                "creator": { "name": "Browser", "version": "1.0" },
+                "entries": [
+                    {
+                        "startedDateTime": "2023-09-02T14:05:09.123Z",
+                        "time": 12.5,
+                        "request": {
+                            "method": "GET",
+                            "url": "https://example.com/greeting?name=Joe",
+                            "httpVersion": "HTTP/2",
+                            "headers": [
+                                { "name": ":authority", "value": "example.com" },
+                                { "name": "accept-encoding", "value": "gzip, br" },
+                                { "name": "accept", "value": "text/plain" }
+                            ],
+                            "queryString": [{ "name": "name", "value": "Joe" }],
+                            "cookies": [],
+                            "headersSize": -1,
+                            "bodySize": 0
+                        },
+                        "response": {
+                            "status": 200,
+                            "statusText": "",
+                            "httpVersion": "HTTP/2",
+                            "headers": [
+                                { "name": "Content-Type", "value": "text/html" }
+                            ],
+                            "cookies": [],
+                            "content": { "size": 9, "mimeType": "text/plain", "text": "hello Joe" },
+                            "redirectURL": "",
+                            "headersSize": -1,
+                            "bodySize": 9
+                        },
+                        "cache": {},
+                        "timings": { "send": 0, "wait": 12.5, "receive": 0 }
+                    },
+                    {
+                        "startedDateTime": "2023-09-02T14:05:10.000Z",
+                        "time": 3.0,
+                        "request": {
+                            "method": "POST",
+                            "url": "https://example.com/echo",
+                            "headers": [],
+                            "postData": { "mimeType": "application/json", "text": "{\"id\":1,\"created_at\":\"yesterday\",\"order\":{\"id\":5,\"total\":10}}" }
+                        },
+                        "response": {
+                            "status": 200,
+                            "headers": [
+                                { "name": "content-type", "value": "application/json" }
+                            ],
+                            "content": { "mimeType": "application/json", "text": "{\"id\":2,\"created_at\":\"today\",\"order\":{\"id\":6,\"total\":10}}" }
+                        }
+                    }
+                ]
+            }
+        });
+
+        let har_path = temp_dir().join(file_name);
+        write(&har_path, ::serde_json::to_vec(&har).unwrap()).unwrap();
+        har_path
+    }
+
+    #[tokio::test]
+    async fn it_should_ignore_json_fields_and_pointers() {
+        let har_path = write_browser_har("axum-test-replay-ignore.har");
+        let app = Router::new()
+            .route("/greeting", get(get_greeting))
+            .route("/echo", post(post_echo));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .replay_har(&har_path)
+            .ignore_json_field(&"id")
+            .ignore_json_pointer(&"/created_at")
+            .await
+            .assert_match();
+    }
+
+    #[tokio::test]
+    async fn it_should_report_ignored_values_when_not_ignored() {
+        let har_path = write_browser_har("axum-test-replay-not-ignored.har");
+        let app = Router::new()
+            .route("/greeting", get(get_greeting))
+            .route("/echo", post(post_echo));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let report = server.replay_har(&har_path).await;
+        let fields: Vec<&str> = report
+            .differences()
+            .iter()
+            .map(|difference| difference.field())
+            .collect();
+
+        assert_eq!(
+            fields,
+            vec!["body /created_at", "body /id", "body /order/id"]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_compare_chosen_headers() {
+        let har_path = write_browser_har("axum-test-replay-headers.har");
+        let app = Router::new()
+            .route("/greeting", get(get_greeting))
+            .route("/echo", post(post_echo));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let report = server
+            .replay_har(&har_path)
+            .compare_header(header::CONTENT_TYPE)
+            .ignore_json_field(&"id")
+            .ignore_json_field(&"created_at")
+            .await;
+
+        assert_eq!(report.differences().len(), 1);
+        let difference = &report.differences()[0];
+        assert_eq!(difference.request(), "GET /greeting");
+        assert_eq!(difference.field(), "header content-type");
+        assert_eq!(difference.expected(), "text/html");
+        assert_eq!(difference.received(), "text/plain; charset=utf-8");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Reading HAR file, from")]
+    async fn it_should_panic_when_file_does_not_exist() {
+        let server = TestServer::new(Router::new()).expect("Should create test server");
+
+        let _ = server.replay_har(&"/this/file/does/not/exist.har");
+    }
+}
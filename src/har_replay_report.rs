@@ -0,0 +1,170 @@
+use ::std::fmt::Display;
+use ::std::fmt::Formatter;
+use ::std::fmt::Result as FmtResult;
+
+///
+/// A difference found when replaying a HAR file,
+/// between the recorded response and the response received.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarDifference {
+    entry_index: usize,
+    request: String,
+    field: String,
+    expected: String,
+    received: String,
+}
+
+impl HarDifference {
+    pub(crate) fn new(
+        entry_index: usize,
+        request: String,
+        field: String,
+        expected: String,
+        received: String,
+    ) -> Self {
+        Self {
+            entry_index,
+            request,
+            field,
+            expected,
+            received,
+        }
+    }
+
+    /// The position of the entry in the HAR file, starting from 0.
+    #[must_use]
+    pub fn entry_index(&self) -> usize {
+        self.entry_index
+    }
+
+    /// The method and path of the request, i.e. `GET /users/1`.
+    #[must_use]
+    pub fn request(&self) -> &str {
+        &self.request
+    }
+
+    /// What was different.
+    ///
+    /// This is `status`, `header <name>`, or `body` followed by
+    /// the JSON Pointer to the value which is different (i.e. `body /user/name`).
+    #[must_use]
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// The value from the recorded response.
+    #[must_use]
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    /// The value from the response received.
+    #[must_use]
+    pub fn received(&self) -> &str {
+        &self.received
+    }
+}
+
+impl Display for HarDifference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let entry_index = self.entry_index;
+        let request = &self.request;
+        let field = &self.field;
+        let expected = &self.expected;
+        let received = &self.received;
+
+        write!(
+            f,
+            "Entry {entry_index}, {request}, {field} expected {expected}, received {received}"
+        )
+    }
+}
+
+///
+/// The result of replaying a HAR file against the [`TestServer`](crate::TestServer),
+/// using [`TestServer::replay_har()`](crate::TestServer::replay_har()).
+///
+#[derive(Debug, Clone)]
+pub struct HarReplayReport {
+    entries_replayed: usize,
+    differences: Vec<HarDifference>,
+}
+
+impl HarReplayReport {
+    pub(crate) fn new(entries_replayed: usize, differences: Vec<HarDifference>) -> Self {
+        Self {
+            entries_replayed,
+            differences,
+        }
+    }
+
+    /// How many entries from the HAR file were sent.
+    #[must_use]
+    pub fn entries_replayed(&self) -> usize {
+        self.entries_replayed
+    }
+
+    /// All differences found, in the order of the entries.
+    #[must_use]
+    pub fn differences(&self) -> &[HarDifference] {
+        &self.differences
+    }
+
+    /// Returns true if every response matched its recording.
+    #[must_use]
+    pub fn is_match(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Asserts that every response matched its recording.
+    ///
+    /// If any differences were found, this will panic listing all of them.
+    #[track_caller]
+    pub fn assert_match(&self) {
+        assert!(self.is_match(), "{self}");
+    }
+}
+
+impl Display for HarReplayReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let entries_replayed = self.entries_replayed;
+        let differences_count = self.differences.len();
+
+        write!(
+            f,
+            "Replayed {entries_replayed} HAR entries, found {differences_count} differences"
+        )?;
+        for difference in &self.differences {
+            write!(f, "\n    {difference}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_assert_match {
+    use super::*;
+
+    #[test]
+    fn it_should_pass_when_there_are_no_differences() {
+        HarReplayReport::new(3, vec![]).assert_match();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Replayed 3 HAR entries, found 1 differences\n    Entry 1, GET /users/1, status expected 200, received 404"
+    )]
+    fn it_should_panic_listing_differences() {
+        let difference = HarDifference::new(
+            1,
+            "GET /users/1".to_string(),
+            "status".to_string(),
+            "200".to_string(),
+            "404".to_string(),
+        );
+
+        HarReplayReport::new(3, vec![difference]).assert_match();
+    }
+}
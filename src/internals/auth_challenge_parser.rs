@@ -0,0 +1,291 @@
+use ::anyhow::anyhow;
+use ::anyhow::Result;
+
+use crate::AuthChallenge;
+
+///
+/// Parses the challenges in a `WWW-Authenticate` header,
+/// following the grammar from RFC 7235.
+///
+/// A single header can hold multiple challenges separated by commas,
+/// and so can the parameters within each challenge.
+/// A new challenge is found when a token is not followed by an `=`.
+///
+#[derive(Debug)]
+pub struct AuthChallengeParser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> AuthChallengeParser<'a> {
+    pub fn parse(input: &'a str) -> Result<Vec<AuthChallenge>> {
+        let mut parser = Self { input, position: 0 };
+        let mut challenges = vec![];
+
+        loop {
+            parser.skip_list_separators();
+            if parser.is_end() {
+                break;
+            }
+
+            challenges.push(parser.parse_challenge()?);
+        }
+
+        Ok(challenges)
+    }
+
+    fn parse_challenge(&mut self) -> Result<AuthChallenge> {
+        let scheme = self.parse_token("auth scheme")?;
+
+        self.skip_whitespace();
+        if self.is_end() || self.peek() == Some(',') {
+            return Ok(AuthChallenge::new(scheme, None, vec![]));
+        }
+
+        if let Some(token68) = self.try_parse_token68() {
+            return Ok(AuthChallenge::new(scheme, Some(token68), vec![]));
+        }
+
+        let mut params = vec![];
+        loop {
+            params.push(self.parse_param()?);
+
+            self.skip_whitespace();
+            if self.is_end() {
+                break;
+            }
+            self.expect(',')?;
+
+            let param_end = self.position;
+            self.skip_list_separators();
+            if self.is_end() {
+                break;
+            }
+
+            if !self.is_at_param() {
+                self.position = param_end;
+                break;
+            }
+        }
+
+        Ok(AuthChallenge::new(scheme, None, params))
+    }
+
+    fn parse_param(&mut self) -> Result<(String, String)> {
+        let name = self.parse_token("parameter name")?;
+        self.skip_whitespace();
+        self.expect('=')?;
+        self.skip_whitespace();
+
+        let value = if self.peek() == Some('"') {
+            self.parse_quoted_string()?
+        } else {
+            self.parse_token("parameter value")?
+        };
+
+        Ok((name, value))
+    }
+
+    /// Returns true if the next item is a parameter,
+    /// rather than the scheme of a new challenge.
+    fn is_at_param(&self) -> bool {
+        let rest = &self.input[self.position..];
+        let token_len = rest.find(|c| !is_token_char(c)).unwrap_or(rest.len());
+
+        token_len > 0
+            && rest[token_len..]
+                .trim_start_matches([' ', '\t'])
+                .starts_with('=')
+    }
+
+    /// Parses a `token68`, restoring the position if what follows is not one.
+    fn try_parse_token68(&mut self) -> Option<String> {
+        let start = self.position;
+        let rest = &self.input[start..];
+
+        let chars_len = rest.find(|c| !is_token68_char(c)).unwrap_or(rest.len());
+        if chars_len == 0 {
+            return None;
+        }
+        let padding_len = rest[chars_len..]
+            .find(|c| c != '=')
+            .unwrap_or(rest.len() - chars_len);
+        let token68 = &rest[..chars_len + padding_len];
+
+        self.position += token68.len();
+        self.skip_whitespace();
+        if self.is_end() || self.peek() == Some(',') {
+            return Some(token68.to_string());
+        }
+
+        self.position = start;
+        None
+    }
+
+    fn parse_token(&mut self, description: &str) -> Result<String> {
+        let rest = &self.input[self.position..];
+        let token_len = rest.find(|c| !is_token_char(c)).unwrap_or(rest.len());
+
+        if token_len == 0 {
+            return Err(self.error(&format!("Expected {description}")));
+        }
+
+        self.position += token_len;
+        Ok(rest[..token_len].to_string())
+    }
+
+    fn parse_quoted_string(&mut self) -> Result<String> {
+        self.expect('"')?;
+
+        let mut value = String::new();
+        let mut chars = self.input[self.position..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += i + 1;
+                    return Ok(value);
+                }
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => value.push(escaped),
+                    None => break,
+                },
+                _ => value.push(c),
+            }
+        }
+
+        self.position = self.input.len();
+        Err(self.error("Unterminated quoted string"))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("Expected '{expected}'")));
+        }
+
+        self.position += expected.len_utf8();
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    }
+
+    fn skip_list_separators(&mut self) {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start_matches([' ', '\t', ',']).len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    fn is_end(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
+    fn error(&self, message: &str) -> anyhow::Error {
+        let input = self.input;
+        let position = self.position;
+
+        anyhow!("{message} at position {position}, in WWW-Authenticate header '{input}'")
+    }
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+fn is_token68_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "-._~+/".contains(c)
+}
+
+#[cfg(test)]
+mod test_parse {
+    use super::*;
+
+    fn challenge(scheme: &str, params: &[(&str, &str)]) -> AuthChallenge {
+        let params = params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+
+        AuthChallenge::new(scheme.to_string(), None, params)
+    }
+
+    #[test]
+    fn it_should_parse_scheme_without_params() {
+        let challenges = AuthChallengeParser::parse("Basic").unwrap();
+
+        assert_eq!(challenges, vec![challenge("Basic", &[])]);
+    }
+
+    #[test]
+    fn it_should_parse_quoted_and_token_params() {
+        let challenges =
+            AuthChallengeParser::parse(r#"Bearer realm="example", error=invalid_token"#).unwrap();
+
+        assert_eq!(
+            challenges,
+            vec![challenge(
+                "Bearer",
+                &[("realm", "example"), ("error", "invalid_token")]
+            )]
+        );
+    }
+
+    #[test]
+    fn it_should_parse_multiple_challenges() {
+        let challenges = AuthChallengeParser::parse(
+            r#"Basic realm="simple", Bearer realm="api", scope="read write", Negotiate"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            challenges,
+            vec![
+                challenge("Basic", &[("realm", "simple")]),
+                challenge("Bearer", &[("realm", "api"), ("scope", "read write")]),
+                challenge("Negotiate", &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_unescape_quoted_strings() {
+        let challenges = AuthChallengeParser::parse(
+            r#"Bearer error_description="the \"token\", has \\ expired""#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            challenges[0].error_description(),
+            Some(r#"the "token", has \ expired"#)
+        );
+    }
+
+    #[test]
+    fn it_should_parse_token68() {
+        let challenges = AuthChallengeParser::parse("Negotiate YIIB+g==, Basic realm=x").unwrap();
+
+        assert_eq!(challenges[0].token68(), Some("YIIB+g=="));
+        assert_eq!(challenges[1], challenge("Basic", &[("realm", "x")]));
+    }
+
+    #[test]
+    fn it_should_allow_whitespace_around_equals() {
+        let challenges = AuthChallengeParser::parse(r#"Bearer realm = "example""#).unwrap();
+
+        assert_eq!(
+            challenges,
+            vec![challenge("Bearer", &[("realm", "example")])]
+        );
+    }
+
+    #[test]
+    fn it_should_error_on_unterminated_quoted_string() {
+        let result = AuthChallengeParser::parse(r#"Bearer realm="example"#);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::base64::engine::general_purpose::STANDARD as BASE64;
+use ::base64::Engine;
+use ::http::HeaderValue;
+use ::std::fmt::Display;
+
+/// Builds the value of an `Authorization` header for a bearer token.
+///
+/// The value is marked as sensitive.
+pub fn bearer_authorization_header<T>(token: T) -> Result<HeaderValue>
+where
+    T: Display,
+{
+    let mut header_value = HeaderValue::from_str(&format!("Bearer {token}"))
+        .context("Building bearer Authorization header, token contains invalid characters")?;
+    header_value.set_sensitive(true);
+
+    Ok(header_value)
+}
+
+/// Builds the value of an `Authorization` header for HTTP Basic authentication.
+///
+/// The value is marked as sensitive.
+pub fn basic_authorization_header<U, P>(username: U, password: P) -> Result<HeaderValue>
+where
+    U: Display,
+    P: Display,
+{
+    let credentials = BASE64.encode(format!("{username}:{password}"));
+    let mut header_value = HeaderValue::from_str(&format!("Basic {credentials}"))
+        .context("Building basic Authorization header")?;
+    header_value.set_sensitive(true);
+
+    Ok(header_value)
+}
+
+#[cfg(test)]
+mod test_bearer_authorization_header {
+    use super::*;
+
+    #[test]
+    fn it_should_build_bearer_header() {
+        let header_value = bearer_authorization_header("abc123").unwrap();
+
+        assert_eq!(header_value, "Bearer abc123");
+        assert!(header_value.is_sensitive());
+    }
+
+    #[test]
+    fn it_should_error_on_invalid_token() {
+        let result = bearer_authorization_header("abc\n123");
+
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_basic_authorization_header {
+    use super::*;
+
+    #[test]
+    fn it_should_build_basic_header() {
+        let header_value = basic_authorization_header("Aladdin", "open sesame").unwrap();
+
+        assert_eq!(header_value, "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
+        assert!(header_value.is_sensitive());
+    }
+}
@@ -0,0 +1,495 @@
+use ::cookie::Cookie;
+use ::cookie::CookieJar;
+use ::std::net::IpAddr;
+use ::std::time::Duration;
+use ::std::time::SystemTime;
+use ::url::Url;
+
+/// Stores cookies following the rules browsers use,
+/// from [RFC 6265](https://datatracker.ietf.org/doc/html/rfc6265).
+///
+/// Cookies are removed when they expire, or when they are deleted by a
+/// `Max-Age` of zero or an `Expires` in the past.
+/// They are only sent to requests with a matching path and domain,
+/// and `Secure` cookies are only sent over `https`.
+#[derive(Debug, Clone, Default)]
+pub struct CookieStore {
+    cookies: Vec<StoredCookie>,
+}
+
+#[derive(Debug, Clone)]
+struct StoredCookie {
+    cookie: Cookie<'static>,
+
+    /// `None` matches every host.
+    /// This is used for cookies added directly, rather than by a response.
+    maybe_domain: Option<String>,
+    is_host_only: bool,
+    path: String,
+    maybe_expires_at: Option<SystemTime>,
+}
+
+impl StoredCookie {
+    /// Cookies added directly have no domain,
+    /// and so are replaced by any cookie with the same name and path.
+    fn is_same_cookie(&self, other: &Self) -> bool {
+        let is_same_domain = match (&self.maybe_domain, &other.maybe_domain) {
+            (Some(domain), Some(other_domain)) => domain == other_domain,
+            _ => true,
+        };
+
+        self.cookie.name() == other.cookie.name() && self.path == other.path && is_same_domain
+    }
+
+    fn is_expired(&self, now: SystemTime) -> bool {
+        self.maybe_expires_at
+            .map(|expires_at| expires_at <= now)
+            .unwrap_or(false)
+    }
+
+    fn is_match(&self, url: &Url, now: SystemTime) -> bool {
+        if self.is_expired(now) {
+            return false;
+        }
+
+        if self.cookie.secure().unwrap_or(false) && !is_secure_url(url) {
+            return false;
+        }
+
+        let host = url_host(url);
+        let is_domain_match = match &self.maybe_domain {
+            None => true,
+            Some(domain) if self.is_host_only => host == *domain,
+            Some(domain) => is_domain_match(&host, domain),
+        };
+
+        is_domain_match && is_path_match(url.path(), &self.path)
+    }
+}
+
+impl CookieStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores a cookie from a `Set-Cookie` header,
+    /// returned from a request to the url given.
+    ///
+    /// Cookies with a `Domain` which the url is not within are ignored.
+    pub fn add_from_response(
+        &mut self,
+        cookie: Cookie<'static>,
+        request_url: &Url,
+        now: SystemTime,
+    ) {
+        let host = url_host(request_url);
+        let (domain, is_host_only) = match cookie.domain() {
+            Some(domain) if !domain.is_empty() => {
+                let domain = domain.trim_start_matches('.').to_ascii_lowercase();
+                if !is_domain_match(&host, &domain) {
+                    return;
+                }
+
+                (domain, false)
+            }
+            _ => (host, true),
+        };
+
+        let path = match cookie.path() {
+            Some(path) if path.starts_with('/') => path.to_string(),
+            _ => default_path(request_url.path()),
+        };
+
+        self.store(
+            StoredCookie {
+                maybe_expires_at: expires_at(&cookie, now),
+                cookie,
+                maybe_domain: Some(domain),
+                is_host_only,
+                path,
+            },
+            now,
+        );
+    }
+
+    /// Stores a cookie added directly.
+    ///
+    /// Unless it has a `Domain` or `Path`, it is sent to every request.
+    pub fn add(&mut self, cookie: Cookie<'static>, now: SystemTime) {
+        let maybe_domain = cookie
+            .domain()
+            .filter(|domain| !domain.is_empty())
+            .map(|domain| domain.trim_start_matches('.').to_ascii_lowercase());
+        let path = cookie
+            .path()
+            .filter(|path| path.starts_with('/'))
+            .unwrap_or("/")
+            .to_string();
+
+        self.store(
+            StoredCookie {
+                maybe_expires_at: expires_at(&cookie, now),
+                cookie,
+                maybe_domain,
+                is_host_only: false,
+                path,
+            },
+            now,
+        );
+    }
+
+    pub fn clear(&mut self) {
+        self.cookies.clear();
+    }
+
+    /// Returns the cookies to send to the url given.
+    ///
+    /// When cookies share a name, the one with the longest path is used.
+    pub fn cookies_for_url(&self, url: &Url, now: SystemTime) -> CookieJar {
+        let mut matching = self
+            .cookies
+            .iter()
+            .filter(|stored| stored.is_match(url, now))
+            .collect::<Vec<_>>();
+
+        // Later cookies replace earlier ones in the jar.
+        matching.sort_by_key(|stored| stored.path.len());
+
+        let mut jar = CookieJar::new();
+        for stored in matching {
+            jar.add(stored.cookie.clone());
+        }
+
+        jar
+    }
+
+    /// Replaces any matching cookie with the one given.
+    /// If it has already expired, then it is just removed.
+    fn store(&mut self, stored: StoredCookie, now: SystemTime) {
+        self.cookies
+            .retain(|existing| !existing.is_same_cookie(&stored));
+
+        if !stored.is_expired(now) {
+            self.cookies.push(stored);
+        }
+    }
+}
+
+/// `Max-Age` takes priority over `Expires`.
+/// A `Max-Age` of zero or less expires the cookie immediately,
+/// and one too large to represent never expires.
+fn expires_at(cookie: &Cookie<'_>, now: SystemTime) -> Option<SystemTime> {
+    if let Some(max_age) = cookie.max_age() {
+        let seconds = max_age.whole_seconds();
+        if seconds <= 0 {
+            return Some(SystemTime::UNIX_EPOCH);
+        }
+
+        return now.checked_add(Duration::from_secs(seconds as u64));
+    }
+
+    cookie.expires_datetime().map(SystemTime::from)
+}
+
+fn url_host(url: &Url) -> String {
+    url.host_str().unwrap_or_default().to_ascii_lowercase()
+}
+
+fn is_secure_url(url: &Url) -> bool {
+    matches!(url.scheme(), "https" | "wss")
+}
+
+/// A host matches a domain if it is the domain, or a subdomain of it.
+/// IP addresses can only match themselves.
+fn is_domain_match(host: &str, domain: &str) -> bool {
+    if host == domain {
+        return true;
+    }
+
+    let is_ip_address = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .is_ok();
+
+    !is_ip_address
+        && host.len() > domain.len()
+        && host.ends_with(domain)
+        && host[..host.len() - domain.len()].ends_with('.')
+}
+
+fn is_path_match(request_path: &str, cookie_path: &str) -> bool {
+    if request_path == cookie_path {
+        return true;
+    }
+
+    request_path.starts_with(cookie_path)
+        && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/'))
+}
+
+/// The directory of the request path, used when a cookie has no `Path`.
+fn default_path(request_path: &str) -> String {
+    match request_path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(last_slash) => request_path[..last_slash].to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test_add_from_response {
+    use super::*;
+
+    fn request_url() -> Url {
+        "http://example.com/users/123".parse().unwrap()
+    }
+
+    fn cookie_names(store: &CookieStore, url: &str, now: SystemTime) -> Vec<String> {
+        let mut names = store
+            .cookies_for_url(&url.parse().unwrap(), now)
+            .iter()
+            .map(|cookie| cookie.name().to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn it_should_remove_cookies_deleted_with_max_age_zero() {
+        let now = SystemTime::now();
+        let mut store = CookieStore::new();
+
+        store.add_from_response(
+            Cookie::parse("session=abc; Path=/").unwrap(),
+            &request_url(),
+            now,
+        );
+        store.add_from_response(
+            Cookie::parse("session=; Path=/; Max-Age=0").unwrap(),
+            &request_url(),
+            now,
+        );
+
+        assert_eq!(store.cookies.len(), 0);
+    }
+
+    #[test]
+    fn it_should_remove_cookies_deleted_with_past_expires() {
+        let now = SystemTime::now();
+        let mut store = CookieStore::new();
+
+        store.add_from_response(
+            Cookie::parse("session=abc; Path=/").unwrap(),
+            &request_url(),
+            now,
+        );
+        store.add_from_response(
+            Cookie::parse("session=; Path=/; Expires=Thu, 01 Jan 1970 00:00:00 GMT").unwrap(),
+            &request_url(),
+            now,
+        );
+
+        assert_eq!(store.cookies.len(), 0);
+    }
+
+    #[test]
+    fn it_should_remove_cookies_added_directly_when_deleted() {
+        let now = SystemTime::now();
+        let mut store = CookieStore::new();
+
+        store.add(Cookie::new("session", "abc"), now);
+        store.add_from_response(
+            Cookie::parse("session=; Path=/; Max-Age=0").unwrap(),
+            &request_url(),
+            now,
+        );
+
+        assert_eq!(store.cookies.len(), 0);
+    }
+
+    #[test]
+    fn it_should_use_directory_of_request_as_default_path() {
+        let now = SystemTime::now();
+        let mut store = CookieStore::new();
+
+        store.add_from_response(Cookie::parse("user=abc").unwrap(), &request_url(), now);
+
+        assert_eq!(
+            cookie_names(&store, "http://example.com/users/456", now),
+            vec!["user"]
+        );
+        assert_eq!(
+            cookie_names(&store, "http://example.com/users", now),
+            vec!["user"]
+        );
+        assert_eq!(
+            cookie_names(&store, "http://example.com/posts", now),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn it_should_ignore_cookies_for_other_domains() {
+        let now = SystemTime::now();
+        let mut store = CookieStore::new();
+
+        store.add_from_response(
+            Cookie::parse("tracking=abc; Domain=other.com").unwrap(),
+            &request_url(),
+            now,
+        );
+
+        assert_eq!(store.cookies.len(), 0);
+    }
+}
+
+#[cfg(test)]
+mod test_cookies_for_url {
+    use super::*;
+
+    fn cookie_values(store: &CookieStore, url: &str, now: SystemTime) -> Vec<String> {
+        let mut values = store
+            .cookies_for_url(&url.parse().unwrap(), now)
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name(), cookie.value()))
+            .collect::<Vec<_>>();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn it_should_only_send_host_only_cookies_to_the_same_host() {
+        let now = SystemTime::now();
+        let mut store = CookieStore::new();
+        let request_url = "http://example.com/".parse().unwrap();
+
+        store.add_from_response(Cookie::parse("a=1").unwrap(), &request_url, now);
+
+        assert_eq!(
+            cookie_values(&store, "http://example.com/", now),
+            vec!["a=1"]
+        );
+        assert_eq!(
+            cookie_values(&store, "http://api.example.com/", now),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn it_should_send_domain_cookies_to_subdomains() {
+        let now = SystemTime::now();
+        let mut store = CookieStore::new();
+        let request_url = "http://api.example.com/".parse().unwrap();
+
+        store.add_from_response(
+            Cookie::parse("a=1; Domain=.example.com").unwrap(),
+            &request_url,
+            now,
+        );
+
+        assert_eq!(
+            cookie_values(&store, "http://example.com/", now),
+            vec!["a=1"]
+        );
+        assert_eq!(
+            cookie_values(&store, "http://www.example.com/", now),
+            vec!["a=1"]
+        );
+        assert_eq!(
+            cookie_values(&store, "http://notexample.com/", now),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn it_should_match_paths_on_segment_boundaries() {
+        let now = SystemTime::now();
+        let mut store = CookieStore::new();
+
+        store.add(Cookie::parse("a=1; Path=/admin").unwrap(), now);
+
+        assert_eq!(
+            cookie_values(&store, "http://localhost/admin", now),
+            vec!["a=1"]
+        );
+        assert_eq!(
+            cookie_values(&store, "http://localhost/admin/users", now),
+            vec!["a=1"]
+        );
+        assert_eq!(
+            cookie_values(&store, "http://localhost/administrator", now),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn it_should_prefer_cookies_with_longer_paths() {
+        let now = SystemTime::now();
+        let mut store = CookieStore::new();
+
+        store.add(Cookie::parse("theme=dark; Path=/admin").unwrap(), now);
+        store.add(Cookie::parse("theme=light; Path=/").unwrap(), now);
+
+        assert_eq!(
+            cookie_values(&store, "http://localhost/admin", now),
+            vec!["theme=dark"]
+        );
+        assert_eq!(
+            cookie_values(&store, "http://localhost/", now),
+            vec!["theme=light"]
+        );
+    }
+
+    #[test]
+    fn it_should_withhold_secure_cookies_over_http() {
+        let now = SystemTime::now();
+        let mut store = CookieStore::new();
+
+        store.add(Cookie::parse("a=1; Secure").unwrap(), now);
+
+        assert_eq!(
+            cookie_values(&store, "http://localhost/", now),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            cookie_values(&store, "https://localhost/", now),
+            vec!["a=1"]
+        );
+    }
+
+    #[test]
+    fn it_should_not_send_cookies_after_max_age() {
+        let now = SystemTime::now();
+        let mut store = CookieStore::new();
+
+        store.add(Cookie::parse("a=1; Max-Age=60").unwrap(), now);
+
+        assert_eq!(
+            cookie_values(&store, "http://localhost/", now + Duration::from_secs(59)),
+            vec!["a=1"]
+        );
+        assert_eq!(
+            cookie_values(&store, "http://localhost/", now + Duration::from_secs(60)),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn it_should_never_expire_cookies_with_max_age_too_large_to_represent() {
+        let now = SystemTime::now();
+        let mut store = CookieStore::new();
+
+        store.add(
+            Cookie::parse("a=1; Max-Age=99999999999999999999").unwrap(),
+            now,
+        );
+
+        assert_eq!(
+            cookie_values(
+                &store,
+                "http://localhost/",
+                now + Duration::from_secs(100 * 365 * 24 * 60 * 60)
+            ),
+            vec!["a=1"]
+        );
+    }
+}
@@ -0,0 +1,463 @@
+use ::anyhow::Error as AnyhowError;
+use ::base64::engine::general_purpose::STANDARD as BASE64;
+use ::base64::Engine;
+use ::bytes::Bytes;
+use ::cookie::Cookie;
+use ::http::header;
+use ::http::response::Parts;
+use ::http::HeaderMap;
+use ::http::Method;
+use ::http::Version;
+use ::serde::Deserialize;
+use ::serde::Serialize;
+use ::std::time::Duration;
+use ::std::time::SystemTime;
+use ::std::time::UNIX_EPOCH;
+use ::url::Url;
+
+pub const HAR_VERSION: &str = "1.2";
+
+///
+/// The types here follow the HAR 1.2 format,
+/// as described at <http://www.softwareishard.com/blog/har-12-spec/>.
+///
+/// Only the fields used by the `TestServer` are included.
+/// Unknown fields are ignored when reading a HAR file.
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarLog {
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub creator: HarCreator,
+    #[serde(default)]
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    #[serde(default)]
+    pub started_date_time: String,
+    #[serde(default)]
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    #[serde(default)]
+    pub cache: HarCache,
+    #[serde(default)]
+    pub timings: HarTimings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<HarCookie>,
+    #[serde(default)]
+    pub headers: Vec<HarNameValue>,
+    #[serde(default)]
+    pub query_string: Vec<HarNameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    #[serde(default)]
+    pub headers_size: i64,
+    #[serde(default)]
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub status_text: String,
+    #[serde(default)]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<HarCookie>,
+    #[serde(default)]
+    pub headers: Vec<HarNameValue>,
+    pub content: HarContent,
+    #[serde(rename = "redirectURL", default)]
+    pub redirect_url: String,
+    #[serde(default)]
+    pub headers_size: i64,
+    #[serde(default)]
+    pub body_size: i64,
+
+    /// Why no response was received, such as a timeout.
+    /// This follows the custom `_error` field used by browsers.
+    #[serde(rename = "_error", default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarCookie {
+    pub name: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_only: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secure: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarNameValue {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    #[serde(default)]
+    pub size: i64,
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HarCache {}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HarTimings {
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+}
+
+impl Har {
+    pub fn new(entries: Vec<HarEntry>) -> Self {
+        Self {
+            log: HarLog {
+                version: HAR_VERSION.to_string(),
+                creator: HarCreator {
+                    name: env!("CARGO_PKG_NAME").to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries,
+            },
+        }
+    }
+}
+
+impl HarEntry {
+    pub fn new(
+        started_at: SystemTime,
+        elapsed: Duration,
+        request: HarRequest,
+        response: HarResponse,
+    ) -> Self {
+        let time = elapsed.as_secs_f64() * 1000.0;
+
+        Self {
+            started_date_time: format_date_time(started_at),
+            time,
+            request,
+            response,
+            cache: HarCache::default(),
+            timings: HarTimings {
+                send: 0.0,
+                wait: time,
+                receive: 0.0,
+            },
+        }
+    }
+}
+
+impl HarRequest {
+    pub fn new(
+        method: &Method,
+        url: &Url,
+        version: Version,
+        headers: &HeaderMap,
+        body: &Bytes,
+    ) -> Self {
+        let cookies = headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|header_value| header_value.to_str().ok())
+            .flat_map(Cookie::split_parse)
+            .filter_map(|maybe_cookie| maybe_cookie.ok())
+            .map(|cookie| HarCookie::from_cookie(&cookie))
+            .collect();
+
+        let query_string = url
+            .query_pairs()
+            .map(|(name, value)| HarNameValue {
+                name: name.into_owned(),
+                value: value.into_owned(),
+            })
+            .collect();
+
+        let post_data = (!body.is_empty()).then(|| HarPostData {
+            mime_type: header_to_string(headers, header::CONTENT_TYPE),
+            text: String::from_utf8_lossy(body).into_owned(),
+        });
+
+        Self {
+            method: method.to_string(),
+            url: url.to_string(),
+            http_version: format!("{version:?}"),
+            cookies,
+            headers: headers_to_har(headers),
+            query_string,
+            post_data,
+            headers_size: -1,
+            body_size: body.len() as i64,
+        }
+    }
+}
+
+impl HarResponse {
+    pub fn new(parts: &Parts, body: &Bytes) -> Self {
+        let cookies = parts
+            .headers
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .filter_map(|header_value| header_value.to_str().ok())
+            .filter_map(|cookie_str| Cookie::parse(cookie_str).ok())
+            .map(|cookie| HarCookie::from_cookie(&cookie))
+            .collect();
+
+        let (text, encoding) = match ::std::str::from_utf8(body) {
+            Ok(text) => (text.to_string(), None),
+            Err(_) => (BASE64.encode(body), Some("base64".to_string())),
+        };
+
+        Self {
+            status: parts.status.as_u16(),
+            status_text: parts
+                .status
+                .canonical_reason()
+                .unwrap_or_default()
+                .to_string(),
+            http_version: format!("{:?}", parts.version),
+            cookies,
+            headers: headers_to_har(&parts.headers),
+            content: HarContent {
+                size: body.len() as i64,
+                mime_type: header_to_string(&parts.headers, header::CONTENT_TYPE),
+                text: Some(text),
+                encoding,
+            },
+            redirect_url: header_to_string(&parts.headers, header::LOCATION),
+            headers_size: -1,
+            body_size: body.len() as i64,
+            error: None,
+        }
+    }
+
+    /// A response for a request which failed, such as by timing out.
+    /// Like browsers, this has a status of `0`.
+    pub fn from_error(error: &AnyhowError) -> Self {
+        Self {
+            status: 0,
+            status_text: String::new(),
+            http_version: String::new(),
+            cookies: vec![],
+            headers: vec![],
+            content: HarContent {
+                size: 0,
+                mime_type: String::new(),
+                text: None,
+                encoding: None,
+            },
+            redirect_url: String::new(),
+            headers_size: -1,
+            body_size: -1,
+            error: Some(format!("{error:#}")),
+        }
+    }
+}
+
+impl HarContent {
+    /// Returns the body, decoding it if it was saved as base64.
+    pub fn body_bytes(&self) -> Bytes {
+        let text = self.text.as_deref().unwrap_or_default();
+
+        match self.encoding.as_deref() {
+            Some("base64") => BASE64
+                .decode(text)
+                .map(Bytes::from)
+                .unwrap_or_else(|_| Bytes::copy_from_slice(text.as_bytes())),
+            _ => Bytes::copy_from_slice(text.as_bytes()),
+        }
+    }
+}
+
+impl HarCookie {
+    fn from_cookie(cookie: &Cookie) -> Self {
+        Self {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            path: cookie.path().map(ToString::to_string),
+            domain: cookie.domain().map(ToString::to_string),
+            http_only: cookie.http_only(),
+            secure: cookie.secure(),
+        }
+    }
+}
+
+fn headers_to_har(headers: &HeaderMap) -> Vec<HarNameValue> {
+    headers
+        .iter()
+        .map(|(name, value)| HarNameValue {
+            name: name.to_string(),
+            value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+        })
+        .collect()
+}
+
+fn header_to_string(headers: &HeaderMap, name: header::HeaderName) -> String {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Formats the time as an ISO 8601 date time in UTC,
+/// i.e. `2023-09-02T14:05:09.123Z`.
+fn format_date_time(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let total_secs = since_epoch.as_secs();
+    let millis = since_epoch.subsec_millis();
+
+    let days = (total_secs / 86_400) as i64;
+    let secs_of_day = total_secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{millis:03}Z",
+        secs_of_day / 3600,
+        (secs_of_day / 60) % 60,
+        secs_of_day % 60,
+    )
+}
+
+/// Converts days since the Unix epoch into a year, month, and day.
+/// This uses Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod test_format_date_time {
+    use super::*;
+
+    #[test]
+    fn it_should_format_unix_epoch() {
+        assert_eq!(format_date_time(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+    }
+
+    #[test]
+    fn it_should_format_date_with_millis() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_693_663_509_123);
+
+        assert_eq!(format_date_time(time), "2023-09-02T14:05:09.123Z");
+    }
+
+    #[test]
+    fn it_should_format_leap_day() {
+        let time = UNIX_EPOCH + Duration::from_secs(951_782_400);
+
+        assert_eq!(format_date_time(time), "2000-02-29T00:00:00.000Z");
+    }
+}
+
+#[cfg(test)]
+mod test_har_content {
+    use super::*;
+
+    #[test]
+    fn it_should_save_binary_bodies_as_base64() {
+        let parts = ::http::Response::new(()).into_parts().0;
+        let body = Bytes::from_static(&[0xff, 0x00, 0xfe]);
+
+        let response = HarResponse::new(&parts, &body);
+
+        assert_eq!(response.content.encoding.as_deref(), Some("base64"));
+        assert_eq!(response.content.text.as_deref(), Some("/wD+"));
+        assert_eq!(response.content.body_bytes(), body);
+    }
+
+    #[test]
+    fn it_should_save_text_bodies_as_text() {
+        let parts = ::http::Response::new(()).into_parts().0;
+        let body = Bytes::from_static(b"hello!");
+
+        let response = HarResponse::new(&parts, &body);
+
+        assert_eq!(response.content.encoding, None);
+        assert_eq!(response.content.text.as_deref(), Some("hello!"));
+    }
+}
+
+#[cfg(test)]
+mod test_har_response_from_error {
+    use super::*;
+    use ::anyhow::anyhow;
+
+    #[test]
+    fn it_should_save_error_with_status_zero() {
+        let error = anyhow!("connection refused").context("Sending request failed");
+
+        let response = HarResponse::from_error(&error);
+
+        assert_eq!(response.status, 0);
+        assert_eq!(
+            response.error.as_deref(),
+            Some("Sending request failed: connection refused")
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::serde_json::to_vec_pretty;
+use ::std::fs::create_dir_all;
+use ::std::fs::write;
+use ::std::path::PathBuf;
+
+use crate::internals::Har;
+use crate::internals::HarEntry;
+
+/// Collects the requests and responses sent through a `TestServer`,
+/// for writing out as a HAR file.
+#[derive(Debug)]
+pub struct HarRecorder {
+    path: PathBuf,
+    entries: Vec<HarEntry>,
+}
+
+impl HarRecorder {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn add_entry(&mut self, entry: HarEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Writes all entries recorded so far to the HAR file,
+    /// replacing it if it already exists.
+    pub fn save(&self) -> Result<()> {
+        let path = &self.path;
+        let har = Har::new(self.entries.clone());
+        let har_bytes = to_vec_pretty(&har).context("Serializing HAR recording")?;
+
+        if let Some(parent_dir) = path.parent() {
+            if !parent_dir.as_os_str().is_empty() {
+                create_dir_all(parent_dir).with_context(|| {
+                    format!("Creating directory for HAR file, {}", parent_dir.display())
+                })?;
+            }
+        }
+
+        write(path, har_bytes).with_context(|| format!("Writing HAR file, to {}", path.display()))
+    }
+}
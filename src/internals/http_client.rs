@@ -0,0 +1,76 @@
+use ::anyhow::Result;
+use ::http::Request;
+use ::http::Response;
+use ::hyper::client::HttpConnector;
+use ::hyper::Body;
+use ::hyper::Client;
+
+#[cfg(feature = "tls")]
+use ::hyper_rustls::HttpsConnector;
+#[cfg(feature = "tls")]
+use ::hyper_rustls::HttpsConnectorBuilder;
+#[cfg(feature = "tls")]
+use ::rustls::ClientConfig;
+#[cfg(unix)]
+use ::std::path::PathBuf;
+
+#[cfg(unix)]
+use crate::internals::UnixConnector;
+
+/// The client used by the `HttpTransportLayer` to talk to the running server.
+///
+/// It is created once and shared,
+/// so requests can reuse pooled connections and run concurrently.
+#[derive(Debug, Clone)]
+pub enum HttpClient {
+    Http(Client<HttpConnector>),
+
+    #[cfg(feature = "tls")]
+    Https(Client<HttpsConnector<HttpConnector>>),
+
+    #[cfg(unix)]
+    Unix(Client<UnixConnector>),
+}
+
+impl HttpClient {
+    pub fn new_http(is_http2_only: bool) -> Self {
+        let client = Client::builder().http2_only(is_http2_only).build_http();
+
+        Self::Http(client)
+    }
+
+    #[cfg(feature = "tls")]
+    pub fn new_https(client_config: ClientConfig) -> Self {
+        let connector = HttpsConnectorBuilder::new()
+            .with_tls_config(client_config)
+            .https_only()
+            .enable_http1()
+            .build();
+        let client = Client::builder().build(connector);
+
+        Self::Https(client)
+    }
+
+    #[cfg(unix)]
+    pub fn new_unix(socket_path: PathBuf, is_http2_only: bool) -> Self {
+        let client = Client::builder()
+            .http2_only(is_http2_only)
+            .build(UnixConnector::new(socket_path));
+
+        Self::Unix(client)
+    }
+
+    pub async fn request(&self, request: Request<Body>) -> Result<Response<Body>> {
+        let response = match self {
+            Self::Http(client) => client.request(request).await?,
+
+            #[cfg(feature = "tls")]
+            Self::Https(client) => client.request(request).await?,
+
+            #[cfg(unix)]
+            Self::Unix(client) => client.request(request).await?,
+        };
+
+        Ok(response)
+    }
+}
@@ -0,0 +1,345 @@
+use ::serde_json::Number;
+use ::serde_json::Value;
+use ::std::fmt::Display;
+use ::std::fmt::Formatter;
+use ::std::fmt::Result as FmtResult;
+
+use crate::internals::JsonPath;
+
+const MISSING_VALUE: &str = "(missing)";
+const NO_MATCHING_ITEM: &str = "(no matching item)";
+
+/// A value in the expected JSON which the received JSON does not contain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonDifference {
+    pub path: JsonPath,
+    pub expected: String,
+    pub received: String,
+}
+
+impl Display for JsonDifference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let path = &self.path;
+        let expected = &self.expected;
+        let received = &self.received;
+
+        write!(f, "{path} expected {expected}, received {received}")
+    }
+}
+
+/// Checks one JSON document contains another.
+///
+/// Objects in the received JSON may have fields which are not expected.
+/// Arrays must have the same length, with each item containing the expected item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JsonContains {
+    pub ignore_array_order: bool,
+    pub float_tolerance: Option<f64>,
+}
+
+impl JsonContains {
+    /// Returns every difference found, keyed by the path to the value.
+    pub fn differences(&self, expected: &Value, received: &Value) -> Vec<JsonDifference> {
+        let mut differences = Vec::new();
+        self.compare(JsonPath::root(), expected, received, &mut differences);
+
+        differences
+    }
+
+    fn is_contained(&self, expected: &Value, received: &Value) -> bool {
+        self.differences(expected, received).is_empty()
+    }
+
+    fn compare(
+        &self,
+        path: JsonPath,
+        expected: &Value,
+        received: &Value,
+        differences: &mut Vec<JsonDifference>,
+    ) {
+        match (expected, received) {
+            (Value::Object(expected_fields), Value::Object(received_fields)) => {
+                for (name, expected_value) in expected_fields {
+                    let field_path = path.join_field(name);
+
+                    match received_fields.get(name) {
+                        Some(received_value) => {
+                            self.compare(field_path, expected_value, received_value, differences)
+                        }
+                        None => differences.push(JsonDifference {
+                            path: field_path,
+                            expected: expected_value.to_string(),
+                            received: MISSING_VALUE.to_string(),
+                        }),
+                    }
+                }
+            }
+            (Value::Array(expected_items), Value::Array(received_items)) => {
+                if expected_items.len() != received_items.len() {
+                    differences.push(JsonDifference {
+                        path,
+                        expected: format!("array of length {}", expected_items.len()),
+                        received: format!("array of length {}", received_items.len()),
+                    });
+                } else if self.ignore_array_order {
+                    self.compare_unordered(path, expected_items, received_items, differences);
+                } else {
+                    for (index, (expected_item, received_item)) in
+                        expected_items.iter().zip(received_items).enumerate()
+                    {
+                        self.compare(
+                            path.join_index(index),
+                            expected_item,
+                            received_item,
+                            differences,
+                        );
+                    }
+                }
+            }
+            (Value::Number(expected_number), Value::Number(received_number)) => {
+                if !self.is_number_equal(expected_number, received_number) {
+                    differences.push(JsonDifference {
+                        path,
+                        expected: expected.to_string(),
+                        received: received.to_string(),
+                    });
+                }
+            }
+            _ => {
+                if expected != received {
+                    differences.push(JsonDifference {
+                        path,
+                        expected: expected.to_string(),
+                        received: received.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Pairs each expected item with a different received item containing it.
+    ///
+    /// An expected item can be contained by many received items,
+    /// so taking the first match could leave later items without one.
+    /// This instead finds the most pairs possible, using augmenting paths.
+    fn compare_unordered(
+        &self,
+        path: JsonPath,
+        expected_items: &[Value],
+        received_items: &[Value],
+        differences: &mut Vec<JsonDifference>,
+    ) {
+        let candidates: Vec<Vec<usize>> = expected_items
+            .iter()
+            .map(|expected_item| {
+                received_items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, received_item)| self.is_contained(expected_item, received_item))
+                    .map(|(received_index, _)| received_index)
+                    .collect()
+            })
+            .collect();
+
+        let mut matched_expected = vec![None; received_items.len()];
+        for (index, expected_item) in expected_items.iter().enumerate() {
+            let mut is_visited = vec![false; received_items.len()];
+            let is_matched =
+                find_augmenting_path(&candidates, index, &mut is_visited, &mut matched_expected);
+
+            if !is_matched {
+                differences.push(JsonDifference {
+                    path: path.join_index(index),
+                    expected: expected_item.to_string(),
+                    received: NO_MATCHING_ITEM.to_string(),
+                });
+            }
+        }
+    }
+
+    fn is_number_equal(&self, expected: &Number, received: &Number) -> bool {
+        if expected == received {
+            return true;
+        }
+
+        match (expected.as_f64(), received.as_f64()) {
+            (Some(expected), Some(received)) => {
+                (expected - received).abs() <= self.float_tolerance.unwrap_or(0.0)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Tries to pair the expected item with a received item,
+/// moving previously paired expected items onto other received items to make room.
+fn find_augmenting_path(
+    candidates: &[Vec<usize>],
+    expected_index: usize,
+    is_visited: &mut [bool],
+    matched_expected: &mut [Option<usize>],
+) -> bool {
+    for &received_index in &candidates[expected_index] {
+        if is_visited[received_index] {
+            continue;
+        }
+        is_visited[received_index] = true;
+
+        let is_available = match matched_expected[received_index] {
+            None => true,
+            Some(other_expected_index) => find_augmenting_path(
+                candidates,
+                other_expected_index,
+                is_visited,
+                matched_expected,
+            ),
+        };
+
+        if is_available {
+            matched_expected[received_index] = Some(expected_index);
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod test_differences {
+    use super::*;
+    use ::serde_json::json;
+
+    const EXACT: JsonContains = JsonContains {
+        ignore_array_order: false,
+        float_tolerance: None,
+    };
+
+    fn format_differences(differences: Vec<JsonDifference>) -> Vec<String> {
+        differences
+            .into_iter()
+            .map(|difference| difference.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn it_should_allow_extra_fields() {
+        let differences = EXACT.differences(
+            &json!({ "name": "Joe" }),
+            &json!({ "id": 1, "name": "Joe", "user": { "age": 20 } }),
+        );
+
+        assert_eq!(differences, vec![]);
+    }
+
+    #[test]
+    fn it_should_report_missing_and_different_fields_by_path() {
+        let differences = EXACT.differences(
+            &json!({ "name": "Joe", "user": { "age": 20, "email": "joe@example.com" } }),
+            &json!({ "name": "Kate", "user": { "age": 20 } }),
+        );
+
+        assert_eq!(
+            format_differences(differences),
+            vec![
+                r#"$.name expected "Joe", received "Kate""#,
+                r#"$.user.email expected "joe@example.com", received (missing)"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_compare_array_items_in_order() {
+        let differences = EXACT.differences(
+            &json!({ "items": [{ "id": 1 }, { "id": 2 }] }),
+            &json!({ "items": [{ "id": 2, "a": 1 }, { "id": 1 }] }),
+        );
+
+        assert_eq!(
+            format_differences(differences),
+            vec![
+                "$.items[0].id expected 1, received 2",
+                "$.items[1].id expected 2, received 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_report_arrays_of_different_length() {
+        let differences = EXACT.differences(&json!([1, 2]), &json!([1, 2, 3]));
+
+        assert_eq!(
+            format_differences(differences),
+            vec!["$ expected array of length 2, received array of length 3"]
+        );
+    }
+
+    #[test]
+    fn it_should_match_array_items_in_any_order_when_ignoring_order() {
+        let contains = JsonContains {
+            ignore_array_order: true,
+            float_tolerance: None,
+        };
+
+        let differences = contains.differences(
+            &json!({ "items": [{ "id": 1 }, { "id": 2 }] }),
+            &json!({ "items": [{ "id": 2, "a": 1 }, { "id": 1 }] }),
+        );
+        assert_eq!(differences, vec![]);
+
+        let differences = contains.differences(&json!([1, 1]), &json!([1, 2]));
+        assert_eq!(
+            format_differences(differences),
+            vec!["$[1] expected 1, received (no matching item)"]
+        );
+    }
+
+    #[test]
+    fn it_should_find_matches_when_first_match_is_needed_by_a_later_item() {
+        let contains = JsonContains {
+            ignore_array_order: true,
+            float_tolerance: None,
+        };
+
+        let differences = contains.differences(
+            &json!([{ "a": 1 }, { "a": 1, "b": 2 }]),
+            &json!([{ "a": 1, "b": 2 }, { "a": 1 }]),
+        );
+
+        assert_eq!(differences, vec![]);
+    }
+
+    #[test]
+    fn it_should_compare_floats_within_tolerance() {
+        let contains = JsonContains {
+            ignore_array_order: false,
+            float_tolerance: Some(0.01),
+        };
+
+        let differences = contains.differences(
+            &json!({ "a": 1.0, "b": 0.5, "c": 2 }),
+            &json!({ "a": 1.005, "b": 0.6, "c": 2 }),
+        );
+
+        assert_eq!(
+            format_differences(differences),
+            vec!["$.b expected 0.5, received 0.6"]
+        );
+    }
+
+    #[test]
+    fn it_should_treat_integers_and_equal_floats_as_equal() {
+        let differences = EXACT.differences(&json!({ "a": 1 }), &json!({ "a": 1.0 }));
+
+        assert_eq!(differences, vec![]);
+    }
+
+    #[test]
+    fn it_should_report_different_types() {
+        let differences = EXACT.differences(&json!({ "a": { "b": 1 } }), &json!({ "a": "b" }));
+
+        assert_eq!(
+            format_differences(differences),
+            vec![r#"$.a expected {"b":1}, received "b""#]
+        );
+    }
+}
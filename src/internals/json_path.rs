@@ -0,0 +1,541 @@
+use ::anyhow::anyhow;
+use ::anyhow::Result;
+use ::serde_json::Value;
+use ::std::fmt::Display;
+use ::std::fmt::Formatter;
+use ::std::fmt::Result as FmtResult;
+
+/// A parsed JSONPath query, such as `$.items[0].id`.
+///
+/// This supports the common subset of JSONPath:
+///
+///  - `$` for the root of the document.
+///  - `.name`, `['name']`, and `["name"]` for fields.
+///  - `[0]` for array items, and `[-1]` for counting back from the end.
+///  - `.*` and `[*]` for every field or item.
+///  - `..name` and `..*` for searching through all descendants.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<JsonPathSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum JsonPathSegment {
+    Field(String),
+    Index(i64),
+    Wildcard,
+    Descendants,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self> {
+        let mut chars = path.trim().chars().peekable();
+        if chars.next() != Some('$') {
+            return Err(anyhow!("JSON path must start with '$'"));
+        }
+
+        let mut segments = Vec::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => {
+                    if chars.peek() == Some(&'.') {
+                        chars.next();
+                        segments.push(JsonPathSegment::Descendants);
+                    }
+
+                    if chars.peek() == Some(&'*') {
+                        chars.next();
+                        segments.push(JsonPathSegment::Wildcard);
+                        continue;
+                    }
+
+                    let mut name = String::new();
+                    while let Some(&next) = chars.peek() {
+                        if next == '.' || next == '[' {
+                            break;
+                        }
+                        name.push(next);
+                        chars.next();
+                    }
+
+                    if name.is_empty() {
+                        return Err(anyhow!("Expected field name after '.'"));
+                    }
+                    segments.push(JsonPathSegment::Field(name));
+                }
+                '[' => {
+                    let mut contents = String::new();
+                    let mut maybe_quote = None;
+                    loop {
+                        let next = chars.next().ok_or_else(|| anyhow!("Missing closing ']'"))?;
+
+                        match maybe_quote {
+                            Some(quote) if next == quote => maybe_quote = None,
+                            Some(_) => {}
+                            None if next == '\'' || next == '"' => maybe_quote = Some(next),
+                            None if next == ']' => break,
+                            None => {}
+                        }
+                        contents.push(next);
+                    }
+
+                    segments.push(parse_bracket(contents.trim())?);
+                }
+                _ => return Err(anyhow!("Unexpected character '{c}'")),
+            }
+        }
+
+        if segments.last() == Some(&JsonPathSegment::Descendants) {
+            return Err(anyhow!("Expected field name after '..'"));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// A path pointing to the root of a document, `$`.
+    pub fn root() -> Self {
+        Self { segments: vec![] }
+    }
+
+    /// Returns a new path to the field given, within the value at this path.
+    pub fn join_field(&self, name: &str) -> Self {
+        self.join(JsonPathSegment::Field(name.to_string()))
+    }
+
+    /// Returns a new path to the array item given, within the value at this path.
+    pub fn join_index(&self, index: usize) -> Self {
+        self.join(JsonPathSegment::Index(index as i64))
+    }
+
+    fn join(&self, segment: JsonPathSegment) -> Self {
+        let mut segments = self.segments.clone();
+        segments.push(segment);
+
+        Self { segments }
+    }
+
+    /// Returns true if this path can only ever match a single value.
+    /// i.e. It has no wildcards and no descendant searches.
+    pub fn is_definite(&self) -> bool {
+        self.segments.iter().all(|segment| {
+            matches!(
+                segment,
+                JsonPathSegment::Field(_) | JsonPathSegment::Index(_)
+            )
+        })
+    }
+
+    /// Returns all of the values matched, in document order.
+    pub fn query<'a>(&self, document: &'a Value) -> Vec<&'a Value> {
+        query_segments(vec![document], &self.segments)
+    }
+
+    /// Finds the value this path matches.
+    ///
+    /// Definite paths return the value found, if it exists.
+    /// All other paths return an array of every value matched.
+    pub fn find(&self, document: &Value) -> Option<Value> {
+        let matches = self.query(document);
+
+        if self.is_definite() {
+            matches.into_iter().next().cloned()
+        } else {
+            Some(Value::Array(matches.into_iter().cloned().collect()))
+        }
+    }
+
+    /// Removes every value this path matches from the document.
+    ///
+    /// Fields are removed from their objects,
+    /// and array items are replaced with `null` so the array keeps its length.
+    /// Removing the root replaces the whole document with `null`.
+    pub fn remove_from(&self, document: &mut Value) {
+        if self.segments.is_empty() {
+            *document = Value::Null;
+            return;
+        }
+
+        remove_segments(document, &self.segments);
+    }
+
+    /// Returns the closest document above what this path points to,
+    /// along with the path to reach it.
+    ///
+    /// This is for adding context when a path is not found, or does not match.
+    pub fn find_surrounding(&self, document: &Value) -> (JsonPath, Value) {
+        for len in (0..self.segments.len()).rev() {
+            let parent_path = JsonPath {
+                segments: self.segments[..len].to_vec(),
+            };
+
+            if parent_path.segments.last() == Some(&JsonPathSegment::Descendants) {
+                continue;
+            }
+
+            if let Some(parent) = parent_path.find(document) {
+                return (parent_path, parent);
+            }
+        }
+
+        (JsonPath { segments: vec![] }, document.clone())
+    }
+}
+
+impl Display for JsonPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "$")?;
+
+        let mut is_after_descendants = false;
+        for segment in &self.segments {
+            let dot = if is_after_descendants { "" } else { "." };
+            is_after_descendants = false;
+
+            match segment {
+                JsonPathSegment::Field(name) => {
+                    if name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                    {
+                        write!(f, "{dot}{name}")?;
+                    } else {
+                        let escaped = name.replace('\'', "\\'");
+                        write!(f, "['{escaped}']")?;
+                    }
+                }
+                JsonPathSegment::Index(index) => write!(f, "[{index}]")?,
+                JsonPathSegment::Wildcard => write!(f, "{dot}*")?,
+                JsonPathSegment::Descendants => {
+                    is_after_descendants = true;
+                    write!(f, "..")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_bracket(contents: &str) -> Result<JsonPathSegment> {
+    if contents == "*" {
+        return Ok(JsonPathSegment::Wildcard);
+    }
+
+    for quote in ['\'', '"'] {
+        if contents.len() >= 2 && contents.starts_with(quote) && contents.ends_with(quote) {
+            let name =
+                contents[1..contents.len() - 1].replace(&format!("\\{quote}"), &quote.to_string());
+            return Ok(JsonPathSegment::Field(name));
+        }
+    }
+
+    contents
+        .parse::<i64>()
+        .map(JsonPathSegment::Index)
+        .map_err(|_| anyhow!("Expected index, quoted field name, or '*', received '[{contents}]'"))
+}
+
+fn query_segments<'a>(values: Vec<&'a Value>, segments: &[JsonPathSegment]) -> Vec<&'a Value> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return values;
+    };
+
+    let next_values = values
+        .into_iter()
+        .flat_map(|value| match segment {
+            JsonPathSegment::Field(name) => value.get(name).into_iter().collect(),
+            JsonPathSegment::Index(index) => find_index(value, *index).into_iter().collect(),
+            JsonPathSegment::Wildcard => children(value),
+            JsonPathSegment::Descendants => {
+                let mut descendants = Vec::new();
+                push_self_and_descendants(value, &mut descendants);
+                descendants
+            }
+        })
+        .collect();
+
+    query_segments(next_values, rest)
+}
+
+fn remove_segments(value: &mut Value, segments: &[JsonPathSegment]) {
+    let Some((segment, rest)) = segments.split_first() else {
+        return;
+    };
+
+    if rest.is_empty() {
+        match (segment, value) {
+            (JsonPathSegment::Field(name), Value::Object(fields)) => {
+                fields.remove(name);
+            }
+            (JsonPathSegment::Index(index), value) => {
+                if let Some(item) = find_index_mut(value, *index) {
+                    *item = Value::Null;
+                }
+            }
+            (JsonPathSegment::Wildcard, Value::Object(fields)) => fields.clear(),
+            (JsonPathSegment::Wildcard, Value::Array(items)) => {
+                for item in items.iter_mut() {
+                    *item = Value::Null;
+                }
+            }
+            _ => {}
+        }
+
+        return;
+    }
+
+    match segment {
+        JsonPathSegment::Field(name) => {
+            if let Some(child) = value.get_mut(name) {
+                remove_segments(child, rest);
+            }
+        }
+        JsonPathSegment::Index(index) => {
+            if let Some(child) = find_index_mut(value, *index) {
+                remove_segments(child, rest);
+            }
+        }
+        JsonPathSegment::Wildcard => {
+            for child in children_mut(value) {
+                remove_segments(child, rest);
+            }
+        }
+        JsonPathSegment::Descendants => {
+            remove_segments(value, rest);
+            for child in children_mut(value) {
+                remove_segments(child, segments);
+            }
+        }
+    }
+}
+
+fn find_index_mut(value: &mut Value, index: i64) -> Option<&mut Value> {
+    let items = value.as_array_mut()?;
+    let index = if index < 0 {
+        items.len().checked_sub(index.unsigned_abs() as usize)?
+    } else {
+        index as usize
+    };
+
+    items.get_mut(index)
+}
+
+fn children_mut(value: &mut Value) -> Vec<&mut Value> {
+    match value {
+        Value::Array(items) => items.iter_mut().collect(),
+        Value::Object(fields) => fields.values_mut().collect(),
+        _ => vec![],
+    }
+}
+
+fn find_index(value: &Value, index: i64) -> Option<&Value> {
+    let items = value.as_array()?;
+    let index = if index < 0 {
+        items.len().checked_sub(index.unsigned_abs() as usize)?
+    } else {
+        index as usize
+    };
+
+    items.get(index)
+}
+
+fn children(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(fields) => fields.values().collect(),
+        _ => vec![],
+    }
+}
+
+fn push_self_and_descendants<'a>(value: &'a Value, output: &mut Vec<&'a Value>) {
+    output.push(value);
+    for child in children(value) {
+        push_self_and_descendants(child, output);
+    }
+}
+
+#[cfg(test)]
+mod test_parse {
+    use super::*;
+
+    #[test]
+    fn it_should_parse_root() {
+        let path = JsonPath::parse(&"$").unwrap();
+
+        assert_eq!(path.segments, vec![]);
+    }
+
+    #[test]
+    fn it_should_parse_fields_indexes_and_wildcards() {
+        let path = JsonPath::parse(&"$.items[0]['first name'][*].*..id").unwrap();
+
+        assert_eq!(
+            path.segments,
+            vec![
+                JsonPathSegment::Field("items".to_string()),
+                JsonPathSegment::Index(0),
+                JsonPathSegment::Field("first name".to_string()),
+                JsonPathSegment::Wildcard,
+                JsonPathSegment::Wildcard,
+                JsonPathSegment::Descendants,
+                JsonPathSegment::Field("id".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_parse_brackets_containing_closing_bracket() {
+        let path = JsonPath::parse(&r#"$["a]b"]"#).unwrap();
+
+        assert_eq!(
+            path.segments,
+            vec![JsonPathSegment::Field("a]b".to_string())]
+        );
+    }
+
+    #[test]
+    fn it_should_error_without_root() {
+        let result = JsonPath::parse(&"items[0]");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_should_error_on_unclosed_bracket() {
+        let result = JsonPath::parse(&"$.items[0");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_should_error_on_trailing_dot() {
+        let result = JsonPath::parse(&"$.items.");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_should_display_as_normalized_path() {
+        let path = JsonPath::parse(&"$['items'][-1][\"first name\"]..*").unwrap();
+
+        assert_eq!(path.to_string(), "$.items[-1]['first name']..*");
+    }
+}
+
+#[cfg(test)]
+mod test_find {
+    use super::*;
+    use ::serde_json::json;
+
+    fn document() -> Value {
+        json!({
+            "items": [
+                { "id": 1, "tags": ["a", "b"] },
+                { "id": 2, "tags": [] },
+                { "id": 3, "child": { "id": 4 } },
+            ],
+            "total": 3,
+        })
+    }
+
+    fn find(path: &str) -> Option<Value> {
+        JsonPath::parse(path).unwrap().find(&document())
+    }
+
+    #[test]
+    fn it_should_find_root() {
+        assert_eq!(find(&"$"), Some(document()));
+    }
+
+    #[test]
+    fn it_should_find_nested_field() {
+        assert_eq!(find(&"$.items[0].id"), Some(json!(1)));
+    }
+
+    #[test]
+    fn it_should_find_from_end_with_negative_index() {
+        assert_eq!(find(&"$.items[-1].child.id"), Some(json!(4)));
+    }
+
+    #[test]
+    fn it_should_return_none_for_missing_values() {
+        assert_eq!(find(&"$.items[5].id"), None);
+        assert_eq!(find(&"$.items[-5]"), None);
+        assert_eq!(find(&"$.total.id"), None);
+    }
+
+    #[test]
+    fn it_should_return_array_for_wildcards() {
+        assert_eq!(find(&"$.items[*].id"), Some(json!([1, 2, 3])));
+    }
+
+    #[test]
+    fn it_should_return_array_for_descendants() {
+        assert_eq!(find(&"$..id"), Some(json!([1, 2, 3, 4])));
+    }
+
+    #[test]
+    fn it_should_return_empty_array_when_wildcard_matches_nothing() {
+        assert_eq!(find(&"$.items[1].tags[*]"), Some(json!([])));
+    }
+
+    #[test]
+    fn it_should_find_closest_surrounding_document() {
+        let path = JsonPath::parse(&"$.items[1].tags[0]").unwrap();
+        let (surrounding_path, surrounding) = path.find_surrounding(&document());
+
+        assert_eq!(surrounding_path.to_string(), "$.items[1].tags");
+        assert_eq!(surrounding, json!([]));
+    }
+}
+
+#[cfg(test)]
+mod test_remove_from {
+    use super::*;
+    use ::serde_json::json;
+
+    fn remove(path: &str, mut document: Value) -> Value {
+        JsonPath::parse(path).unwrap().remove_from(&mut document);
+        document
+    }
+
+    #[test]
+    fn it_should_remove_field() {
+        let document = remove(&"$.user.id", json!({ "user": { "id": 1, "name": "Joe" } }));
+
+        assert_eq!(document, json!({ "user": { "name": "Joe" } }));
+    }
+
+    #[test]
+    fn it_should_replace_array_items_with_null() {
+        let document = remove(&"$.items[-1]", json!({ "items": [1, 2, 3] }));
+
+        assert_eq!(document, json!({ "items": [1, 2, null] }));
+    }
+
+    #[test]
+    fn it_should_remove_fields_matching_wildcards() {
+        let document = remove(
+            &"$.items[*].id",
+            json!({ "items": [{ "id": 1, "a": true }, { "id": 2 }] }),
+        );
+
+        assert_eq!(document, json!({ "items": [{ "a": true }, {}] }));
+    }
+
+    #[test]
+    fn it_should_remove_fields_from_all_descendants() {
+        let document = remove(
+            &"$..id",
+            json!({ "id": 1, "child": { "id": 2, "items": [{ "id": 3 }] } }),
+        );
+
+        assert_eq!(document, json!({ "child": { "items": [{}] } }));
+    }
+
+    #[test]
+    fn it_should_do_nothing_when_path_is_missing() {
+        let document = remove(&"$.user.id", json!({ "items": [] }));
+
+        assert_eq!(document, json!({ "items": [] }));
+    }
+}
@@ -0,0 +1,126 @@
+use ::std::future::Future;
+use ::std::io::Result as IoResult;
+use ::std::io::Write;
+use ::std::sync::Arc;
+use ::std::sync::Mutex;
+use ::tracing::dispatcher::get_default;
+use ::tracing::instrument::WithDispatch;
+use ::tracing::instrument::WithSubscriber;
+use ::tracing::Dispatch;
+use ::tracing::Level;
+use ::tracing_subscriber::fmt::MakeWriter;
+
+/// Collects the tracing events emitted whilst handling a single request,
+/// formatted as text with one event per line.
+#[derive(Debug, Clone, Default)]
+pub struct LogCapture {
+    buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl LogCapture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs the future with events sent to this capture,
+    /// instead of to the current subscriber.
+    ///
+    /// When there is no capture, the future runs with the current subscriber as normal.
+    pub fn capture_future<F>(maybe_capture: Option<&Self>, future: F) -> WithDispatch<F>
+    where
+        F: Future,
+    {
+        let dispatch = match maybe_capture {
+            Some(capture) => capture.dispatch(),
+            None => get_default(Dispatch::clone),
+        };
+
+        future.with_subscriber(dispatch)
+    }
+
+    fn dispatch(&self) -> Dispatch {
+        let subscriber = ::tracing_subscriber::fmt()
+            .with_writer(self.clone())
+            .with_max_level(Level::TRACE)
+            .with_ansi(false)
+            .without_time()
+            .finish();
+
+        Dispatch::new(subscriber)
+    }
+
+    /// Returns everything captured so far.
+    pub fn logs(&self) -> String {
+        let buffer = match self.buffer.lock() {
+            Ok(buffer) => buffer,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        String::from_utf8_lossy(&buffer).to_string()
+    }
+}
+
+impl Write for LogCapture {
+    fn write(&mut self, bytes: &[u8]) -> IoResult<usize> {
+        let mut buffer = match self.buffer.lock() {
+            Ok(buffer) => buffer,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        buffer.extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogCapture {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod test_capture_future {
+    use super::*;
+    use ::tracing::info;
+    use ::tracing::warn;
+
+    #[tokio::test]
+    async fn it_should_capture_events_within_the_future() {
+        let capture = LogCapture::new();
+
+        LogCapture::capture_future(Some(&capture), async {
+            info!(user_id = 5, "loading user");
+            warn!("user not found");
+        })
+        .await;
+
+        assert_eq!(
+            capture.logs(),
+            " INFO axum_test::internals::log_capture::test_capture_future: loading user user_id=5\n WARN axum_test::internals::log_capture::test_capture_future: user not found\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_not_capture_events_outside_the_future() {
+        let capture = LogCapture::new();
+
+        info!("before");
+        LogCapture::capture_future(Some(&capture), async {}).await;
+        info!("after");
+
+        assert_eq!(capture.logs(), "");
+    }
+
+    #[tokio::test]
+    async fn it_should_run_future_without_capture() {
+        let result = LogCapture::capture_future(None, async { 123 }).await;
+
+        assert_eq!(result, 123);
+    }
+}
@@ -16,5 +16,71 @@ pub use self::query_params_store::*;
 mod starting_tcp_setup;
 pub use self::starting_tcp_setup::*;
 
+mod http_client;
+pub use self::http_client::*;
+
+mod server_task;
+pub use self::server_task::*;
+
+mod sse_parser;
+pub use self::sse_parser::*;
+
+mod har;
+pub use self::har::*;
+
+mod har_recorder;
+pub use self::har_recorder::*;
+
+mod snapshot;
+pub use self::snapshot::*;
+
+mod json_path;
+pub use self::json_path::*;
+
+mod json_contains;
+pub use self::json_contains::*;
+
+#[cfg(feature = "json-schema")]
+mod route_pattern;
+#[cfg(feature = "json-schema")]
+pub use self::route_pattern::*;
+
+#[cfg(feature = "openapi")]
+mod openapi_contract;
+#[cfg(feature = "openapi")]
+pub use self::openapi_contract::*;
+
+#[cfg(feature = "tracing")]
+mod log_capture;
+#[cfg(feature = "tracing")]
+pub use self::log_capture::*;
+
+#[cfg(feature = "tls")]
+mod tls_setup;
+#[cfg(feature = "tls")]
+pub use self::tls_setup::*;
+
+#[cfg(feature = "tls")]
+mod tls_incoming;
+#[cfg(feature = "tls")]
+pub use self::tls_incoming::*;
+
+#[cfg(unix)]
+mod unix_socket;
+#[cfg(unix)]
+pub use self::unix_socket::*;
+
+mod auth_challenge_parser;
+pub use self::auth_challenge_parser::*;
+
+mod authorization_header;
+pub use self::authorization_header::*;
+
+mod redirect;
+pub use self::redirect::*;
+
+mod cookie_store;
+pub use self::cookie_store::*;
+
 mod with_this_mut;
 pub use self::with_this_mut::*;
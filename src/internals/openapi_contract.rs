@@ -0,0 +1,936 @@
+use ::anyhow::anyhow;
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::bytes::Bytes;
+use ::http::header::CONTENT_TYPE;
+use ::http::HeaderMap;
+use ::http::HeaderValue;
+use ::http::Method;
+use ::http::StatusCode;
+use ::serde_json::Map;
+use ::serde_json::Value;
+use ::std::fs::read;
+use ::std::path::Path;
+use ::std::sync::Mutex;
+use ::url::Url;
+
+use crate::internals::RoutePattern;
+use crate::internals::StatusCodeFormatter;
+use crate::JsonSchema;
+
+const OPERATION_METHODS: [(&str, Method); 8] = [
+    ("get", Method::GET),
+    ("put", Method::PUT),
+    ("post", Method::POST),
+    ("delete", Method::DELETE),
+    ("options", Method::OPTIONS),
+    ("head", Method::HEAD),
+    ("patch", Method::PATCH),
+    ("trace", Method::TRACE),
+];
+
+/// A request and response sent through the `TestServer`,
+/// for checking against an OpenAPI spec.
+#[derive(Debug)]
+pub struct OpenApiExchange<'a> {
+    pub method: &'a Method,
+    pub url: &'a Url,
+    pub request_headers: &'a HeaderMap<HeaderValue>,
+    pub request_body: &'a Bytes,
+    pub status_code: StatusCode,
+    pub response_headers: &'a HeaderMap<HeaderValue>,
+    pub response_body: &'a Bytes,
+}
+
+/// Checks requests and responses match the operations in an OpenAPI 3.x spec,
+/// and tracks which operations have been exercised.
+#[derive(Debug)]
+pub struct OpenApiContract {
+    spec: Value,
+    operations: Vec<OpenApiOperation>,
+    exercised: Mutex<Vec<bool>>,
+}
+
+#[derive(Debug)]
+struct OpenApiOperation {
+    method: Method,
+    path_template: String,
+    route: RoutePattern,
+    operation: Value,
+    path_item_parameters: Vec<Value>,
+}
+
+impl OpenApiOperation {
+    fn name(&self) -> String {
+        let method = &self.method;
+        let path_template = &self.path_template;
+
+        match self.operation.get("operationId").and_then(Value::as_str) {
+            Some(operation_id) => format!("{method} {path_template} ({operation_id})"),
+            None => format!("{method} {path_template}"),
+        }
+    }
+}
+
+impl OpenApiContract {
+    /// Reads the spec from a JSON or YAML file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let debug_path = path.display();
+        let file_bytes =
+            read(path).with_context(|| format!("Reading OpenAPI spec, from {debug_path}"))?;
+
+        let spec = match ::serde_json::from_slice::<Value>(&file_bytes) {
+            Ok(spec) => spec,
+            Err(_) => ::serde_yaml_ng::from_slice::<Value>(&file_bytes).with_context(|| {
+                format!("Parsing OpenAPI spec as JSON or YAML, from {debug_path}")
+            })?,
+        };
+
+        Self::from_value(spec).with_context(|| format!("Loading OpenAPI spec, from {debug_path}"))
+    }
+
+    pub fn from_value(mut spec: Value) -> Result<Self> {
+        let version = spec
+            .get("openapi")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("Missing 'openapi' version field"))?;
+        if !version.starts_with("3.") {
+            return Err(anyhow!(
+                "Unsupported OpenAPI version '{version}', expected 3.x"
+            ));
+        }
+
+        // 3.0 schemas are not quite JSON Schema, so they are converted up front.
+        if version.starts_with("3.0") {
+            normalize_openapi_3_0_schemas(&mut spec);
+        }
+
+        let mut operations = Vec::new();
+        if let Some(paths) = spec.get("paths").and_then(Value::as_object) {
+            for (path_template, path_item) in paths {
+                let path_item = resolve_ref(&spec, path_item);
+                let route = RoutePattern::parse(path_template)
+                    .with_context(|| format!("Parsing OpenAPI path '{path_template}'"))?;
+                let path_item_parameters = path_item
+                    .get("parameters")
+                    .and_then(Value::as_array)
+                    .cloned()
+                    .unwrap_or_default();
+
+                for (method_name, method) in &OPERATION_METHODS {
+                    if let Some(operation) = path_item.get(*method_name) {
+                        operations.push(OpenApiOperation {
+                            method: method.clone(),
+                            path_template: path_template.clone(),
+                            route: route.clone(),
+                            operation: operation.clone(),
+                            path_item_parameters: path_item_parameters.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let exercised = Mutex::new(vec![false; operations.len()]);
+        Ok(Self {
+            spec,
+            operations,
+            exercised,
+        })
+    }
+
+    /// Returns every way the exchange does not match the spec.
+    ///
+    /// The operation matched is marked as exercised.
+    pub fn violations(&self, exchange: &OpenApiExchange<'_>) -> Vec<String> {
+        let path = exchange.url.path();
+        let maybe_matched = self
+            .operations
+            .iter()
+            .enumerate()
+            .filter_map(|(index, operation)| {
+                operation
+                    .route
+                    .path_params(exchange.method, path)
+                    .filter(|_| operation.method == *exchange.method)
+                    .map(|path_params| (index, operation, path_params))
+            })
+            .max_by_key(|(_, operation, _)| operation.route.specificity());
+
+        let Some((index, operation, path_params)) = maybe_matched else {
+            let method = exchange.method;
+            return vec![format!(
+                "no operation in the OpenAPI spec matches {method} {path}"
+            )];
+        };
+
+        if let Ok(mut exercised) = self.exercised.lock() {
+            exercised[index] = true;
+        }
+
+        let mut violations = Vec::new();
+        self.check_parameters(operation, &path_params, exchange, &mut violations);
+        self.check_request_body(operation, exchange, &mut violations);
+        self.check_response(operation, exchange, &mut violations);
+
+        let operation_name = operation.name();
+        violations
+            .into_iter()
+            .map(|violation| format!("{operation_name}, {violation}"))
+            .collect()
+    }
+
+    /// Returns the names of all operations which have not been exercised,
+    /// i.e. `GET /users/{id} (getUser)`.
+    pub fn unexercised_operations(&self) -> Vec<String> {
+        let exercised = match self.exercised.lock() {
+            Ok(exercised) => exercised.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+
+        self.operations
+            .iter()
+            .zip(exercised)
+            .filter(|(_, is_exercised)| !is_exercised)
+            .map(|(operation, _)| operation.name())
+            .collect()
+    }
+
+    fn check_parameters(
+        &self,
+        operation: &OpenApiOperation,
+        path_params: &[(String, String)],
+        exchange: &OpenApiExchange<'_>,
+        violations: &mut Vec<String>,
+    ) {
+        let operation_parameters = operation
+            .operation
+            .get("parameters")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        // Operation parameters override path item parameters with the same name and location.
+        let mut parameters: Vec<&Value> = operation_parameters
+            .iter()
+            .map(|parameter| resolve_ref(&self.spec, parameter))
+            .collect();
+        for path_item_parameter in &operation.path_item_parameters {
+            let path_item_parameter = resolve_ref(&self.spec, path_item_parameter);
+            let is_overridden = parameters.iter().any(|parameter| {
+                parameter.get("name") == path_item_parameter.get("name")
+                    && parameter.get("in") == path_item_parameter.get("in")
+            });
+
+            if !is_overridden {
+                parameters.push(path_item_parameter);
+            }
+        }
+
+        for parameter in parameters {
+            let name = parameter
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let location = parameter
+                .get("in")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let is_required = location == "path"
+                || parameter
+                    .get("required")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+
+            let values: Vec<String> = match location {
+                "path" => path_params
+                    .iter()
+                    .filter(|(param_name, _)| param_name == name)
+                    .map(|(_, value)| value.clone())
+                    .collect(),
+                "query" => exchange
+                    .url
+                    .query_pairs()
+                    .filter(|(param_name, _)| param_name == name)
+                    .map(|(_, value)| value.to_string())
+                    .collect(),
+                "header" => exchange
+                    .request_headers
+                    .get_all(name)
+                    .iter()
+                    .map(|value| String::from_utf8_lossy(value.as_bytes()).to_string())
+                    .collect(),
+                _ => continue,
+            };
+
+            if values.is_empty() {
+                if is_required {
+                    violations.push(format!("{location} parameter '{name}' is required"));
+                }
+                continue;
+            }
+
+            if let Some(schema) = parameter.get("schema") {
+                let value = coerce_string_values(&self.spec, &values, schema);
+                let prefix = format!("{location} parameter '{name}'");
+                self.check_schema(&prefix, schema, &value, violations);
+            }
+        }
+    }
+
+    fn check_request_body(
+        &self,
+        operation: &OpenApiOperation,
+        exchange: &OpenApiExchange<'_>,
+        violations: &mut Vec<String>,
+    ) {
+        let Some(request_body) = operation.operation.get("requestBody") else {
+            return;
+        };
+        let request_body = resolve_ref(&self.spec, request_body);
+
+        if exchange.request_body.is_empty() {
+            if request_body
+                .get("required")
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+            {
+                violations.push("request body is required".to_string());
+            }
+            return;
+        }
+
+        self.check_content(
+            "request",
+            request_body.get("content"),
+            exchange.request_headers,
+            exchange.request_body,
+            violations,
+        );
+    }
+
+    fn check_response(
+        &self,
+        operation: &OpenApiOperation,
+        exchange: &OpenApiExchange<'_>,
+        violations: &mut Vec<String>,
+    ) {
+        let Some(responses) = operation
+            .operation
+            .get("responses")
+            .and_then(Value::as_object)
+        else {
+            return;
+        };
+
+        let status_code = exchange.status_code;
+        let status_key = status_code.as_str();
+        let status_range_key = format!("{}XX", &status_key[..1]);
+        let maybe_response = responses
+            .get(status_key)
+            .or_else(|| {
+                responses
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(&status_range_key))
+                    .map(|(_, response)| response)
+            })
+            .or_else(|| responses.get("default"));
+
+        let Some(response) = maybe_response else {
+            let status_format = StatusCodeFormatter(status_code);
+            let documented = responses.keys().cloned().collect::<Vec<_>>().join(", ");
+            violations.push(format!(
+                "response status {status_format} is not documented, expected one of {documented}"
+            ));
+            return;
+        };
+        let response = resolve_ref(&self.spec, response);
+
+        if let Some(headers) = response.get("headers").and_then(Value::as_object) {
+            for (name, header) in headers {
+                if name.eq_ignore_ascii_case(CONTENT_TYPE.as_str()) {
+                    continue;
+                }
+
+                let header = resolve_ref(&self.spec, header);
+                let values: Vec<String> = exchange
+                    .response_headers
+                    .get_all(name.as_str())
+                    .iter()
+                    .map(|value| String::from_utf8_lossy(value.as_bytes()).to_string())
+                    .collect();
+
+                if values.is_empty() {
+                    if header
+                        .get("required")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false)
+                    {
+                        violations.push(format!("response header '{name}' is required"));
+                    }
+                    continue;
+                }
+
+                if let Some(schema) = header.get("schema") {
+                    let value = coerce_string_values(&self.spec, &values, schema);
+                    let prefix = format!("response header '{name}'");
+                    self.check_schema(&prefix, schema, &value, violations);
+                }
+            }
+        }
+
+        if !exchange.response_body.is_empty() {
+            self.check_content(
+                "response",
+                response.get("content"),
+                exchange.response_headers,
+                exchange.response_body,
+                violations,
+            );
+        }
+    }
+
+    fn check_content(
+        &self,
+        kind: &str,
+        maybe_content: Option<&Value>,
+        headers: &HeaderMap<HeaderValue>,
+        body: &Bytes,
+        violations: &mut Vec<String>,
+    ) {
+        let Some(content) = maybe_content.and_then(Value::as_object) else {
+            return;
+        };
+        if content.is_empty() {
+            return;
+        }
+
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .map(|content_type| {
+                content_type
+                    .split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_ascii_lowercase()
+            })
+            .unwrap_or_default();
+
+        let Some(media_type) = find_media_type(content, &content_type) else {
+            let documented = content.keys().cloned().collect::<Vec<_>>().join(", ");
+            violations.push(format!(
+                "{kind} content type '{content_type}' is not documented, expected one of {documented}"
+            ));
+            return;
+        };
+
+        let Some(schema) = media_type.get("schema") else {
+            return;
+        };
+        if !content_type.contains("json") {
+            return;
+        }
+
+        match ::serde_json::from_slice::<Value>(body) {
+            Ok(value) => {
+                let prefix = format!("{kind} body");
+                self.check_schema(&prefix, schema, &value, violations);
+            }
+            Err(err) => violations.push(format!("{kind} body is not valid JSON, {err}")),
+        }
+    }
+
+    fn check_schema(
+        &self,
+        prefix: &str,
+        schema: &Value,
+        value: &Value,
+        violations: &mut Vec<String>,
+    ) {
+        let json_schema = JsonSchema::new(with_spec_components(&self.spec, schema));
+
+        match json_schema.violations(value) {
+            Ok(schema_violations) => violations.extend(
+                schema_violations
+                    .into_iter()
+                    .map(|violation| format!("{prefix} {violation}")),
+            ),
+            Err(err) => violations.push(format!("{prefix} has an invalid schema, {err:#}")),
+        }
+    }
+}
+
+/// Finds the media type for the content type given,
+/// falling back to wildcards such as `application/*` and `*/*`.
+fn find_media_type<'a>(content: &'a Map<String, Value>, content_type: &str) -> Option<&'a Value> {
+    let type_wildcard = content_type
+        .split('/')
+        .next()
+        .map(|main_type| format!("{main_type}/*"))
+        .unwrap_or_default();
+
+    content
+        .iter()
+        .find(|(media_type, _)| media_type.eq_ignore_ascii_case(content_type))
+        .or_else(|| {
+            content
+                .iter()
+                .find(|(media_type, _)| media_type.eq_ignore_ascii_case(&type_wildcard))
+        })
+        .or_else(|| content.iter().find(|(media_type, _)| *media_type == "*/*"))
+        .map(|(_, media)| media)
+}
+
+/// Follows local `$ref`s (i.e. `#/components/parameters/UserId`) to the value they point to.
+fn resolve_ref<'a>(spec: &'a Value, value: &'a Value) -> &'a Value {
+    let mut current = value;
+
+    // Limited, to avoid looping forever on references which point to themselves.
+    for _ in 0..32 {
+        let maybe_target = current
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix('#'))
+            .and_then(|pointer| spec.pointer(pointer));
+
+        match maybe_target {
+            Some(target) => current = target,
+            None => return current,
+        }
+    }
+
+    current
+}
+
+/// Schemas refer to others using `#/components/schemas/...`.
+/// So the components are copied in, for those references to be found.
+fn with_spec_components(spec: &Value, schema: &Value) -> Value {
+    let mut schema = schema.clone();
+
+    if let (Value::Object(schema_fields), Some(components)) = (&mut schema, spec.get("components"))
+    {
+        if !schema_fields.contains_key("components") {
+            schema_fields.insert("components".to_string(), components.clone());
+        }
+    }
+
+    schema
+}
+
+/// Parameters and headers arrive as text,
+/// so they are converted to the JSON type their schema expects before validating.
+fn coerce_string_values(spec: &Value, values: &[String], schema: &Value) -> Value {
+    let schema = resolve_ref(spec, schema);
+
+    if schema_type(schema) == Some("array") {
+        let items_schema = schema.get("items").cloned().unwrap_or(Value::Null);
+        let items = values
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(|item| coerce_string_value(item, resolve_ref(spec, &items_schema)))
+            .collect();
+
+        return Value::Array(items);
+    }
+
+    coerce_string_value(&values[0], schema)
+}
+
+fn coerce_string_value(value: &str, schema: &Value) -> Value {
+    let maybe_coerced = match schema_type(schema) {
+        Some("integer") => value.parse::<i64>().ok().map(Value::from),
+        Some("number") => value.parse::<f64>().ok().map(Value::from),
+        Some("boolean") => value.parse::<bool>().ok().map(Value::from),
+        _ => None,
+    };
+
+    maybe_coerced.unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+fn schema_type(schema: &Value) -> Option<&str> {
+    match schema.get("type")? {
+        Value::String(schema_type) => Some(schema_type),
+        Value::Array(schema_types) => schema_types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|schema_type| *schema_type != "null"),
+        _ => None,
+    }
+}
+
+/// Converts the OpenAPI 3.0 schema keywords which differ from JSON Schema.
+///
+/// `nullable` becomes a `null` type, and boolean `exclusiveMinimum`
+/// and `exclusiveMaximum` become the numeric form.
+fn normalize_openapi_3_0_schemas(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            if fields.remove("nullable") == Some(Value::Bool(true)) {
+                if let Some(Value::String(schema_type)) = fields.get("type") {
+                    let schema_type = Value::String(schema_type.clone());
+                    fields.insert(
+                        "type".to_string(),
+                        Value::Array(vec![schema_type, Value::String("null".to_string())]),
+                    );
+                }
+            }
+
+            for (exclusive_key, bound_key) in [
+                ("exclusiveMinimum", "minimum"),
+                ("exclusiveMaximum", "maximum"),
+            ] {
+                if let Some(Value::Bool(is_exclusive)) = fields.get(exclusive_key).cloned() {
+                    fields.remove(exclusive_key);
+                    if is_exclusive {
+                        if let Some(bound) = fields.remove(bound_key) {
+                            fields.insert(exclusive_key.to_string(), bound);
+                        }
+                    }
+                }
+            }
+
+            for child in fields.values_mut() {
+                normalize_openapi_3_0_schemas(child);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                normalize_openapi_3_0_schemas(item);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test_from_value {
+    use super::*;
+    use ::serde_json::json;
+
+    #[test]
+    fn it_should_error_for_swagger_2() {
+        let result = OpenApiContract::from_value(json!({ "swagger": "2.0", "paths": {} }));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_should_convert_openapi_3_0_nullable_and_exclusive_bounds() {
+        let contract = OpenApiContract::from_value(json!({
+            "openapi": "3.0.3",
+            "components": {
+                "schemas": {
+                    "Price": {
+                        "type": "number",
+                        "nullable": true,
+                        "minimum": 0,
+                        "exclusiveMinimum": true,
+                    },
+                },
+            },
+        }))
+        .unwrap();
+
+        assert_eq!(
+            contract.spec.pointer("/components/schemas/Price"),
+            Some(&json!({
+                "type": ["number", "null"],
+                "exclusiveMinimum": 0,
+            }))
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_violations {
+    use super::*;
+    use ::http::header::HeaderName;
+    use ::serde_json::json;
+
+    fn new_contract() -> OpenApiContract {
+        OpenApiContract::from_value(json!({
+            "openapi": "3.1.0",
+            "paths": {
+                "/users/{id}": {
+                    "parameters": [{ "$ref": "#/components/parameters/UserId" }],
+                    "get": {
+                        "operationId": "getUser",
+                        "parameters": [{
+                            "name": "fields",
+                            "in": "query",
+                            "schema": { "type": "array", "items": { "enum": ["id", "name"] } },
+                        }],
+                        "responses": {
+                            "200": {
+                                "headers": {
+                                    "x-request-id": {
+                                        "required": true,
+                                        "schema": { "type": "string" },
+                                    },
+                                },
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" },
+                                    },
+                                },
+                            },
+                            "4XX": { "description": "Client error" },
+                        },
+                    },
+                    "put": {
+                        "requestBody": {
+                            "required": true,
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/User" },
+                                },
+                            },
+                        },
+                        "responses": {
+                            "204": { "description": "Updated" },
+                        },
+                    },
+                },
+                "/users/me": {
+                    "get": {
+                        "operationId": "getMe",
+                        "responses": {
+                            "default": { "description": "Anything" },
+                        },
+                    },
+                },
+            },
+            "components": {
+                "parameters": {
+                    "UserId": {
+                        "name": "id",
+                        "in": "path",
+                        "required": true,
+                        "schema": { "type": "integer" },
+                    },
+                },
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "required": ["id", "name"],
+                        "properties": {
+                            "id": { "type": "integer" },
+                            "name": { "type": "string" },
+                        },
+                    },
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    fn json_headers() -> HeaderMap<HeaderValue> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers
+    }
+
+    fn violations(
+        contract: &OpenApiContract,
+        method: Method,
+        url: &str,
+        request_body: Value,
+        status_code: StatusCode,
+        response_headers: HeaderMap<HeaderValue>,
+        response_body: Value,
+    ) -> Vec<String> {
+        let url = Url::parse(url).unwrap();
+        let to_bytes = |value: Value| match value {
+            Value::Null => Bytes::new(),
+            value => Bytes::from(value.to_string()),
+        };
+
+        contract.violations(&OpenApiExchange {
+            method: &method,
+            url: &url,
+            request_headers: &json_headers(),
+            request_body: &to_bytes(request_body),
+            status_code,
+            response_headers: &response_headers,
+            response_body: &to_bytes(response_body),
+        })
+    }
+
+    fn user_response_headers() -> HeaderMap<HeaderValue> {
+        let mut headers = json_headers();
+        headers.insert(
+            HeaderName::from_static("x-request-id"),
+            HeaderValue::from_static("abc"),
+        );
+        headers
+    }
+
+    #[test]
+    fn it_should_return_no_violations_for_matching_exchange() {
+        let contract = new_contract();
+
+        let violations = violations(
+            &contract,
+            Method::GET,
+            "http://localhost/users/1?fields=id,name",
+            Value::Null,
+            StatusCode::OK,
+            user_response_headers(),
+            json!({ "id": 1, "name": "Joe" }),
+        );
+
+        assert_eq!(violations, Vec::<String>::new());
+    }
+
+    #[test]
+    fn it_should_report_unknown_operations() {
+        let contract = new_contract();
+
+        let violations = violations(
+            &contract,
+            Method::DELETE,
+            "http://localhost/users/1",
+            Value::Null,
+            StatusCode::OK,
+            HeaderMap::new(),
+            Value::Null,
+        );
+
+        assert_eq!(
+            violations,
+            vec!["no operation in the OpenAPI spec matches DELETE /users/1"]
+        );
+    }
+
+    #[test]
+    fn it_should_report_invalid_parameters() {
+        let contract = new_contract();
+
+        let violations = violations(
+            &contract,
+            Method::GET,
+            "http://localhost/users/abc?fields=id,email",
+            Value::Null,
+            StatusCode::OK,
+            user_response_headers(),
+            json!({ "id": 1, "name": "Joe" }),
+        );
+
+        assert_eq!(
+            violations,
+            vec![
+                r#"GET /users/{id} (getUser), query parameter 'fields' /1 "email" is not one of ["id","name"]"#,
+                r#"GET /users/{id} (getUser), path parameter 'id' (root) "abc" is not of type "integer""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_report_invalid_response_headers_and_body() {
+        let contract = new_contract();
+
+        let violations = violations(
+            &contract,
+            Method::GET,
+            "http://localhost/users/1",
+            Value::Null,
+            StatusCode::OK,
+            json_headers(),
+            json!({ "id": "1", "name": "Joe" }),
+        );
+
+        assert_eq!(
+            violations,
+            vec![
+                "GET /users/{id} (getUser), response header 'x-request-id' is required",
+                r#"GET /users/{id} (getUser), response body /id "1" is not of type "integer""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_match_status_ranges_and_report_undocumented_statuses() {
+        let contract = new_contract();
+
+        let not_found_violations = violations(
+            &contract,
+            Method::GET,
+            "http://localhost/users/1",
+            Value::Null,
+            StatusCode::NOT_FOUND,
+            HeaderMap::new(),
+            Value::Null,
+        );
+        assert_eq!(not_found_violations, Vec::<String>::new());
+
+        let server_error_violations = violations(
+            &contract,
+            Method::GET,
+            "http://localhost/users/1",
+            Value::Null,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            HeaderMap::new(),
+            Value::Null,
+        );
+        assert_eq!(
+            server_error_violations,
+            vec!["GET /users/{id} (getUser), response status 500 (Internal Server Error) is not documented, expected one of 200, 4XX"]
+        );
+    }
+
+    #[test]
+    fn it_should_report_invalid_and_missing_request_bodies() {
+        let contract = new_contract();
+
+        let invalid_violations = violations(
+            &contract,
+            Method::PUT,
+            "http://localhost/users/1",
+            json!({ "id": 1 }),
+            StatusCode::NO_CONTENT,
+            HeaderMap::new(),
+            Value::Null,
+        );
+        assert_eq!(
+            invalid_violations,
+            vec![r#"PUT /users/{id}, request body (root) "name" is a required property"#]
+        );
+
+        let missing_violations = violations(
+            &contract,
+            Method::PUT,
+            "http://localhost/users/1",
+            Value::Null,
+            StatusCode::NO_CONTENT,
+            HeaderMap::new(),
+            Value::Null,
+        );
+        assert_eq!(
+            missing_violations,
+            vec!["PUT /users/{id}, request body is required"]
+        );
+    }
+
+    #[test]
+    fn it_should_prefer_the_most_specific_path() {
+        let contract = new_contract();
+
+        let violations = violations(
+            &contract,
+            Method::GET,
+            "http://localhost/users/me",
+            Value::Null,
+            StatusCode::IM_A_TEAPOT,
+            HeaderMap::new(),
+            Value::Null,
+        );
+
+        assert_eq!(violations, Vec::<String>::new());
+        assert_eq!(
+            contract.unexercised_operations(),
+            vec!["GET /users/{id} (getUser)", "PUT /users/{id}"]
+        );
+    }
+}
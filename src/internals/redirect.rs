@@ -0,0 +1,148 @@
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::http::header::LOCATION;
+use ::http::HeaderMap;
+use ::http::HeaderValue;
+use ::http::Method;
+use ::http::StatusCode;
+use ::url::Url;
+
+/// Where to send the next request, when following a redirect.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Redirect {
+    pub url: Url,
+    pub method: Method,
+
+    /// False when the request is changed to a `GET`,
+    /// which is sent without the body.
+    pub is_keeping_body: bool,
+}
+
+impl Redirect {
+    /// Returns the redirect to follow for the response,
+    /// or `None` if it is not a redirect with a `Location` header.
+    ///
+    /// The method is rewritten as browsers do:
+    ///  - 301 and 302 change a `POST` into a `GET`.
+    ///  - 303 changes everything apart from `HEAD` into a `GET`.
+    ///  - 307 and 308 keep the method and body.
+    pub fn from_response(
+        method: &Method,
+        url: &Url,
+        status_code: StatusCode,
+        headers: &HeaderMap<HeaderValue>,
+    ) -> Result<Option<Self>> {
+        let is_changing_to_get = match status_code {
+            StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND => *method == Method::POST,
+            StatusCode::SEE_OTHER => *method != Method::HEAD,
+            StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT => false,
+            _ => return Ok(None),
+        };
+
+        let Some(location) = headers.get(LOCATION) else {
+            return Ok(None);
+        };
+        let location = location
+            .to_str()
+            .context("Reading 'Location' header of redirect")?;
+        let redirect_url = url
+            .join(location)
+            .with_context(|| format!("Parsing 'Location' header of redirect, '{location}'"))?;
+
+        let redirect = if is_changing_to_get {
+            Self {
+                url: redirect_url,
+                method: Method::GET,
+                is_keeping_body: false,
+            }
+        } else {
+            Self {
+                url: redirect_url,
+                method: method.clone(),
+                is_keeping_body: true,
+            }
+        };
+
+        Ok(Some(redirect))
+    }
+}
+
+#[cfg(test)]
+mod test_from_response {
+    use super::*;
+
+    fn redirect_for(method: Method, status_code: StatusCode) -> Option<Redirect> {
+        let url = "http://localhost/login".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(LOCATION, HeaderValue::from_static("/dashboard?tab=1"));
+
+        Redirect::from_response(&method, &url, status_code, &headers).unwrap()
+    }
+
+    #[test]
+    fn it_should_change_post_to_get_for_301_and_302() {
+        for status_code in [StatusCode::MOVED_PERMANENTLY, StatusCode::FOUND] {
+            let redirect = redirect_for(Method::POST, status_code).unwrap();
+
+            assert_eq!(redirect.method, Method::GET);
+            assert_eq!(redirect.is_keeping_body, false);
+        }
+    }
+
+    #[test]
+    fn it_should_keep_other_methods_for_301_and_302() {
+        for status_code in [StatusCode::MOVED_PERMANENTLY, StatusCode::FOUND] {
+            let redirect = redirect_for(Method::PUT, status_code).unwrap();
+
+            assert_eq!(redirect.method, Method::PUT);
+            assert_eq!(redirect.is_keeping_body, true);
+        }
+    }
+
+    #[test]
+    fn it_should_change_to_get_for_303_apart_from_head() {
+        let put_redirect = redirect_for(Method::PUT, StatusCode::SEE_OTHER).unwrap();
+        assert_eq!(put_redirect.method, Method::GET);
+        assert_eq!(put_redirect.is_keeping_body, false);
+
+        let head_redirect = redirect_for(Method::HEAD, StatusCode::SEE_OTHER).unwrap();
+        assert_eq!(head_redirect.method, Method::HEAD);
+    }
+
+    #[test]
+    fn it_should_keep_method_and_body_for_307_and_308() {
+        for status_code in [
+            StatusCode::TEMPORARY_REDIRECT,
+            StatusCode::PERMANENT_REDIRECT,
+        ] {
+            let redirect = redirect_for(Method::POST, status_code).unwrap();
+
+            assert_eq!(redirect.method, Method::POST);
+            assert_eq!(redirect.is_keeping_body, true);
+        }
+    }
+
+    #[test]
+    fn it_should_resolve_location_against_request_url() {
+        let redirect = redirect_for(Method::GET, StatusCode::FOUND).unwrap();
+
+        assert_eq!(redirect.url.as_str(), "http://localhost/dashboard?tab=1");
+    }
+
+    #[test]
+    fn it_should_not_redirect_for_other_statuses() {
+        assert_eq!(redirect_for(Method::GET, StatusCode::NOT_MODIFIED), None);
+        assert_eq!(redirect_for(Method::GET, StatusCode::OK), None);
+    }
+
+    #[test]
+    fn it_should_not_redirect_without_location() {
+        let url = "http://localhost/login".parse().unwrap();
+
+        let redirect =
+            Redirect::from_response(&Method::GET, &url, StatusCode::FOUND, &HeaderMap::new())
+                .unwrap();
+
+        assert_eq!(redirect, None);
+    }
+}
@@ -0,0 +1,249 @@
+use ::anyhow::anyhow;
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::http::Method;
+use ::std::fmt::Display;
+use ::std::fmt::Formatter;
+use ::std::fmt::Result as FmtResult;
+use ::std::str::FromStr;
+
+/// A route for matching against request paths,
+/// optionally starting with a method (i.e. `GET /users/:id`).
+///
+/// Path parameters can be written in the Axum style, `/users/:id`,
+/// or the OpenAPI style, `/users/{id}`.
+/// A final `*rest` or `{*rest}` segment matches the remainder of the path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutePattern {
+    route: String,
+    maybe_method: Option<Method>,
+    segments: Vec<RouteSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum RouteSegment {
+    Literal(String),
+    Param(String),
+    Wildcard,
+}
+
+impl RoutePattern {
+    pub fn parse(route: &str) -> Result<Self> {
+        let route = route.trim();
+        let (maybe_method, path) = match route.split_once(' ') {
+            Some((method, path)) => {
+                let method = Method::from_str(&method.to_ascii_uppercase())
+                    .with_context(|| format!("Parsing method '{method}' of route '{route}'"))?;
+                (Some(method), path.trim())
+            }
+            None => (None, route),
+        };
+
+        if !path.starts_with('/') {
+            return Err(anyhow!(
+                "Route path must start with '/', received '{route}'"
+            ));
+        }
+
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                if segment.starts_with('*') || segment.starts_with("{*") {
+                    RouteSegment::Wildcard
+                } else if let Some(name) = segment.strip_prefix(':') {
+                    RouteSegment::Param(name.to_string())
+                } else if segment.starts_with('{') && segment.ends_with('}') {
+                    RouteSegment::Param(segment[1..segment.len() - 1].to_string())
+                } else {
+                    RouteSegment::Literal(segment.to_string())
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let wildcard_position = segments
+            .iter()
+            .position(|segment| *segment == RouteSegment::Wildcard);
+        if let Some(position) = wildcard_position {
+            if position != segments.len() - 1 {
+                return Err(anyhow!(
+                    "Wildcards must be at the end of the route, received '{route}'"
+                ));
+            }
+        }
+
+        Ok(Self {
+            route: route.to_string(),
+            maybe_method,
+            segments,
+        })
+    }
+
+    /// Returns true if the method and path given match this route.
+    pub fn is_match(&self, method: &Method, path: &str) -> bool {
+        self.path_params(method, path).is_some()
+    }
+
+    /// Returns the path parameters, by name, if the method and path given match this route.
+    pub fn path_params(&self, method: &Method, path: &str) -> Option<Vec<(String, String)>> {
+        if let Some(route_method) = &self.maybe_method {
+            if route_method != method {
+                return None;
+            }
+        }
+
+        let mut params = Vec::new();
+        let mut path_segments = path.split('/').filter(|segment| !segment.is_empty());
+        for route_segment in &self.segments {
+            match route_segment {
+                RouteSegment::Wildcard => return Some(params),
+                RouteSegment::Param(name) => {
+                    let value = path_segments.next()?;
+                    params.push((name.clone(), value.to_string()));
+                }
+                RouteSegment::Literal(literal) => {
+                    if path_segments.next() != Some(literal.as_str()) {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        path_segments.next().is_none().then_some(params)
+    }
+
+    /// The number of fixed segments in the route.
+    ///
+    /// When multiple routes match, the one with the most fixed segments
+    /// is the most specific (i.e. `/users/me` over `/users/{id}`).
+    pub fn specificity(&self) -> usize {
+        self.segments
+            .iter()
+            .filter(|segment| matches!(segment, RouteSegment::Literal(_)))
+            .count()
+    }
+}
+
+impl Display for RoutePattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.route)
+    }
+}
+
+#[cfg(test)]
+mod test_parse {
+    use super::*;
+
+    #[test]
+    fn it_should_parse_path_with_params() {
+        let route = RoutePattern::parse(&"/users/:id/posts/{post_id}").unwrap();
+
+        assert_eq!(route.maybe_method, None);
+        assert_eq!(
+            route.segments,
+            vec![
+                RouteSegment::Literal("users".to_string()),
+                RouteSegment::Param("id".to_string()),
+                RouteSegment::Literal("posts".to_string()),
+                RouteSegment::Param("post_id".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_parse_method() {
+        let route = RoutePattern::parse(&"post /users").unwrap();
+
+        assert_eq!(route.maybe_method, Some(Method::POST));
+    }
+
+    #[test]
+    fn it_should_error_without_leading_slash() {
+        let result = RoutePattern::parse(&"users/:id");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_should_error_when_wildcard_is_not_last() {
+        let result = RoutePattern::parse(&"/files/*path/info");
+
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_is_match {
+    use super::*;
+
+    #[test]
+    fn it_should_match_literal_paths() {
+        let route = RoutePattern::parse(&"/users").unwrap();
+
+        assert!(route.is_match(&Method::GET, &"/users"));
+        assert!(route.is_match(&Method::GET, &"/users/"));
+        assert!(!route.is_match(&Method::GET, &"/users/123"));
+        assert!(!route.is_match(&Method::GET, &"/posts"));
+    }
+
+    #[test]
+    fn it_should_match_params() {
+        let route = RoutePattern::parse(&"/users/:id").unwrap();
+
+        assert!(route.is_match(&Method::GET, &"/users/123"));
+        assert!(!route.is_match(&Method::GET, &"/users"));
+    }
+
+    #[test]
+    fn it_should_match_wildcards() {
+        let route = RoutePattern::parse(&"/files/*path").unwrap();
+
+        assert!(route.is_match(&Method::GET, &"/files/a/b/c.txt"));
+        assert!(!route.is_match(&Method::GET, &"/users/a"));
+    }
+
+    #[test]
+    fn it_should_only_match_method_given() {
+        let route = RoutePattern::parse(&"POST /users").unwrap();
+
+        assert!(route.is_match(&Method::POST, &"/users"));
+        assert!(!route.is_match(&Method::GET, &"/users"));
+    }
+
+    #[test]
+    fn it_should_match_root() {
+        let route = RoutePattern::parse(&"/").unwrap();
+
+        assert!(route.is_match(&Method::GET, &"/"));
+        assert!(!route.is_match(&Method::GET, &"/users"));
+    }
+}
+
+#[cfg(test)]
+mod test_path_params {
+    use super::*;
+
+    #[test]
+    fn it_should_return_params_by_name() {
+        let route = RoutePattern::parse(&"/users/{user_id}/posts/:post_id").unwrap();
+
+        let params = route.path_params(&Method::GET, &"/users/1/posts/2");
+
+        assert_eq!(
+            params,
+            Some(vec![
+                ("user_id".to_string(), "1".to_string()),
+                ("post_id".to_string(), "2".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn it_should_return_none_when_not_matching() {
+        let route = RoutePattern::parse(&"/users/{user_id}").unwrap();
+
+        let params = route.path_params(&Method::GET, &"/posts/1");
+
+        assert_eq!(params, None);
+    }
+}
@@ -0,0 +1,357 @@
+use ::anyhow::anyhow;
+use ::anyhow::Result;
+use ::std::any::Any;
+use ::std::fmt::Display;
+use ::std::future::Future;
+use ::std::pin::Pin;
+use ::std::time::Duration;
+use ::tokio::spawn;
+use ::tokio::sync::watch;
+use ::tokio::task::AbortHandle;
+use ::tokio::task::JoinError;
+use ::tokio::time::timeout;
+
+/// Completes when a graceful shutdown of the server has been started.
+pub type GracefulShutdownSignal = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Why the server task is no longer running.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerStopped {
+    /// It was stopped by the `TestServer`, either straight away or gracefully.
+    Shutdown,
+
+    /// The server future completed, when it should serve forever.
+    Finished,
+
+    /// The server future returned an error.
+    Errored(String),
+
+    /// The server task panicked.
+    Panicked(String),
+}
+
+impl Display for ServerStopped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Shutdown => write!(f, "Test server has been shut down"),
+            Self::Finished => write!(f, "Test server has stopped serving"),
+            Self::Errored(message) => write!(f, "Test server has stopped, with error: {message}"),
+            Self::Panicked(message) => {
+                write!(f, "Test server has stopped, it panicked with: {message}")
+            }
+        }
+    }
+}
+
+///
+/// Runs the server in the background, and watches it.
+///
+/// If the server stops, the reason is kept,
+/// so it can be given to the next request.
+///
+#[derive(Debug)]
+pub struct ServerTask {
+    abort_handle: AbortHandle,
+    stopped_receiver: watch::Receiver<Option<ServerStopped>>,
+    graceful_shutdown_sender: watch::Sender<bool>,
+}
+
+impl ServerTask {
+    /// Spawns the server returned by `build_server`.
+    ///
+    /// It is given a signal, for the server to begin shutting down gracefully.
+    pub fn spawn<B, F, E>(build_server: B) -> Self
+    where
+        B: FnOnce(GracefulShutdownSignal) -> F,
+        F: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        let (stopped_sender, stopped_receiver) = watch::channel(None);
+        let (graceful_shutdown_sender, graceful_shutdown_receiver) = watch::channel(false);
+
+        let mut signal_receiver = graceful_shutdown_receiver.clone();
+        let graceful_shutdown_signal = Box::pin(async move {
+            // An error means the `ServerTask` is gone, so shut down regardless.
+            let _ = signal_receiver
+                .wait_for(|is_shutting_down| *is_shutting_down)
+                .await;
+        });
+
+        let server = build_server(graceful_shutdown_signal);
+        let server_handle = spawn(async move { server.await.map_err(|err| err.to_string()) });
+        let abort_handle = server_handle.abort_handle();
+
+        spawn(async move {
+            let stopped = match server_handle.await {
+                Ok(Ok(())) if *graceful_shutdown_receiver.borrow() => ServerStopped::Shutdown,
+                Ok(Ok(())) => ServerStopped::Finished,
+                Ok(Err(message)) => ServerStopped::Errored(message),
+                Err(join_error) => join_error_to_stopped(join_error),
+            };
+
+            stopped_sender.send_replace(Some(stopped));
+        });
+
+        Self {
+            abort_handle,
+            stopped_receiver,
+            graceful_shutdown_sender,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.maybe_stopped().is_none()
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        *self.graceful_shutdown_sender.borrow()
+    }
+
+    pub fn maybe_stopped(&self) -> Option<ServerStopped> {
+        self.stopped_receiver.borrow().clone()
+    }
+
+    /// Returns an error, with the reason why, if the server has stopped.
+    pub fn ensure_running(&self) -> Result<()> {
+        match self.maybe_stopped() {
+            Some(stopped) => Err(anyhow!("{stopped}")),
+            None => Ok(()),
+        }
+    }
+
+    /// Waits up to the duration given for the server to stop,
+    /// returning an error with the reason why if it does.
+    ///
+    /// This gives a server which is crashing the chance to record why,
+    /// after a request to it has failed.
+    pub async fn ensure_running_within(&self, duration: Duration) -> Result<()> {
+        // Timing out means the server is still running.
+        let _ = timeout(duration, self.wait_until_stopped()).await;
+        self.ensure_running()
+    }
+
+    /// Stops the server, and waits for it to finish.
+    pub async fn shutdown(&self) {
+        self.abort_handle.abort();
+        self.wait_until_stopped().await
+    }
+
+    /// Signals the server to stop taking new connections,
+    /// and to stop once the requests in flight have finished.
+    pub fn start_graceful_shutdown(&self) {
+        self.graceful_shutdown_sender.send_replace(true);
+    }
+
+    pub async fn wait_until_stopped(&self) {
+        let mut stopped_receiver = self.stopped_receiver.clone();
+        // An error here means the watcher is gone, and so the server is too.
+        let _ = stopped_receiver.wait_for(Option::is_some).await;
+    }
+}
+
+impl Drop for ServerTask {
+    fn drop(&mut self) {
+        self.abort_handle.abort()
+    }
+}
+
+fn join_error_to_stopped(join_error: JoinError) -> ServerStopped {
+    if join_error.is_cancelled() {
+        return ServerStopped::Shutdown;
+    }
+
+    match join_error.try_into_panic() {
+        Ok(panic) => ServerStopped::Panicked(panic_message(panic)),
+        Err(join_error) => ServerStopped::Errored(join_error.to_string()),
+    }
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    if let Some(message) = panic.downcast_ref::<&'static str>() {
+        return message.to_string();
+    }
+
+    if let Some(message) = panic.downcast_ref::<String>() {
+        return message.clone();
+    }
+
+    "unknown panic".to_string()
+}
+
+#[cfg(test)]
+mod test_spawn {
+    use super::*;
+    use ::std::future::pending;
+
+    async fn wait_until_stopped(server_task: &ServerTask) -> ServerStopped {
+        let mut stopped_receiver = server_task.stopped_receiver.clone();
+        let stopped = stopped_receiver
+            .wait_for(Option::is_some)
+            .await
+            .expect("Should receive stopped");
+
+        stopped.clone().unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_should_be_running_while_server_runs() {
+        let server_task = ServerTask::spawn(|_| pending::<Result<(), String>>());
+
+        assert!(server_task.is_running());
+        assert!(server_task.ensure_running().is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_error_returned_by_server() {
+        let server_task = ServerTask::spawn(|_| async { Err("address in use") });
+
+        let stopped = wait_until_stopped(&server_task).await;
+        assert_eq!(
+            stopped,
+            ServerStopped::Errored("address in use".to_string())
+        );
+        assert!(!server_task.is_running());
+
+        let err = server_task.ensure_running().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Test server has stopped, with error: address in use"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_panic_raised_by_server() {
+        let server_task = ServerTask::spawn(|_| async {
+            if true {
+                panic!("server blew up");
+            }
+
+            Ok::<(), String>(())
+        });
+
+        let stopped = wait_until_stopped(&server_task).await;
+        assert_eq!(
+            stopped,
+            ServerStopped::Panicked("server blew up".to_string())
+        );
+
+        let err = server_task.ensure_running().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Test server has stopped, it panicked with: server blew up"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_formatted_panic_raised_by_server() {
+        let server_task = ServerTask::spawn(|_| async {
+            if true {
+                panic!("server blew up on port {}", 1234);
+            }
+
+            Ok::<(), String>(())
+        });
+
+        let stopped = wait_until_stopped(&server_task).await;
+        assert_eq!(
+            stopped,
+            ServerStopped::Panicked("server blew up on port 1234".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_report_server_finishing() {
+        let server_task = ServerTask::spawn(|_| async { Ok::<(), String>(()) });
+
+        let stopped = wait_until_stopped(&server_task).await;
+        assert_eq!(stopped, ServerStopped::Finished);
+    }
+}
+
+#[cfg(test)]
+mod test_ensure_running_within {
+    use super::*;
+    use ::std::future::pending;
+    use ::tokio::time::sleep;
+
+    #[tokio::test]
+    async fn it_should_return_ok_when_server_keeps_running() {
+        let server_task = ServerTask::spawn(|_| pending::<Result<(), String>>());
+
+        let result = server_task
+            .ensure_running_within(Duration::from_millis(10))
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_should_wait_for_server_which_is_crashing() {
+        let server_task = ServerTask::spawn(|_| async {
+            sleep(Duration::from_millis(10)).await;
+            Err("address in use")
+        });
+
+        let err = server_task
+            .ensure_running_within(Duration::from_secs(5))
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Test server has stopped, with error: address in use"
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_shutdown {
+    use super::*;
+    use ::std::future::pending;
+
+    #[tokio::test]
+    async fn it_should_stop_server_on_shutdown() {
+        let server_task = ServerTask::spawn(|_| pending::<Result<(), String>>());
+
+        server_task.shutdown().await;
+
+        assert!(!server_task.is_running());
+        assert_eq!(server_task.maybe_stopped(), Some(ServerStopped::Shutdown));
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_crash_reason_when_shutdown_after_crashing() {
+        let server_task = ServerTask::spawn(|_| async { Err("address in use") });
+
+        let mut stopped_receiver = server_task.stopped_receiver.clone();
+        let _ = stopped_receiver.wait_for(Option::is_some).await;
+        server_task.shutdown().await;
+
+        assert_eq!(
+            server_task.maybe_stopped(),
+            Some(ServerStopped::Errored("address in use".to_string()))
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_start_graceful_shutdown {
+    use super::*;
+
+    #[tokio::test]
+    async fn it_should_signal_server_to_stop() {
+        let server_task = ServerTask::spawn(|graceful_shutdown_signal| async move {
+            graceful_shutdown_signal.await;
+            Ok::<(), String>(())
+        });
+
+        assert!(!server_task.is_shutting_down());
+
+        server_task.start_graceful_shutdown();
+        assert!(server_task.is_shutting_down());
+
+        server_task.wait_until_stopped().await;
+        assert!(!server_task.is_running());
+        assert_eq!(server_task.maybe_stopped(), Some(ServerStopped::Shutdown));
+    }
+}
@@ -0,0 +1,453 @@
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::bytes::Bytes;
+use ::http::HeaderMap;
+use ::http::HeaderName;
+use ::http::HeaderValue;
+use ::http::StatusCode;
+use ::serde_json::Value;
+use ::std::env::current_dir;
+use ::std::env::var;
+use ::std::fs::create_dir_all;
+use ::std::fs::read_to_string;
+use ::std::fs::write;
+use ::std::io::ErrorKind;
+use ::std::path::Path;
+use ::std::path::PathBuf;
+
+#[cfg(feature = "pretty-assertions")]
+use ::pretty_assertions::assert_eq;
+
+use crate::internals::StatusCodeFormatter;
+
+/// When set, snapshots which are missing or do not match are written with the new response.
+pub const UPDATE_SNAPSHOTS_ENV_VAR: &str = "AXUM_TEST_UPDATE_SNAPSHOTS";
+
+/// The value written in place of redacted JSON values.
+pub const REDACTED_VALUE: &str = "[redacted]";
+
+const SNAPSHOT_DIRECTORY: &str = "snapshots";
+const SNAPSHOT_EXTENSION: &str = "snap";
+
+/// Returns true if the environment asks for snapshots to be updated.
+pub fn is_updating_snapshots() -> bool {
+    match var(UPDATE_SNAPSHOTS_ENV_VAR) {
+        Ok(value) => !matches!(value.trim(), "" | "0" | "false"),
+        Err(_) => false,
+    }
+}
+
+/// Works out where a snapshot is stored.
+///
+/// By default this is a `snapshots` directory next to the source file
+/// which made the assertion, with the file named after both the source file
+/// and the snapshot (i.e. `tests/snapshots/users__get_user.snap`).
+pub fn snapshot_path(caller_file: &str, maybe_directory: Option<&Path>, name: &str) -> PathBuf {
+    let source_file = resolve_source_file(caller_file);
+    let source_stem = source_file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let directory = match maybe_directory {
+        Some(directory) => directory.to_path_buf(),
+        None => source_file
+            .parent()
+            .map(|parent| parent.join(SNAPSHOT_DIRECTORY))
+            .unwrap_or_else(|| PathBuf::from(SNAPSHOT_DIRECTORY)),
+    };
+
+    let file_name = format!(
+        "{}__{}.{SNAPSHOT_EXTENSION}",
+        sanitize_name(&source_stem),
+        sanitize_name(name)
+    );
+    directory.join(file_name)
+}
+
+/// `file!()` paths are relative to the workspace root,
+/// whilst tests are run from within the crate. So we search upwards for it.
+fn resolve_source_file(caller_file: &str) -> PathBuf {
+    let file = Path::new(caller_file);
+    if file.is_absolute() {
+        return file.to_path_buf();
+    }
+
+    if let Ok(working_dir) = current_dir() {
+        for ancestor in working_dir.ancestors() {
+            let candidate = ancestor.join(file);
+            if candidate.exists() {
+                return candidate;
+            }
+        }
+    }
+
+    file.to_path_buf()
+}
+
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Renders the parts of a response stored in a snapshot.
+///
+/// This is the status, the headers chosen, and then the body.
+/// JSON bodies are pretty printed with the redactions applied,
+/// and all other bodies are written as text.
+pub fn render_snapshot(
+    status_code: StatusCode,
+    headers: &HeaderMap<HeaderValue>,
+    snapshot_headers: &[HeaderName],
+    body: &Bytes,
+    redactions: &[String],
+) -> String {
+    let mut output = format!("status: {}\n", StatusCodeFormatter(status_code));
+
+    for header_name in snapshot_headers {
+        for header_value in headers.get_all(header_name) {
+            let header_value = String::from_utf8_lossy(header_value.as_bytes());
+            output.push_str(&format!("{header_name}: {header_value}\n"));
+        }
+    }
+
+    output.push('\n');
+    output.push_str(&render_body(body, redactions));
+    if !output.ends_with('\n') {
+        output.push('\n');
+    }
+
+    output
+}
+
+fn render_body(body: &Bytes, redactions: &[String]) -> String {
+    if body.is_empty() {
+        return String::new();
+    }
+
+    match ::serde_json::from_slice::<Value>(body) {
+        Ok(mut json) => {
+            for redaction in redactions {
+                redact_json(&mut json, redaction);
+            }
+
+            ::serde_json::to_string_pretty(&json).unwrap_or_default()
+        }
+        Err(_) => String::from_utf8_lossy(body).to_string(),
+    }
+}
+
+/// Replaces the values found at the selector with [`REDACTED_VALUE`].
+///
+/// Selectors are JSON Pointers, where a `*` segment matches
+/// every field of an object or every item of an array (i.e. `/users/*/id`).
+pub fn redact_json(json: &mut Value, selector: &str) {
+    let segments: Vec<String> = selector
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect();
+
+    redact_json_segments(json, &segments);
+}
+
+fn redact_json_segments(json: &mut Value, segments: &[String]) {
+    let Some((segment, rest)) = segments.split_first() else {
+        *json = Value::String(REDACTED_VALUE.to_string());
+        return;
+    };
+
+    match json {
+        Value::Object(fields) if segment == "*" => {
+            for value in fields.values_mut() {
+                redact_json_segments(value, rest);
+            }
+        }
+        Value::Object(fields) => {
+            if let Some(value) = fields.get_mut(segment) {
+                redact_json_segments(value, rest);
+            }
+        }
+        Value::Array(items) if segment == "*" => {
+            for value in items.iter_mut() {
+                redact_json_segments(value, rest);
+            }
+        }
+        Value::Array(items) => {
+            if let Some(value) = segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get_mut(index))
+            {
+                redact_json_segments(value, rest);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Compares the snapshot received against the one stored at the path.
+///
+/// Snapshots are only written or replaced when updating.
+/// Otherwise this will panic if the snapshot is missing,
+/// or showing the difference if it does not match.
+#[track_caller]
+pub fn assert_snapshot(
+    path: &Path,
+    name: &str,
+    received: &str,
+    is_updating: bool,
+    captured_logs: &str,
+) {
+    let maybe_expected = read_snapshot(path)
+        .with_context(|| format!("Reading snapshot '{name}', from {}", path.display()))
+        .unwrap();
+
+    match maybe_expected {
+        Some(expected) if expected == received => {}
+        None if !is_updating => {
+            panic!(
+                "Snapshot '{name}' is missing, expected at {}. Set {UPDATE_SNAPSHOTS_ENV_VAR}=1 to write it{captured_logs}",
+                path.display(),
+            );
+        }
+        Some(expected) if !is_updating => {
+            assert_eq!(
+                expected,
+                received,
+                "Snapshot '{name}' does not match the response, stored at {}. Set {UPDATE_SNAPSHOTS_ENV_VAR}=1 to accept the new response{captured_logs}",
+                path.display(),
+            );
+        }
+        _ => {
+            write_snapshot(path, received)
+                .with_context(|| format!("Writing snapshot '{name}', to {}", path.display()))
+                .unwrap();
+        }
+    }
+}
+
+fn read_snapshot(path: &Path) -> Result<Option<String>> {
+    match read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn write_snapshot(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent_dir) = path.parent() {
+        if !parent_dir.as_os_str().is_empty() {
+            create_dir_all(parent_dir)?;
+        }
+    }
+
+    write(path, contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_snapshot_path {
+    use super::resolve_source_file;
+    use super::snapshot_path;
+    use ::std::path::Path;
+    use ::std::path::PathBuf;
+
+    #[test]
+    fn it_should_place_snapshots_next_to_the_source_file() {
+        let path = snapshot_path(file!(), None, &"get_user");
+        let expected = resolve_source_file(file!())
+            .parent()
+            .unwrap()
+            .join("snapshots/snapshot__get_user.snap");
+
+        assert_eq!(path, expected);
+    }
+
+    #[test]
+    fn it_should_use_directory_given() {
+        let path = snapshot_path(file!(), Some(Path::new("/tmp/my-snapshots")), &"get_user");
+
+        assert_eq!(
+            path,
+            PathBuf::from("/tmp/my-snapshots/snapshot__get_user.snap")
+        );
+    }
+
+    #[test]
+    fn it_should_replace_unsafe_characters_in_name() {
+        let path = snapshot_path(file!(), Some(Path::new("/tmp")), &"../users/get user");
+
+        assert_eq!(path, PathBuf::from("/tmp/snapshot_____users_get_user.snap"));
+    }
+}
+
+#[cfg(test)]
+mod test_render_snapshot {
+    use super::redact_json;
+    use super::render_snapshot;
+    use ::bytes::Bytes;
+    use ::http::header::CONTENT_TYPE;
+    use ::http::HeaderMap;
+    use ::http::HeaderValue;
+    use ::http::StatusCode;
+    use ::serde_json::json;
+
+    #[test]
+    fn it_should_render_json_pretty_printed() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        let body = Bytes::from(r#"{"name":"Joe","age":20}"#);
+
+        let output = render_snapshot(StatusCode::OK, &headers, &[CONTENT_TYPE], &body, &[]);
+
+        assert_eq!(
+            output,
+            "status: 200 (OK)\ncontent-type: application/json\n\n{\n  \"age\": 20,\n  \"name\": \"Joe\"\n}\n"
+        );
+    }
+
+    #[test]
+    fn it_should_render_text_as_is() {
+        let body = Bytes::from("hello!");
+
+        let output = render_snapshot(StatusCode::NOT_FOUND, &HeaderMap::new(), &[], &body, &[]);
+
+        assert_eq!(output, "status: 404 (Not Found)\n\nhello!\n");
+    }
+
+    #[test]
+    fn it_should_skip_headers_not_in_response() {
+        let output = render_snapshot(
+            StatusCode::OK,
+            &HeaderMap::new(),
+            &[CONTENT_TYPE],
+            &Bytes::new(),
+            &[],
+        );
+
+        assert_eq!(output, "status: 200 (OK)\n\n");
+    }
+
+    #[test]
+    fn it_should_redact_json_using_wildcards() {
+        let mut json = json!({
+            "users": [
+                { "id": 1, "name": "Joe" },
+                { "id": 2, "name": "Kate" },
+            ],
+            "meta": { "a/b": 3, "total": 2 },
+        });
+
+        redact_json(&mut json, &"/users/*/id");
+        redact_json(&mut json, &"/meta/a~1b");
+        redact_json(&mut json, &"/does/not/exist");
+
+        assert_eq!(
+            json,
+            json!({
+                "users": [
+                    { "id": "[redacted]", "name": "Joe" },
+                    { "id": "[redacted]", "name": "Kate" },
+                ],
+                "meta": { "a/b": "[redacted]", "total": 2 },
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_assert_snapshot {
+    use super::assert_snapshot;
+    use ::std::env::temp_dir;
+    use ::std::fs::read_to_string;
+    use ::std::fs::remove_file;
+    use ::std::panic::catch_unwind;
+    use ::std::path::PathBuf;
+
+    fn new_snapshot_path(file_name: &str) -> PathBuf {
+        let path = temp_dir()
+            .join("axum-test-internal-snapshots")
+            .join(file_name);
+        let _ = remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn it_should_write_new_snapshots_when_updating() {
+        let path = new_snapshot_path("new.snap");
+
+        assert_snapshot(&path, &"new", &"status: 200 (OK)\n\n", true, &"");
+
+        assert_eq!(read_to_string(&path).unwrap(), "status: 200 (OK)\n\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "Snapshot 'missing' is missing")]
+    fn it_should_panic_when_snapshot_is_missing() {
+        let path = new_snapshot_path("missing.snap");
+
+        assert_snapshot(&path, &"missing", &"status: 200 (OK)\n\n", false, &"");
+    }
+
+    #[test]
+    fn it_should_not_write_missing_snapshots_when_not_updating() {
+        let path = new_snapshot_path("not-written.snap");
+
+        let result = catch_unwind(|| {
+            assert_snapshot(&path, &"not-written", &"status: 200 (OK)\n\n", false, &"");
+        });
+
+        assert!(result.is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn it_should_pass_when_snapshot_matches() {
+        let path = new_snapshot_path("matches.snap");
+
+        assert_snapshot(&path, &"matches", &"status: 200 (OK)\n\n", true, &"");
+        assert_snapshot(&path, &"matches", &"status: 200 (OK)\n\n", false, &"");
+    }
+
+    #[test]
+    #[should_panic(expected = "Snapshot 'mismatch' does not match the response")]
+    fn it_should_panic_when_snapshot_differs() {
+        let path = new_snapshot_path("mismatch.snap");
+
+        assert_snapshot(&path, &"mismatch", &"status: 200 (OK)\n\n", true, &"");
+        assert_snapshot(
+            &path,
+            &"mismatch",
+            &"status: 404 (Not Found)\n\n",
+            false,
+            &"",
+        );
+    }
+
+    #[test]
+    fn it_should_replace_snapshot_when_updating() {
+        let path = new_snapshot_path("updating.snap");
+
+        assert_snapshot(&path, &"updating", &"status: 200 (OK)\n\n", true, &"");
+        assert_snapshot(
+            &path,
+            &"updating",
+            &"status: 404 (Not Found)\n\n",
+            true,
+            &"",
+        );
+
+        assert_eq!(
+            read_to_string(&path).unwrap(),
+            "status: 404 (Not Found)\n\n"
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use ::std::collections::VecDeque;
+use ::std::time::Duration;
+
+use crate::SseEvent;
+
+///
+/// Parses a `text/event-stream` body into events,
+/// following the rules for parsing an event stream from the HTML standard.
+///
+/// The body can be given in chunks of any size.
+/// Lines split across chunks are held until they are complete.
+///
+#[derive(Debug, Default)]
+pub struct SseParser {
+    /// Bytes of a line which has not been completed yet.
+    partial_line: Vec<u8>,
+
+    /// True if the last byte seen was a `\r`,
+    /// so a `\n` straight after is part of the same line ending.
+    last_was_carriage_return: bool,
+
+    has_started: bool,
+
+    event_type: Option<String>,
+    data: String,
+    maybe_event_id: Option<String>,
+    maybe_retry: Option<Duration>,
+
+    last_event_id: Option<String>,
+    reconnection_time: Option<Duration>,
+
+    events: VecDeque<SseEvent>,
+}
+
+impl SseParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a complete body in one go.
+    pub fn parse_all(body: &[u8]) -> Vec<SseEvent> {
+        let mut parser = Self::new();
+        parser.push(body);
+        parser.finish();
+
+        parser.events.into_iter().collect()
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            match byte {
+                b'\n' if self.last_was_carriage_return => {
+                    self.last_was_carriage_return = false;
+                }
+                b'\r' | b'\n' => {
+                    self.last_was_carriage_return = byte == b'\r';
+                    self.end_line();
+                }
+                _ => {
+                    self.last_was_carriage_return = false;
+                    self.partial_line.push(byte);
+                }
+            }
+        }
+    }
+
+    /// Called when the stream has ended.
+    ///
+    /// Any incomplete event is thrown away, as the standard requires.
+    pub fn finish(&mut self) {
+        self.partial_line.clear();
+        self.reset_event();
+    }
+
+    pub fn next_event(&mut self) -> Option<SseEvent> {
+        self.events.pop_front()
+    }
+
+    /// The id of the last event, which persists between events.
+    /// This is what a client would send as the `Last-Event-ID` header.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+
+    /// The last reconnection time sent using a `retry` field.
+    pub fn reconnection_time(&self) -> Option<Duration> {
+        self.reconnection_time
+    }
+
+    fn end_line(&mut self) {
+        let line_bytes = ::std::mem::take(&mut self.partial_line);
+        let mut line = String::from_utf8_lossy(&line_bytes).into_owned();
+
+        if !self.has_started {
+            self.has_started = true;
+            if let Some(without_bom) = line.strip_prefix('\u{feff}') {
+                line = without_bom.to_string();
+            }
+        }
+
+        self.process_line(&line);
+    }
+
+    fn process_line(&mut self, line: &str) {
+        if line.is_empty() {
+            self.dispatch_event();
+            return;
+        }
+
+        if line.starts_with(':') {
+            return;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "event" => self.event_type = Some(value.to_string()),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => {
+                self.maybe_event_id = Some(value.to_string());
+                self.last_event_id = Some(value.to_string());
+            }
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(millis) = value.parse::<u64>() {
+                    let retry = Duration::from_millis(millis);
+                    self.maybe_retry = Some(retry);
+                    self.reconnection_time = Some(retry);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn dispatch_event(&mut self) {
+        if self.data.is_empty() {
+            self.reset_event();
+            return;
+        }
+
+        let mut data = ::std::mem::take(&mut self.data);
+        data.pop(); // Remove the trailing newline.
+
+        let event = SseEvent::new(
+            self.event_type.take(),
+            data,
+            self.maybe_event_id.take(),
+            self.maybe_retry.take(),
+        );
+
+        self.events.push_back(event);
+    }
+
+    fn reset_event(&mut self) {
+        self.event_type = None;
+        self.data.clear();
+        self.maybe_event_id = None;
+        self.maybe_retry = None;
+    }
+}
+
+#[cfg(test)]
+mod test_push {
+    use super::*;
+
+    #[test]
+    fn it_should_parse_event_with_all_fields() {
+        let events = SseParser::parse_all(b"event: update\ndata: hello\nid: 123\nretry: 5000\n\n");
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event(), "update");
+        assert_eq!(events[0].data(), "hello");
+        assert_eq!(events[0].id(), Some("123"));
+        assert_eq!(events[0].retry(), Some(Duration::from_millis(5000)));
+    }
+
+    #[test]
+    fn it_should_default_event_type_to_message() {
+        let events = SseParser::parse_all(b"data: hello\n\n");
+
+        assert_eq!(events[0].event(), "message");
+    }
+
+    #[test]
+    fn it_should_join_multiple_data_lines_with_newlines() {
+        let events = SseParser::parse_all(b"data: first\ndata: second\ndata\n\n");
+
+        assert_eq!(events[0].data(), "first\nsecond\n");
+    }
+
+    #[test]
+    fn it_should_only_strip_one_leading_space() {
+        let events = SseParser::parse_all(b"data:  two spaces\ndata:none\n\n");
+
+        assert_eq!(events[0].data(), " two spaces\nnone");
+    }
+
+    #[test]
+    fn it_should_ignore_comments_and_unknown_fields() {
+        let events = SseParser::parse_all(b": keep alive\nunknown: field\ndata: hello\n\n");
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data(), "hello");
+    }
+
+    #[test]
+    fn it_should_not_dispatch_events_without_data() {
+        let mut parser = SseParser::new();
+        parser.push(b"event: empty\nid: 1\n\ndata: hello\n\n");
+
+        let event = parser.next_event().unwrap();
+        assert_eq!(event.event(), "message");
+        assert_eq!(event.id(), None);
+        assert_eq!(parser.next_event(), None);
+        assert_eq!(parser.last_event_id(), Some("1"));
+    }
+
+    #[test]
+    fn it_should_ignore_retry_which_is_not_a_number() {
+        let events = SseParser::parse_all(b"retry: soon\ndata: hello\n\n");
+
+        assert_eq!(events[0].retry(), None);
+    }
+
+    #[test]
+    fn it_should_support_all_line_endings() {
+        let events = SseParser::parse_all(b"data: one\r\n\r\ndata: two\r\rdata: three\n\n");
+
+        let data: Vec<&str> = events.iter().map(|event| event.data()).collect();
+        assert_eq!(data, vec!["one", "two", "three"]);
+    }
+
+    #[test]
+    fn it_should_parse_events_split_across_chunks() {
+        let mut parser = SseParser::new();
+        parser.push(b"da");
+        parser.push(b"ta: hel");
+        assert_eq!(parser.next_event(), None);
+
+        parser.push(b"lo\r");
+        parser.push(b"\n\r");
+        parser.push(b"\n");
+
+        assert_eq!(parser.next_event().unwrap().data(), "hello");
+        assert_eq!(parser.next_event(), None);
+    }
+
+    #[test]
+    fn it_should_strip_byte_order_mark_at_start() {
+        let events = SseParser::parse_all("\u{feff}data: hello\n\n".as_bytes());
+
+        assert_eq!(events[0].data(), "hello");
+    }
+
+    #[test]
+    fn it_should_drop_incomplete_event_when_finished() {
+        let events = SseParser::parse_all(b"data: one\n\ndata: incomplete\n");
+
+        assert_eq!(events.len(), 1);
+    }
+
+    #[test]
+    fn it_should_keep_last_event_id_between_events() {
+        let mut parser = SseParser::new();
+        parser.push(b"id: 1\ndata: one\n\ndata: two\n\n");
+
+        assert_eq!(parser.next_event().unwrap().id(), Some("1"));
+        assert_eq!(parser.next_event().unwrap().id(), None);
+        assert_eq!(parser.last_event_id(), Some("1"));
+    }
+}
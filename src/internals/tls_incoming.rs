@@ -0,0 +1,104 @@
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::hyper::server::accept::Accept;
+use ::rustls::ServerConfig;
+use ::std::io::Error as IoError;
+use ::std::io::ErrorKind;
+use ::std::net::TcpListener as StdTcpListener;
+use ::std::pin::Pin;
+use ::std::sync::Arc;
+use ::std::task::Context as TaskContext;
+use ::std::task::Poll;
+use ::std::time::Duration;
+use ::tokio::net::TcpListener;
+use ::tokio::net::TcpStream;
+use ::tokio::spawn;
+use ::tokio::sync::mpsc::unbounded_channel;
+use ::tokio::sync::mpsc::UnboundedReceiver;
+use ::tokio::task::JoinHandle;
+use ::tokio::time::sleep;
+use ::tokio_rustls::server::TlsStream;
+use ::tokio_rustls::TlsAcceptor;
+
+/// How long to wait before accepting again, after an error accepting a connection.
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Accepts TCP connections, and performs the TLS handshake on them,
+/// for the server to then serve.
+///
+/// Handshakes run in their own tasks, so one slow or failing client
+/// will not hold up the others.
+pub struct TlsIncoming {
+    receiver: UnboundedReceiver<TlsStream<TcpStream>>,
+    accept_handle: JoinHandle<()>,
+}
+
+impl TlsIncoming {
+    pub fn new(tcp_listener: StdTcpListener, server_config: Arc<ServerConfig>) -> Result<Self> {
+        tcp_listener
+            .set_nonblocking(true)
+            .context("Failed to set TcpListener as non-blocking, for TLS")?;
+        let tcp_listener = TcpListener::from_std(tcp_listener)
+            .context("Failed to convert TcpListener for use with TLS")?;
+
+        let tls_acceptor = TlsAcceptor::from(server_config);
+        let (sender, receiver) = unbounded_channel();
+
+        let accept_handle = spawn(async move {
+            loop {
+                let tcp_stream = match tcp_listener.accept().await {
+                    Ok((tcp_stream, _)) => tcp_stream,
+                    Err(err) if is_connection_error(&err) => continue,
+                    Err(_) => {
+                        // Errors such as running out of file descriptors will not clear straight away,
+                        // so back off rather than spinning on them, as hyper's `AddrIncoming` does.
+                        sleep(ACCEPT_ERROR_BACKOFF).await;
+                        continue;
+                    }
+                };
+
+                let tls_acceptor = tls_acceptor.clone();
+                let sender = sender.clone();
+                spawn(async move {
+                    if let Ok(tls_stream) = tls_acceptor.accept(tcp_stream).await {
+                        let _ = sender.send(tls_stream);
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            receiver,
+            accept_handle,
+        })
+    }
+}
+
+/// Returns true for errors which only affect the connection being accepted,
+/// and so the next connection can be accepted straight away.
+fn is_connection_error(err: &IoError) -> bool {
+    matches!(
+        err.kind(),
+        ErrorKind::ConnectionRefused | ErrorKind::ConnectionAborted | ErrorKind::ConnectionReset
+    )
+}
+
+impl Accept for TlsIncoming {
+    type Conn = TlsStream<TcpStream>;
+    type Error = IoError;
+
+    fn poll_accept(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
+        self.receiver
+            .poll_recv(cx)
+            .map(|maybe_tls_stream| maybe_tls_stream.map(Ok))
+    }
+}
+
+impl Drop for TlsIncoming {
+    fn drop(&mut self) {
+        self.accept_handle.abort()
+    }
+}
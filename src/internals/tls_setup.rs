@@ -0,0 +1,102 @@
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::rcgen::BasicConstraints;
+use ::rcgen::Certificate as RcgenCertificate;
+use ::rcgen::CertificateParams;
+use ::rcgen::DnType;
+use ::rcgen::IsCa;
+use ::rcgen::KeyUsagePurpose;
+use ::rcgen::SanType;
+use ::rustls::Certificate;
+use ::rustls::ClientConfig;
+use ::rustls::PrivateKey;
+use ::rustls::RootCertStore;
+use ::rustls::ServerConfig;
+use ::std::net::IpAddr;
+use ::std::net::Ipv4Addr;
+use ::std::net::Ipv6Addr;
+use ::std::sync::Arc;
+
+/// A throwaway certificate authority, and a certificate for localhost signed by it.
+///
+/// The server is given the localhost certificate,
+/// and the client is set up to trust _only_ the certificate authority.
+#[derive(Clone)]
+pub struct TlsSetup {
+    pub server_config: Arc<ServerConfig>,
+    pub client_config: ClientConfig,
+}
+
+impl TlsSetup {
+    pub fn new_self_signed() -> Result<Self> {
+        let ca = new_certificate_authority()?;
+        let ca_der = ca
+            .serialize_der()
+            .context("Failed to serialize generated certificate authority")?;
+
+        let localhost = new_localhost_certificate()?;
+        let localhost_der = localhost
+            .serialize_der_with_signer(&ca)
+            .context("Failed to sign generated localhost certificate")?;
+        let localhost_key_der = localhost.serialize_private_key_der();
+
+        let mut server_config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(
+                vec![Certificate(localhost_der), Certificate(ca_der.clone())],
+                PrivateKey(localhost_key_der),
+            )
+            .context("Failed to build TLS config for the server")?;
+        server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+        let mut root_store = RootCertStore::empty();
+        root_store
+            .add(&Certificate(ca_der))
+            .context("Failed to trust generated certificate authority")?;
+        let client_config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(root_store)
+            .with_no_client_auth();
+
+        Ok(Self {
+            server_config: Arc::new(server_config),
+            client_config,
+        })
+    }
+}
+
+fn new_certificate_authority() -> Result<RcgenCertificate> {
+    let mut params = CertificateParams::new(Vec::new());
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "axum-test certificate authority");
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+
+    RcgenCertificate::from_params(params).context("Failed to generate certificate authority")
+}
+
+fn new_localhost_certificate() -> Result<RcgenCertificate> {
+    let mut params = CertificateParams::new(Vec::new());
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "localhost");
+    params.subject_alt_names = vec![
+        SanType::DnsName("localhost".to_string()),
+        SanType::IpAddress(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        SanType::IpAddress(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+    ];
+
+    RcgenCertificate::from_params(params).context("Failed to generate localhost certificate")
+}
+
+impl ::std::fmt::Debug for TlsSetup {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        write!(f, "TlsSetup {{ .. }}")
+    }
+}
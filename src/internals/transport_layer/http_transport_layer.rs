@@ -1,20 +1,32 @@
 use ::anyhow::Result;
 use ::async_trait::async_trait;
-use ::bytes::Bytes;
-use ::http::response::Parts;
+use ::axum::body::boxed;
+use ::axum::body::BoxBody;
 use ::http::Request;
-use ::hyper::body::to_bytes;
+use ::http::Response;
 use ::hyper::Body;
-use ::hyper::Client;
 use ::reserve_port::ReservedPort;
-use ::tokio::task::JoinHandle;
+use ::std::time::Duration;
 use ::url::Url;
 
+use crate::internals::HttpClient;
+use crate::internals::ServerTask;
+#[cfg(unix)]
+use crate::internals::UnixSocketFile;
 use crate::transport_layer::TransportLayer;
 
+/// How long to wait for a crashing server to record why,
+/// after a request to it has failed.
+const CRASHED_SERVER_WAIT: Duration = Duration::from_millis(100);
+
 #[derive(Debug)]
 pub struct HttpTransportLayer {
-    server_handle: JoinHandle<()>,
+    /// The server is stopped when this is dropped.
+    server_task: ServerTask,
+
+    /// A shared client, so requests can reuse pooled connections
+    /// and run concurrently.
+    client: HttpClient,
 
     /// If this has reserved a port for the test,
     /// then it is stored here.
@@ -24,40 +36,146 @@ pub struct HttpTransportLayer {
     maybe_reserved_port: Option<ReservedPort>,
 
     url: Url,
+
+    /// The socket file when serving over a Unix socket.
+    ///
+    /// It is deleted when this is dropped.
+    #[cfg(unix)]
+    #[allow(dead_code)]
+    maybe_unix_socket_file: Option<UnixSocketFile>,
 }
 
 impl HttpTransportLayer {
     pub(crate) fn new(
-        server_handle: JoinHandle<()>,
+        server_task: ServerTask,
         maybe_reserved_port: Option<ReservedPort>,
         url: Url,
+        client: HttpClient,
     ) -> Self {
         Self {
-            server_handle,
+            server_task,
+            client,
             maybe_reserved_port,
             url,
+
+            #[cfg(unix)]
+            maybe_unix_socket_file: None,
         }
     }
+
+    #[cfg(unix)]
+    pub(crate) fn with_unix_socket_file(mut self, unix_socket_file: UnixSocketFile) -> Self {
+        self.maybe_unix_socket_file = Some(unix_socket_file);
+        self
+    }
 }
 
 #[async_trait]
 impl TransportLayer for HttpTransportLayer {
-    async fn send(&mut self, request: Request<Body>) -> Result<(Parts, Bytes)> {
-        let hyper_response = Client::new().request(request).await?;
+    async fn send_streaming(&self, request: Request<Body>) -> Result<Response<BoxBody>> {
+        self.server_task.ensure_running()?;
 
-        let (parts, response_body) = hyper_response.into_parts();
-        let response_bytes = to_bytes(response_body).await?;
+        let hyper_response = match self.client.request(request).await {
+            Ok(hyper_response) => hyper_response,
+            Err(err) => {
+                // Prefer the reason the server died, over the connection error it caused.
+                // This waits briefly first, to let a crashing server record why.
+                self.server_task
+                    .ensure_running_within(CRASHED_SERVER_WAIT)
+                    .await?;
 
-        Ok((parts, response_bytes))
+                if self.server_task.is_shutting_down() {
+                    return Err(err.context("Test server is shutting down gracefully"));
+                }
+
+                return Err(err);
+            }
+        };
+
+        Ok(hyper_response.map(boxed))
     }
 
     fn url<'a>(&'a self) -> Option<&'a Url> {
         Some(&self.url)
     }
+
+    fn is_running(&self) -> bool {
+        self.server_task.is_running()
+    }
+
+    async fn shutdown(&self) {
+        self.server_task.shutdown().await
+    }
+
+    async fn graceful_shutdown(&self) {
+        self.server_task.start_graceful_shutdown();
+        self.server_task.wait_until_stopped().await
+    }
 }
 
-impl Drop for HttpTransportLayer {
-    fn drop(&mut self) {
-        self.server_handle.abort()
+#[cfg(test)]
+mod test_send {
+    use super::*;
+
+    use ::reserve_port::ReservedPort;
+
+    fn new_crashed_transport_layer(server_task: ServerTask) -> HttpTransportLayer {
+        let reserved_port = ReservedPort::random().unwrap();
+        let url: Url = format!("http://127.0.0.1:{}", reserved_port.port())
+            .parse()
+            .unwrap();
+
+        HttpTransportLayer::new(
+            server_task,
+            Some(reserved_port),
+            url,
+            HttpClient::new_http(false),
+        )
+    }
+
+    fn new_request(transport_layer: &HttpTransportLayer) -> Request<Body> {
+        Request::builder()
+            .uri(transport_layer.url.as_str())
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_should_return_panic_from_server_instead_of_connection_error() {
+        let server_task = ServerTask::spawn(|_| async {
+            if true {
+                panic!("failed to accept connection");
+            }
+
+            Ok::<(), String>(())
+        });
+        let transport_layer = new_crashed_transport_layer(server_task);
+
+        let err = transport_layer
+            .send(new_request(&transport_layer))
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Test server has stopped, it panicked with: failed to accept connection"
+        );
+        assert!(!transport_layer.is_running());
+    }
+
+    #[tokio::test]
+    async fn it_should_return_error_from_server_instead_of_connection_error() {
+        let server_task = ServerTask::spawn(|_| async { Err("listener closed") });
+        let transport_layer = new_crashed_transport_layer(server_task);
+
+        let err = transport_layer
+            .send(new_request(&transport_layer))
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Test server has stopped, with error: listener closed"
+        );
     }
 }
@@ -1,54 +1,188 @@
+use ::anyhow::anyhow;
 use ::anyhow::Error as AnyhowError;
 use ::anyhow::Result;
 use ::async_trait::async_trait;
-use ::axum::Router;
+use ::axum::body::boxed;
+use ::axum::body::BoxBody;
+use ::axum::body::HttpBody;
+use ::axum::BoxError;
+use ::axum::Error as AxumError;
 use ::bytes::Bytes;
-use ::http::response::Parts;
+use ::http::HeaderMap;
 use ::http::Request;
-use ::hyper::body::to_bytes;
+use ::http::Response;
+use ::hyper::body::SizeHint;
 use ::hyper::Body;
 use ::std::fmt::Debug;
+use ::std::pin::Pin;
+use ::std::sync::atomic::AtomicBool;
+use ::std::sync::atomic::Ordering;
+use ::std::sync::Arc;
+use ::std::sync::Mutex;
+use ::std::task::Context;
+use ::std::task::Poll;
+use ::tokio::sync::watch;
 use ::tower::util::ServiceExt;
 use ::tower::Service;
 
+use crate::internals::ServerStopped;
 use crate::transport_layer::TransportLayer;
 
 pub struct MockTransportLayer<S> {
-    service: S,
+    /// Axum services are `Send` but not `Sync`.
+    /// The mutex is only held to clone the service out,
+    /// so each request runs on its own copy, concurrently with the others.
+    service: Mutex<S>,
+
+    is_shutdown: AtomicBool,
+
+    /// The number of requests currently being handled,
+    /// including those with response bodies still being read.
+    in_flight_sender: Arc<watch::Sender<usize>>,
 }
 
-impl<S> MockTransportLayer<S>
+impl<S, R> MockTransportLayer<S>
 where
-    S: Service<Request<Body>, Response = Router> + Clone + Send,
+    S: Service<Request<Body>, Response = R> + Clone + Send,
     AnyhowError: From<S::Error>,
     S::Future: Send,
+    R: Service<Request<Body>, Response = Response<BoxBody>> + Send,
+    R::Error: Into<BoxError>,
+    R::Future: Send,
 {
     pub(crate) fn new(service: S) -> Self {
-        Self { service }
+        Self {
+            service: Mutex::new(service),
+            is_shutdown: AtomicBool::new(false),
+            in_flight_sender: Arc::new(watch::channel(0).0),
+        }
+    }
+
+    fn clone_service(&self) -> Result<S> {
+        let service_locked = self
+            .service
+            .lock()
+            .map_err(|err| anyhow!("Failed to lock service for MockTransportLayer, {err:?}"))?;
+
+        Ok(service_locked.clone())
     }
 }
 
 #[async_trait]
-impl<S> TransportLayer for MockTransportLayer<S>
+impl<S, R> TransportLayer for MockTransportLayer<S>
 where
-    S: Service<Request<Body>, Response = Router> + Clone + Send,
+    S: Service<Request<Body>, Response = R> + Clone + Send,
     AnyhowError: From<S::Error>,
     S::Future: Send,
+    R: Service<Request<Body>, Response = Response<BoxBody>> + Send,
+    R::Error: Into<BoxError>,
+    R::Future: Send,
 {
-    async fn send(&mut self, request: Request<Body>) -> Result<(Parts, Bytes)> {
+    async fn send_streaming(&self, request: Request<Body>) -> Result<Response<BoxBody>> {
+        // Counted before checking, so a graceful shutdown cannot miss this request.
+        let in_flight_guard = InFlightGuard::new(self.in_flight_sender.clone());
+        if !self.is_running() {
+            return Err(anyhow!("{}", ServerStopped::Shutdown));
+        }
+
         let body: Body = Bytes::new().into();
         let empty_request = Request::builder()
             .body(body)
             .expect("should build empty request");
 
-        let service = self.service.clone();
-        let router = service.oneshot(empty_request).await?;
+        let make_service = self.clone_service()?;
+        let service = make_service.oneshot(empty_request).await?;
+
+        let response = service
+            .oneshot(request)
+            .await
+            .map_err(|err| anyhow!(err.into()))?;
+
+        // The guard is moved into the body, so a graceful shutdown waits for it to be read.
+        let response = response.map(|body| {
+            boxed(InFlightBody {
+                body,
+                _in_flight_guard: in_flight_guard,
+            })
+        });
+
+        Ok(response)
+    }
 
-        let response = router.oneshot(request).await?;
-        let (parts, response_body) = response.into_parts();
-        let response_bytes = to_bytes(response_body).await?;
+    fn is_mock(&self) -> bool {
+        true
+    }
+
+    fn is_running(&self) -> bool {
+        !self.is_shutdown.load(Ordering::Acquire)
+    }
+
+    async fn shutdown(&self) {
+        self.is_shutdown.store(true, Ordering::Release)
+    }
+
+    async fn graceful_shutdown(&self) {
+        self.shutdown().await;
+
+        let mut in_flight_receiver = self.in_flight_sender.subscribe();
+        // Cannot error, as the sender is held by `self`.
+        let _ = in_flight_receiver
+            .wait_for(|in_flight| *in_flight == 0)
+            .await;
+    }
+}
+
+/// Counts a request as in flight, until it is dropped.
+struct InFlightGuard {
+    in_flight_sender: Arc<watch::Sender<usize>>,
+}
+
+impl InFlightGuard {
+    fn new(in_flight_sender: Arc<watch::Sender<usize>>) -> Self {
+        in_flight_sender.send_modify(|in_flight| *in_flight += 1);
+
+        Self { in_flight_sender }
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.in_flight_sender
+            .send_modify(|in_flight| *in_flight -= 1);
+    }
+}
+
+/// A response body, which keeps its request counted as in flight
+/// until it has been read or dropped.
+struct InFlightBody {
+    body: BoxBody,
+    _in_flight_guard: InFlightGuard,
+}
+
+impl HttpBody for InFlightBody {
+    type Data = Bytes;
+    type Error = AxumError;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        Pin::new(&mut self.body).poll_data(cx)
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Pin::new(&mut self.body).poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
 
-        Ok((parts, response_bytes))
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
     }
 }
 
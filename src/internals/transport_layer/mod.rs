@@ -3,3 +3,6 @@ pub use self::http_transport_layer::*;
 
 mod mock_transport_layer;
 pub use self::mock_transport_layer::*;
+
+mod spawn_http_transport_layer;
+pub use self::spawn_http_transport_layer::*;
@@ -0,0 +1,127 @@
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::axum::response::Response;
+use ::axum::BoxError;
+use ::axum::Server as AxumServer;
+use ::http::Request;
+use ::hyper::Body;
+#[cfg(any(feature = "tls", unix))]
+use ::hyper::Server as HyperServer;
+use ::std::convert::Infallible;
+use ::std::task::Context as TaskContext;
+use ::std::task::Poll;
+use ::tower::Service;
+use ::url::Url;
+
+use crate::internals::HttpTransportLayer;
+use crate::internals::ServerTask;
+#[cfg(feature = "tls")]
+use crate::internals::TlsIncoming;
+#[cfg(unix)]
+use crate::internals::UnixIncoming;
+#[cfg(unix)]
+use crate::internals::UNIX_SOCKET_URL_ADDRESS;
+use crate::transport_layer::TransportLayer;
+use crate::transport_layer::TransportLayerBuilder;
+
+///
+/// Serves the make service given in the background,
+/// over a Unix socket, TLS, or plain TCP, as set on the builder.
+///
+/// The make service is called with `()`, rather than the connection,
+/// and so this is only for make services which do not look at the connection.
+///
+pub fn spawn_http_transport_layer<M, S>(
+    make_service: M,
+    builder: TransportLayerBuilder,
+) -> Result<Box<dyn TransportLayer>>
+where
+    M: Service<(), Response = S, Error = Infallible> + Send + 'static,
+    M::Future: Send + 'static,
+    S: Service<Request<Body>, Response = Response> + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send + 'static,
+{
+    let make_service = IgnoreConnection(make_service);
+
+    #[cfg(unix)]
+    if let Some(socket_path) = builder.maybe_unix_socket_path() {
+        let (unix_incoming, unix_socket_file) = UnixIncoming::bind(socket_path)?;
+        let server = HyperServer::builder(unix_incoming)
+            .http2_only(builder.is_http2_only())
+            .serve(make_service);
+        let server_task = ServerTask::spawn(|graceful_shutdown_signal| {
+            server.with_graceful_shutdown(graceful_shutdown_signal)
+        });
+
+        let server_url: Url = UNIX_SOCKET_URL_ADDRESS.parse()?;
+        let transport_layer =
+            HttpTransportLayer::new(server_task, None, server_url, builder.http_client())
+                .with_unix_socket_file(unix_socket_file);
+
+        return Ok(Box::new(transport_layer));
+    }
+
+    let (socket_addr, tcp_listener, maybe_reserved_port) =
+        builder.tcp_listener_with_reserved_port()?;
+
+    #[cfg(feature = "tls")]
+    if let Some(tls_setup) = builder.maybe_tls_setup() {
+        let tls_incoming = TlsIncoming::new(tcp_listener, tls_setup.server_config.clone())?;
+        let server = HyperServer::builder(tls_incoming).serve(make_service);
+        let server_task = ServerTask::spawn(|graceful_shutdown_signal| {
+            server.with_graceful_shutdown(graceful_shutdown_signal)
+        });
+
+        let server_address = format!("https://{socket_addr}");
+        let server_url: Url = server_address.parse()?;
+
+        return Ok(Box::new(HttpTransportLayer::new(
+            server_task,
+            maybe_reserved_port,
+            server_url,
+            builder.http_client(),
+        )));
+    }
+
+    let maybe_local_address = tcp_listener.local_addr().ok();
+    let server_builder = AxumServer::from_tcp(tcp_listener)
+        .with_context(|| format!("Failed to create ::axum::Server for TestServer, with address '{maybe_local_address:?}'"))?;
+
+    let server = server_builder
+        .http2_only(builder.is_http2_only())
+        .serve(make_service);
+    let server_task = ServerTask::spawn(|graceful_shutdown_signal| {
+        server.with_graceful_shutdown(graceful_shutdown_signal)
+    });
+
+    let server_address = format!("http://{socket_addr}");
+    let server_url: Url = server_address.parse()?;
+
+    Ok(Box::new(HttpTransportLayer::new(
+        server_task,
+        maybe_reserved_port,
+        server_url,
+        builder.http_client(),
+    )))
+}
+
+/// Allows the same make service to be served over each kind of connection.
+struct IgnoreConnection<M>(M);
+
+impl<M, T> Service<T> for IgnoreConnection<M>
+where
+    M: Service<()>,
+{
+    type Response = M::Response;
+    type Error = M::Error;
+    type Future = M::Future;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, _connection: T) -> Self::Future {
+        self.0.call(())
+    }
+}
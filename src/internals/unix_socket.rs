@@ -0,0 +1,163 @@
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::http::Uri;
+use ::hyper::client::connect::Connected;
+use ::hyper::client::connect::Connection;
+use ::hyper::server::accept::Accept;
+use ::std::fs::remove_file;
+use ::std::future::Future;
+use ::std::io::Error as IoError;
+use ::std::path::Path;
+use ::std::path::PathBuf;
+use ::std::pin::Pin;
+use ::std::process;
+use ::std::sync::atomic::AtomicUsize;
+use ::std::sync::atomic::Ordering;
+use ::std::task::Context as TaskContext;
+use ::std::task::Poll;
+use ::std::time::SystemTime;
+use ::std::time::UNIX_EPOCH;
+use ::tokio::io::AsyncRead;
+use ::tokio::io::AsyncWrite;
+use ::tokio::io::ReadBuf;
+use ::tokio::net::UnixListener;
+use ::tokio::net::UnixStream;
+use ::tower::Service;
+
+/// The address requests are made against when using a Unix socket.
+///
+/// The host is never resolved, as all requests go to the socket file.
+pub const UNIX_SOCKET_URL_ADDRESS: &str = "http://localhost";
+
+static SOCKET_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns a path for a new socket file, within the temp directory.
+pub fn new_unix_socket_path() -> PathBuf {
+    let count = SOCKET_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos())
+        .unwrap_or_default();
+    let file_name = format!("axum-test-{}-{count}-{nanos}.sock", process::id());
+
+    ::std::env::temp_dir().join(file_name)
+}
+
+/// Owns the socket file the server is listening on,
+/// and deletes it when dropped.
+#[derive(Debug)]
+pub struct UnixSocketFile {
+    path: PathBuf,
+}
+
+impl Drop for UnixSocketFile {
+    fn drop(&mut self) {
+        let _ = remove_file(&self.path);
+    }
+}
+
+/// Accepts connections on a Unix socket, for the server to then serve.
+#[derive(Debug)]
+pub struct UnixIncoming {
+    unix_listener: UnixListener,
+}
+
+impl UnixIncoming {
+    pub fn bind(path: &Path) -> Result<(Self, UnixSocketFile)> {
+        let unix_listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to bind Unix socket for TestServer, at '{path:?}'"))?;
+        let socket_file = UnixSocketFile {
+            path: path.to_path_buf(),
+        };
+
+        Ok((Self { unix_listener }, socket_file))
+    }
+}
+
+impl Accept for UnixIncoming {
+    type Conn = UnixStream;
+    type Error = IoError;
+
+    fn poll_accept(
+        self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<Option<Result<Self::Conn, Self::Error>>> {
+        self.unix_listener
+            .poll_accept(cx)
+            .map(|result| Some(result.map(|(unix_stream, _)| unix_stream)))
+    }
+}
+
+/// A hyper connector, which sends every request to the same Unix socket.
+///
+/// The host in the request URI is ignored.
+#[derive(Debug, Clone)]
+pub struct UnixConnector {
+    path: PathBuf,
+}
+
+impl UnixConnector {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl Service<Uri> for UnixConnector {
+    type Response = UnixConnection;
+    type Error = IoError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let path = self.path.clone();
+
+        Box::pin(async move {
+            let unix_stream = UnixStream::connect(path).await?;
+            Ok(UnixConnection(unix_stream))
+        })
+    }
+}
+
+/// A client side connection to a Unix socket.
+#[derive(Debug)]
+pub struct UnixConnection(UnixStream);
+
+impl Connection for UnixConnection {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl AsyncRead for UnixConnection {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<(), IoError>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for UnixConnection {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize, IoError>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Result<(), IoError>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<Result<(), IoError>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
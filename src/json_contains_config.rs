@@ -0,0 +1,55 @@
+use crate::JsonContainsConfigBuilder;
+
+/// This is for customising how responses are compared when using
+/// [`TestResponse::assert_json_contains_with_config()`](crate::TestResponse::assert_json_contains_with_config()).
+///
+/// It implements [`Default`] to ease building configurations:
+///
+/// ```rust
+/// use ::axum_test::JsonContainsConfig;
+///
+/// let config = JsonContainsConfig {
+///     ignore_array_order: true,
+///     ..JsonContainsConfig::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsonContainsConfig {
+    /// Set for arrays to match when they hold the same items in any order.
+    ///
+    /// Arrays must always be the same length.
+    ///
+    /// **Defaults** to false, where items must be in the same order.
+    pub ignore_array_order: bool,
+
+    /// JSONPath expressions for values to leave out of the comparison,
+    /// such as generated ids and timestamps (i.e. `$.items[*].id`).
+    ///
+    /// The values are skipped in both the expected JSON and the response.
+    ///
+    /// **Defaults** to none.
+    pub ignored_paths: Vec<String>,
+
+    /// The largest difference allowed between two numbers
+    /// for them to be treated as equal.
+    ///
+    /// **Defaults** to `None`, where numbers must be exactly equal.
+    pub float_tolerance: Option<f64>,
+}
+
+impl JsonContainsConfig {
+    /// Creates a builder for making it simpler to creating configs.
+    ///
+    /// ```rust
+    /// use ::axum_test::JsonContainsConfig;
+    ///
+    /// let config = JsonContainsConfig::builder()
+    ///     .ignore_array_order()
+    ///     .ignore_path(&"$..created_at")
+    ///     .float_tolerance(0.001)
+    ///     .build();
+    /// ```
+    pub fn builder() -> JsonContainsConfigBuilder {
+        JsonContainsConfigBuilder::default()
+    }
+}
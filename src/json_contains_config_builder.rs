@@ -0,0 +1,79 @@
+use crate::JsonContainsConfig;
+
+/// This is for easing the building of [`JsonContainsConfig`](crate::JsonContainsConfig).
+///
+/// For full documentation see there.
+///
+/// ```rust
+/// use ::axum_test::JsonContainsConfig;
+///
+/// let config = JsonContainsConfig::builder()
+///     .ignore_array_order()
+///     .ignore_path(&"$.items[*].id")
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct JsonContainsConfigBuilder {
+    config: JsonContainsConfig,
+}
+
+impl JsonContainsConfigBuilder {
+    pub fn ignore_array_order(mut self) -> Self {
+        self.config.ignore_array_order = true;
+        self
+    }
+
+    pub fn ignore_path(mut self, path: &str) -> Self {
+        self.config.ignored_paths.push(path.to_string());
+        self
+    }
+
+    pub fn float_tolerance(mut self, tolerance: f64) -> Self {
+        self.config.float_tolerance = Some(tolerance);
+        self
+    }
+
+    pub fn build(self) -> JsonContainsConfig {
+        self.config
+    }
+}
+
+#[cfg(test)]
+mod test_build {
+    use super::*;
+
+    #[test]
+    fn it_should_build_default_config_by_default() {
+        let config = JsonContainsConfig::builder().build();
+        let expected = JsonContainsConfig::default();
+
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn it_should_ignore_array_order_when_set() {
+        let config = JsonContainsConfig::builder().ignore_array_order().build();
+
+        assert_eq!(config.ignore_array_order, true);
+    }
+
+    #[test]
+    fn it_should_add_ignored_paths_in_order() {
+        let config = JsonContainsConfig::builder()
+            .ignore_path(&"$.id")
+            .ignore_path(&"$..created_at")
+            .build();
+
+        assert_eq!(
+            config.ignored_paths,
+            vec!["$.id".to_string(), "$..created_at".to_string()]
+        );
+    }
+
+    #[test]
+    fn it_should_set_float_tolerance_when_set() {
+        let config = JsonContainsConfig::builder().float_tolerance(0.5).build();
+
+        assert_eq!(config.float_tolerance, Some(0.5));
+    }
+}
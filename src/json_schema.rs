@@ -0,0 +1,190 @@
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::serde_json::Value;
+use ::std::fs::read;
+use ::std::path::Path;
+use ::std::path::PathBuf;
+
+///
+/// A [JSON Schema](https://json-schema.org/draft/2020-12/json-schema-core),
+/// for validating response bodies using
+/// [`TestResponse::assert_json_schema()`](crate::TestResponse::assert_json_schema()).
+///
+/// Schemas are validated using draft 2020-12.
+/// They can be built from a `serde_json::Value`, or read from a file.
+///
+/// ```rust
+/// use ::axum_test::JsonSchema;
+/// use ::serde_json::json;
+///
+/// let schema = JsonSchema::new(json!({
+///     "type": "object",
+///     "required": ["id"],
+///     "properties": {
+///         "id": { "type": "integer" },
+///     },
+/// }));
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonSchema {
+    schema: Value,
+}
+
+impl JsonSchema {
+    /// Creates a schema from the JSON given.
+    pub fn new(schema: Value) -> Self {
+        Self { schema }
+    }
+
+    /// Creates a schema by reading the JSON file at the path given.
+    ///
+    /// This will panic if the file cannot be read, or is not JSON.
+    pub fn from_file<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let file_bytes = read(path)
+            .with_context(|| {
+                let debug_path = path.display();
+                format!("Reading JSON schema file, from {debug_path}")
+            })
+            .unwrap();
+        let schema = ::serde_json::from_slice::<Value>(&file_bytes)
+            .with_context(|| {
+                let debug_path = path.display();
+                format!("Parsing JSON schema file, from {debug_path}")
+            })
+            .unwrap();
+
+        Self::new(schema)
+    }
+
+    /// Returns the JSON of the schema.
+    #[must_use]
+    pub fn as_value(&self) -> &Value {
+        &self.schema
+    }
+
+    /// Validates the JSON given against this schema, returning every violation found.
+    ///
+    /// This errors if the schema itself is invalid.
+    pub(crate) fn violations(&self, instance: &Value) -> Result<Vec<String>> {
+        let validator = ::jsonschema::draft202012::new(&self.schema)
+            .context("Building JSON schema validator")?;
+
+        let violations = validator
+            .iter_errors(instance)
+            .map(|error| {
+                let instance_path = error.instance_path.as_str();
+                let instance_path = if instance_path.is_empty() {
+                    "(root)"
+                } else {
+                    instance_path
+                };
+
+                format!("{instance_path} {error}")
+            })
+            .collect();
+
+        Ok(violations)
+    }
+}
+
+impl From<Value> for JsonSchema {
+    fn from(schema: Value) -> Self {
+        Self::new(schema)
+    }
+}
+
+impl From<&Value> for JsonSchema {
+    fn from(schema: &Value) -> Self {
+        Self::new(schema.clone())
+    }
+}
+
+impl From<&Path> for JsonSchema {
+    fn from(path: &Path) -> Self {
+        Self::from_file(path)
+    }
+}
+
+impl From<PathBuf> for JsonSchema {
+    fn from(path: PathBuf) -> Self {
+        Self::from_file(path)
+    }
+}
+
+impl From<&PathBuf> for JsonSchema {
+    fn from(path: &PathBuf) -> Self {
+        Self::from_file(path)
+    }
+}
+
+#[cfg(test)]
+mod test_violations {
+    use super::*;
+    use ::serde_json::json;
+
+    fn user_schema() -> JsonSchema {
+        JsonSchema::new(json!({
+            "type": "object",
+            "required": ["id", "name"],
+            "properties": {
+                "id": { "type": "integer" },
+                "name": { "type": "string" },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                },
+            },
+        }))
+    }
+
+    #[test]
+    fn it_should_return_no_violations_for_valid_json() {
+        let violations = user_schema()
+            .violations(&json!({ "id": 1, "name": "Joe", "tags": ["a"] }))
+            .unwrap();
+
+        assert_eq!(violations, Vec::<String>::new());
+    }
+
+    #[test]
+    fn it_should_return_every_violation_with_instance_path() {
+        let violations = user_schema()
+            .violations(&json!({ "id": "abc", "tags": ["a", 2] }))
+            .unwrap();
+
+        assert_eq!(
+            violations,
+            vec![
+                r#"/id "abc" is not of type "integer""#,
+                r#"/tags/1 2 is not of type "string""#,
+                r#"(root) "name" is a required property"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_use_draft_2020_12() {
+        let schema = JsonSchema::new(json!({
+            "type": "array",
+            "prefixItems": [{ "type": "integer" }],
+        }));
+
+        let violations = schema.violations(&json!(["abc"])).unwrap();
+
+        assert_eq!(violations, vec![r#"/0 "abc" is not of type "integer""#]);
+    }
+
+    #[test]
+    fn it_should_error_for_invalid_schema() {
+        let schema = JsonSchema::new(json!({ "type": "not-a-type" }));
+
+        let result = schema.violations(&json!(1));
+
+        assert!(result.is_err());
+    }
+}
@@ -196,6 +196,52 @@ pub use self::test_request::*;
 mod test_response;
 pub use self::test_response::*;
 
+mod test_streaming_response;
+pub use self::test_streaming_response::*;
+
+mod test_sse_reader;
+pub use self::test_sse_reader::*;
+
+mod sse_event;
+pub use self::sse_event::*;
+
+mod auth_challenge;
+pub use self::auth_challenge::*;
+
+mod har_replay;
+pub use self::har_replay::*;
+
+mod har_replay_report;
+pub use self::har_replay_report::*;
+
+mod snapshot_config;
+pub use self::snapshot_config::*;
+
+mod snapshot_config_builder;
+pub use self::snapshot_config_builder::*;
+
+mod json_contains_config;
+pub use self::json_contains_config::*;
+
+mod json_contains_config_builder;
+pub use self::json_contains_config_builder::*;
+
+#[cfg(feature = "json-schema")]
+mod json_schema;
+#[cfg(feature = "json-schema")]
+pub use self::json_schema::*;
+
+#[cfg(feature = "ws")]
+mod test_web_socket;
+#[cfg(feature = "ws")]
+pub use self::test_web_socket::*;
+
+#[cfg(feature = "ws")]
+mod ws_message;
+#[cfg(feature = "ws")]
+pub use self::ws_message::*;
+
+pub mod multipart;
 pub mod transport_layer;
 pub mod util;
 
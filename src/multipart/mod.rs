@@ -0,0 +1,5 @@
+mod multipart_form;
+pub use self::multipart_form::*;
+
+mod part;
+pub use self::part::*;
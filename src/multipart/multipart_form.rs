@@ -0,0 +1,202 @@
+use ::bytes::BufMut;
+use ::bytes::Bytes;
+use ::bytes::BytesMut;
+use ::std::collections::hash_map::RandomState;
+use ::std::fmt::Display;
+use ::std::hash::BuildHasher;
+use ::std::hash::Hasher;
+use ::std::path::Path;
+
+use crate::multipart::Part;
+
+///
+/// A `multipart/form-data` body, for sending using
+/// [`TestRequest::multipart()`](crate::TestRequest::multipart()).
+///
+/// This is built up of named parts, which can be text, raw bytes, or files.
+/// It can be read using Axum's [`Multipart`](axum::extract::Multipart) extractor.
+///
+/// ```rust
+/// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+/// #
+/// use ::axum::routing::post;
+/// use ::axum::Router;
+///
+/// use ::axum_test::multipart::MultipartForm;
+/// use ::axum_test::multipart::Part;
+/// use ::axum_test::TestServer;
+///
+/// let app = Router::new().route(&"/upload", post(|| async { "uploaded!" }));
+/// let server = TestServer::new(app)?;
+///
+/// let readme = Part::bytes("# My Project".into())
+///     .file_name(&"README.md")
+///     .mime_type(&"text/markdown");
+///
+/// let form = MultipartForm::new()
+///     .add_text("name", "My Project")
+///     .add_part("readme", readme);
+///
+/// server.post(&"/upload").multipart(form).await;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct MultipartForm {
+    boundary: String,
+    parts: Vec<(String, Part)>,
+}
+
+impl MultipartForm {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a text part, with the name given.
+    pub fn add_text<T>(self, name: &str, text: T) -> Self
+    where
+        T: Display,
+    {
+        self.add_part(name, Part::text(text))
+    }
+
+    /// Adds a part read from a file on disk, with the name given.
+    ///
+    /// See [`Part::file()`](crate::multipart::Part::file()) for how the part is built.
+    /// This will panic if the file cannot be read.
+    pub fn add_file<P>(self, name: &str, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.add_part(name, Part::file(path))
+    }
+
+    /// Adds a part, with the name given.
+    pub fn add_part(mut self, name: &str, part: Part) -> Self {
+        self.parts.push((name.to_string(), part));
+        self
+    }
+
+    /// The content type to send this form with,
+    /// which includes the boundary between parts.
+    #[must_use]
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    pub(crate) fn into_bytes(self) -> Bytes {
+        let boundary = &self.boundary;
+        let mut body = BytesMut::new();
+
+        for (name, part) in self.parts {
+            body.put(format!("--{boundary}\r\n").as_bytes());
+
+            let name = escape_quoted(&name);
+            body.put(format!("Content-Disposition: form-data; name=\"{name}\"").as_bytes());
+            if let Some(file_name) = part.file_name {
+                let file_name = escape_quoted(&file_name);
+                body.put(format!("; filename=\"{file_name}\"").as_bytes());
+            }
+            body.put(&b"\r\n"[..]);
+
+            if let Some(mime_type) = part.mime_type {
+                body.put(format!("Content-Type: {mime_type}\r\n").as_bytes());
+            }
+
+            body.put(&b"\r\n"[..]);
+            body.put(part.bytes);
+            body.put(&b"\r\n"[..]);
+        }
+
+        body.put(format!("--{boundary}--\r\n").as_bytes());
+        body.freeze()
+    }
+}
+
+impl Default for MultipartForm {
+    fn default() -> Self {
+        Self {
+            boundary: new_boundary(),
+            parts: Vec::new(),
+        }
+    }
+}
+
+/// Builds a boundary which is random,
+/// so it is very unlikely to appear within the parts.
+fn new_boundary() -> String {
+    let random_number = RandomState::new().build_hasher().finish();
+
+    format!("axum-test-boundary-{random_number:016x}")
+}
+
+/// Escapes names for use within quotes,
+/// the same way browsers do.
+fn escape_quoted(name: &str) -> String {
+    name.replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+#[cfg(test)]
+mod test_into_bytes {
+    use super::*;
+
+    fn new_form(boundary: &str) -> MultipartForm {
+        MultipartForm {
+            boundary: boundary.to_string(),
+            parts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn it_should_encode_text_and_file_parts() {
+        let form = new_form("BOUNDARY").add_text("name", "Joe").add_part(
+            "avatar",
+            Part::bytes("abc".into())
+                .file_name("avatar.png")
+                .mime_type("image/png"),
+        );
+
+        let body = form.into_bytes();
+
+        assert_eq!(
+            body,
+            Bytes::from_static(
+                b"--BOUNDARY\r\n\
+                Content-Disposition: form-data; name=\"name\"\r\n\
+                \r\n\
+                Joe\r\n\
+                --BOUNDARY\r\n\
+                Content-Disposition: form-data; name=\"avatar\"; filename=\"avatar.png\"\r\n\
+                Content-Type: image/png\r\n\
+                \r\n\
+                abc\r\n\
+                --BOUNDARY--\r\n"
+            )
+        );
+    }
+
+    #[test]
+    fn it_should_escape_quotes_and_newlines_in_names() {
+        let form = new_form("BOUNDARY").add_part(
+            "my \"field\"",
+            Part::text("hello").file_name("line\r\nbreak.txt"),
+        );
+
+        let body = form.into_bytes();
+        let body_text = String::from_utf8_lossy(&body);
+
+        assert!(body_text.contains("name=\"my %22field%22\"; filename=\"line%0D%0Abreak.txt\"\r\n"));
+    }
+
+    #[test]
+    fn it_should_use_different_boundaries_for_each_form() {
+        let first = MultipartForm::new();
+        let second = MultipartForm::new();
+
+        assert_ne!(first.content_type(), second.content_type());
+    }
+}
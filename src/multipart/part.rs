@@ -0,0 +1,120 @@
+use ::anyhow::Context;
+use ::bytes::Bytes;
+use ::std::fmt::Display;
+use ::std::fs::read;
+use ::std::path::Path;
+
+/// The content type used for files read from disk,
+/// unless changed using [`Part::mime_type()`](crate::multipart::Part::mime_type()).
+pub const DEFAULT_FILE_MIME_TYPE: &str = "application/octet-stream";
+
+///
+/// A single part of a [`MultipartForm`](crate::multipart::MultipartForm).
+///
+/// This is for when a part needs a file name or content type,
+/// such as when uploading files.
+///
+/// ```rust
+/// use ::axum_test::multipart::MultipartForm;
+/// use ::axum_test::multipart::Part;
+///
+/// let avatar = Part::bytes(vec![0x89, 0x50, 0x4e, 0x47].into())
+///     .file_name(&"avatar.png")
+///     .mime_type(&"image/png");
+///
+/// let form = MultipartForm::new()
+///     .add_text("username", "Joe")
+///     .add_part("avatar", avatar);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub(crate) bytes: Bytes,
+    pub(crate) file_name: Option<String>,
+    pub(crate) mime_type: Option<String>,
+}
+
+impl Part {
+    /// Creates a part containing the text given.
+    pub fn text<T>(text: T) -> Self
+    where
+        T: Display,
+    {
+        let text = format!("{}", text);
+
+        Self::bytes(text.into())
+    }
+
+    /// Creates a part containing the raw bytes given.
+    pub fn bytes(bytes: Bytes) -> Self {
+        Self {
+            bytes,
+            file_name: None,
+            mime_type: None,
+        }
+    }
+
+    /// Creates a part by reading the file at the path given.
+    ///
+    /// The file name is set from the path,
+    /// and the content type is set to `application/octet-stream`.
+    /// Both can be changed afterwards.
+    ///
+    /// This will panic if the file cannot be read.
+    pub fn file<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let file_bytes = read(path)
+            .with_context(|| {
+                let debug_path = path.display();
+                format!("Reading file for multipart form, from {debug_path}")
+            })
+            .unwrap();
+
+        let part = Self::bytes(file_bytes.into()).mime_type(DEFAULT_FILE_MIME_TYPE);
+        match path.file_name() {
+            Some(file_name) => part.file_name(&file_name.to_string_lossy()),
+            None => part,
+        }
+    }
+
+    /// Sets the file name sent with this part.
+    pub fn file_name(mut self, file_name: &str) -> Self {
+        self.file_name = Some(file_name.to_string());
+        self
+    }
+
+    /// Sets the content type sent with this part.
+    pub fn mime_type(mut self, mime_type: &str) -> Self {
+        self.mime_type = Some(mime_type.to_string());
+        self
+    }
+}
+
+#[cfg(test)]
+mod test_file {
+    use super::*;
+
+    use ::std::env::temp_dir;
+    use ::std::fs::write;
+
+    #[test]
+    fn it_should_read_file_contents_and_name() {
+        let path = temp_dir().join("axum-test-part-file.txt");
+        write(&path, "hello!").unwrap();
+
+        let part = Part::file(&path);
+
+        assert_eq!(part.bytes, Bytes::from_static(b"hello!"));
+        assert_eq!(part.file_name.as_deref(), Some("axum-test-part-file.txt"));
+        assert_eq!(part.mime_type.as_deref(), Some(DEFAULT_FILE_MIME_TYPE));
+    }
+
+    #[test]
+    #[should_panic(expected = "Reading file for multipart form")]
+    fn it_should_panic_when_file_does_not_exist() {
+        let _ = Part::file("/this/file/does/not/exist.txt");
+    }
+}
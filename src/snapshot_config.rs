@@ -0,0 +1,70 @@
+use ::http::header::CONTENT_TYPE;
+use ::http::HeaderName;
+use ::std::path::PathBuf;
+
+use crate::SnapshotConfigBuilder;
+
+/// This is for customising what is stored when using
+/// [`TestResponse::assert_snapshot_with_config()`](crate::TestResponse::assert_snapshot_with_config()).
+///
+/// It implements [`Default`] to ease building configurations:
+///
+/// ```rust
+/// use ::axum_test::SnapshotConfig;
+///
+/// let config = SnapshotConfig {
+///     redactions: vec!["/id".to_string()],
+///     ..SnapshotConfig::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotConfig {
+    /// The response headers stored in the snapshot.
+    ///
+    /// Headers missing from the response are left out.
+    ///
+    /// **Defaults** to just `Content-Type`.
+    pub headers: Vec<HeaderName>,
+
+    /// JSON Pointers to values in the response body which change between runs,
+    /// such as ids and timestamps.
+    /// These are written to the snapshot as `"[redacted]"`.
+    ///
+    /// A `*` segment matches every field of an object, or every item of an array.
+    /// i.e. `/users/*/id` redacts the `id` of every user.
+    ///
+    /// **Defaults** to none.
+    pub redactions: Vec<String>,
+
+    /// The directory to store snapshots in.
+    ///
+    /// **Defaults** to `None`, which stores them in a `snapshots` directory
+    /// next to the source file of the test.
+    pub directory: Option<PathBuf>,
+}
+
+impl SnapshotConfig {
+    /// Creates a builder for making it simpler to creating configs.
+    ///
+    /// ```rust
+    /// use ::axum_test::SnapshotConfig;
+    ///
+    /// let config = SnapshotConfig::builder()
+    ///     .redact(&"/id")
+    ///     .redact(&"/users/*/created_at")
+    ///     .build();
+    /// ```
+    pub fn builder() -> SnapshotConfigBuilder {
+        SnapshotConfigBuilder::default()
+    }
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            headers: vec![CONTENT_TYPE],
+            redactions: vec![],
+            directory: None,
+        }
+    }
+}
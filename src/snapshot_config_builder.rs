@@ -0,0 +1,107 @@
+use ::http::HeaderName;
+use ::std::path::PathBuf;
+
+use crate::SnapshotConfig;
+
+/// This is for easing the building of [`SnapshotConfig`](crate::SnapshotConfig).
+///
+/// For full documentation see there.
+///
+/// ```rust
+/// use ::axum_test::SnapshotConfig;
+/// use ::axum_test::http::header::ETAG;
+///
+/// let config = SnapshotConfig::builder()
+///     .header(ETAG)
+///     .redact(&"/id")
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotConfigBuilder {
+    config: SnapshotConfig,
+}
+
+impl SnapshotConfigBuilder {
+    /// Adds a header to be stored, alongside those already chosen.
+    pub fn header(mut self, header_name: HeaderName) -> Self {
+        self.config.headers.push(header_name);
+        self
+    }
+
+    /// Stops all headers from being stored, including `Content-Type`.
+    pub fn without_headers(mut self) -> Self {
+        self.config.headers.clear();
+        self
+    }
+
+    pub fn redact(mut self, selector: &str) -> Self {
+        self.config.redactions.push(selector.to_string());
+        self
+    }
+
+    pub fn directory<P>(mut self, directory: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.config.directory = Some(directory.into());
+        self
+    }
+
+    pub fn build(self) -> SnapshotConfig {
+        self.config
+    }
+}
+
+#[cfg(test)]
+mod test_build {
+    use super::*;
+    use ::http::header::CONTENT_TYPE;
+    use ::http::header::ETAG;
+
+    #[test]
+    fn it_should_build_default_config_by_default() {
+        let config = SnapshotConfig::builder().build();
+        let expected = SnapshotConfig::default();
+
+        assert_eq!(config, expected);
+    }
+
+    #[test]
+    fn it_should_add_headers_after_content_type() {
+        let config = SnapshotConfig::builder().header(ETAG).build();
+
+        assert_eq!(config.headers, vec![CONTENT_TYPE, ETAG]);
+    }
+
+    #[test]
+    fn it_should_remove_all_headers_when_set() {
+        let config = SnapshotConfig::builder()
+            .without_headers()
+            .header(ETAG)
+            .build();
+
+        assert_eq!(config.headers, vec![ETAG]);
+    }
+
+    #[test]
+    fn it_should_add_redactions_in_order() {
+        let config = SnapshotConfig::builder()
+            .redact(&"/id")
+            .redact(&"/users/*/id")
+            .build();
+
+        assert_eq!(
+            config.redactions,
+            vec!["/id".to_string(), "/users/*/id".to_string()]
+        );
+    }
+
+    #[test]
+    fn it_should_set_directory_when_set() {
+        let config = SnapshotConfig::builder()
+            .directory(&"tests/snapshots")
+            .build();
+
+        assert_eq!(config.directory, Some(PathBuf::from("tests/snapshots")));
+    }
+}
@@ -0,0 +1,163 @@
+use ::anyhow::Context;
+use ::serde::de::DeserializeOwned;
+use ::std::fmt::Debug;
+use ::std::time::Duration;
+
+#[cfg(feature = "pretty-assertions")]
+use ::pretty_assertions::assert_eq;
+
+/// The event type used when an event does not set one.
+pub const DEFAULT_SSE_EVENT_TYPE: &str = "message";
+
+///
+/// A single Server-Sent Event, received from a `text/event-stream` response.
+///
+/// These are read using a [`TestSseReader`](crate::TestSseReader),
+/// or all at once using [`TestResponse::sse_events()`](crate::TestResponse::sse_events()).
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    event: Option<String>,
+    data: String,
+    id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl SseEvent {
+    pub(crate) fn new(
+        event: Option<String>,
+        data: String,
+        id: Option<String>,
+        retry: Option<Duration>,
+    ) -> Self {
+        Self {
+            event,
+            data,
+            id,
+            retry,
+        }
+    }
+
+    /// The type of the event, from the `event:` field.
+    ///
+    /// This is `"message"` when the event does not set one.
+    #[must_use]
+    pub fn event(&self) -> &str {
+        self.event.as_deref().unwrap_or(DEFAULT_SSE_EVENT_TYPE)
+    }
+
+    /// The data of the event.
+    /// When sent over multiple `data:` fields, these are joined with a newline.
+    #[must_use]
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// The `id:` set on this event, if it has one.
+    #[must_use]
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// The `retry:` set on this event, if it has one.
+    #[must_use]
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+
+    /// Reads the data of the event as Json,
+    /// and deserializes it into the type given.
+    ///
+    /// If the data cannot be deserialized, then this will panic.
+    #[must_use]
+    pub fn json<T>(&self) -> T
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_str::<T>(&self.data)
+            .with_context(|| {
+                let event = self.event();
+                let data = &self.data;
+
+                format!("Deserializing data of SSE event '{event}' as Json, received: {data}")
+            })
+            .unwrap()
+    }
+
+    /// Assert the event type matches the one given.
+    #[track_caller]
+    pub fn assert_event<C>(&self, expected: C)
+    where
+        C: AsRef<str>,
+    {
+        assert_eq!(expected.as_ref(), self.event());
+    }
+
+    /// Assert the data of the event matches the text given.
+    #[track_caller]
+    pub fn assert_data<C>(&self, expected: C)
+    where
+        C: AsRef<str>,
+    {
+        assert_eq!(expected.as_ref(), self.data());
+    }
+
+    /// Deserializes the data of the event as Json,
+    /// and asserts it matches the value given.
+    #[track_caller]
+    pub fn assert_json<T>(&self, expected: &T)
+    where
+        T: DeserializeOwned + PartialEq<T> + Debug,
+    {
+        assert_eq!(*expected, self.json::<T>());
+    }
+}
+
+#[cfg(test)]
+mod test_json {
+    use crate::SseEvent;
+
+    use ::serde::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Progress {
+        percent: u32,
+    }
+
+    #[test]
+    fn it_should_deserialize_data_as_json() {
+        let event = SseEvent::new(None, r#"{"percent":50}"#.to_string(), None, None);
+
+        assert_eq!(event.json::<Progress>(), Progress { percent: 50 });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Deserializing data of SSE event 'message' as Json, received: not json"
+    )]
+    fn it_should_panic_when_data_is_not_json() {
+        let event = SseEvent::new(None, "not json".to_string(), None, None);
+
+        let _ = event.json::<Progress>();
+    }
+}
+
+#[cfg(test)]
+mod test_assert_event {
+    use crate::SseEvent;
+
+    #[test]
+    fn it_should_pass_when_event_type_matches() {
+        let event = SseEvent::new(Some("update".to_string()), "".to_string(), None, None);
+
+        event.assert_event(&"update");
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_should_panic_when_event_type_differs() {
+        let event = SseEvent::new(None, "".to_string(), None, None);
+
+        event.assert_event(&"update");
+    }
+}
@@ -3,6 +3,7 @@ use ::anyhow::Context;
 use ::anyhow::Error as AnyhowError;
 use ::anyhow::Result;
 use ::auto_future::AutoFuture;
+use ::axum::body::BoxBody;
 use ::bytes::Bytes;
 use ::cookie::Cookie;
 use ::cookie::CookieJar;
@@ -11,24 +12,55 @@ use ::http::header::SET_COOKIE;
 use ::http::HeaderName;
 use ::http::HeaderValue;
 use ::http::Request;
+use ::http::Response;
+#[cfg(feature = "ws")]
+use ::http::StatusCode;
 use ::hyper::body::Body;
 use ::serde::Serialize;
 use ::serde_json::to_vec as json_to_vec;
 use ::serde_urlencoded::to_string;
+#[cfg(feature = "json-schema")]
+use ::std::cmp::Reverse;
 use ::std::convert::AsRef;
 use ::std::fmt::Debug;
 use ::std::fmt::Display;
+use ::std::future::Future;
 use ::std::future::IntoFuture;
+use ::std::net::SocketAddr;
 use ::std::sync::Arc;
 use ::std::sync::Mutex;
+use ::std::time::Duration;
+use ::std::time::Instant;
+use ::std::time::SystemTime;
+use ::tokio::time::timeout_at;
+use ::tokio::time::Instant as TokioInstant;
 use ::url::Url;
 
+#[cfg(feature = "ws")]
+use ::tokio_tungstenite::tungstenite::handshake::client::generate_key;
+#[cfg(feature = "ws")]
+use ::tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+
+use crate::internals::basic_authorization_header;
+use crate::internals::bearer_authorization_header;
+use crate::internals::CookieStore;
 use crate::internals::ExpectedState;
+use crate::internals::HarEntry;
+use crate::internals::HarRequest;
+use crate::internals::HarResponse;
+#[cfg(feature = "tracing")]
+use crate::internals::LogCapture;
 use crate::internals::QueryParamsStore;
+use crate::internals::Redirect;
 use crate::internals::RequestPathFormatter;
+use crate::multipart::MultipartForm;
+use crate::transport_layer::MockConnection;
 use crate::transport_layer::TransportLayer;
 use crate::ServerSharedState;
 use crate::TestResponse;
+use crate::TestStreamingResponse;
+#[cfg(feature = "ws")]
+use crate::TestWebSocket;
 
 pub(crate) use self::test_request_config::*;
 mod test_request_config;
@@ -36,6 +68,7 @@ mod test_request_config;
 const JSON_CONTENT_TYPE: &'static str = &"application/json";
 const FORM_CONTENT_TYPE: &'static str = &"application/x-www-form-urlencoded";
 const TEXT_CONTENT_TYPE: &'static str = &"text/plain";
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
 
 ///
 /// A `TestRequest` is for building and executing a HTTP request to the [`TestServer`](crate::TestServer).
@@ -97,12 +130,15 @@ pub struct TestRequest {
     config: TestRequestConfig,
 
     server_state: Arc<Mutex<ServerSharedState>>,
-    transport: Arc<Mutex<Box<dyn TransportLayer>>>,
+    transport: Arc<Box<dyn TransportLayer>>,
 
-    body: Option<Body>,
+    body: Option<Bytes>,
     headers: Vec<(HeaderName, HeaderValue)>,
     cookies: CookieJar,
+    added_cookies: CookieJar,
+    is_using_server_cookies: bool,
     query_params: QueryParamsStore,
+    maybe_client_address: Option<SocketAddr>,
 
     expected_state: ExpectedState,
 }
@@ -110,7 +146,7 @@ pub struct TestRequest {
 impl TestRequest {
     pub(crate) fn new(
         server_state: Arc<Mutex<ServerSharedState>>,
-        transport: Arc<Mutex<Box<dyn TransportLayer>>>,
+        transport: Arc<Box<dyn TransportLayer>>,
         config: TestRequestConfig,
     ) -> Result<Self> {
         let expected_state = config.expected_state;
@@ -121,12 +157,14 @@ impl TestRequest {
             )
         })?;
 
-        let cookies = server_locked.cookies().clone();
+        let cookies = server_locked.cookies_for_url(&config.full_request_url);
         let query_params = server_locked.query_params().clone();
         let headers = server_locked.headers().clone();
 
         ::std::mem::drop(server_locked);
 
+        let maybe_client_address = config.client_address;
+
         Ok(Self {
             config,
             server_state,
@@ -134,7 +172,10 @@ impl TestRequest {
             body: None,
             headers,
             cookies,
+            added_cookies: CookieJar::new(),
+            is_using_server_cookies: true,
             query_params,
+            maybe_client_address,
             expected_state,
         })
     }
@@ -162,6 +203,17 @@ impl TestRequest {
         self.bytes(body_text.into()).content_type(FORM_CONTENT_TYPE)
     }
 
+    /// Sets the body of the request to the multipart form given,
+    /// and sets the content type to `multipart/form-data`, with the form's boundary.
+    ///
+    /// See [`MultipartForm`](crate::multipart::MultipartForm) for building the form.
+    pub fn multipart(self, multipart: MultipartForm) -> Self {
+        let content_type = multipart.content_type();
+        let body_bytes = multipart.into_bytes();
+
+        self.bytes(body_bytes).content_type(&content_type)
+    }
+
     /// Set raw text as the body of the request,
     /// and sets the content type to `text/plain`.
     pub fn text<T>(self, raw_text: T) -> Self
@@ -177,9 +229,7 @@ impl TestRequest {
     ///
     /// The content type is left unchanged.
     pub fn bytes(mut self, body_bytes: Bytes) -> Self {
-        let body: Body = body_bytes.into();
-
-        self.body = Some(body);
+        self.body = Some(body_bytes);
         self
     }
 
@@ -191,7 +241,9 @@ impl TestRequest {
 
     /// Adds a Cookie to be sent with this request.
     pub fn add_cookie<'c>(mut self, cookie: Cookie<'c>) -> Self {
-        self.cookies.add(cookie.into_owned());
+        let cookie = cookie.into_owned();
+        self.cookies.add(cookie.clone());
+        self.added_cookies.add(cookie);
         self
     }
 
@@ -199,6 +251,7 @@ impl TestRequest {
     pub fn add_cookies(mut self, cookies: CookieJar) -> Self {
         for cookie in cookies.iter() {
             self.cookies.add(cookie.clone());
+            self.added_cookies.add(cookie.clone());
         }
 
         self
@@ -208,6 +261,8 @@ impl TestRequest {
     /// including any that came from the `TestServer`.
     pub fn clear_cookies(mut self) -> Self {
         self.cookies = CookieJar::new();
+        self.added_cookies = CookieJar::new();
+        self.is_using_server_cookies = false;
         self
     }
 
@@ -228,6 +283,36 @@ impl TestRequest {
         self
     }
 
+    /// Redirects returned will be followed,
+    /// with the response at the end of the redirects returned.
+    ///
+    /// The method and body are changed for each redirect as browsers do.
+    /// A `POST` redirected by a 301 or 302, or any request redirected by a 303,
+    /// is sent on as a `GET` without a body.
+    /// Cookies are carried between each redirect.
+    ///
+    /// When redirected to a different origin, the `Authorization`, `Proxy-Authorization`,
+    /// and `Cookie` headers are no longer sent, and neither are the cookies from the `TestServer`
+    /// or those added to this request. This applies to the rest of the redirects followed.
+    ///
+    /// The responses along the way are available using
+    /// [`TestResponse::redirect_history()`](crate::TestResponse::redirect_history()).
+    /// This will fail if there are more redirects than the `max_redirects`
+    /// set on the [`TestServerConfig`](crate::TestServerConfig).
+    pub fn follow_redirects(mut self) -> Self {
+        self.config.is_following_redirects = true;
+        self
+    }
+
+    /// Redirects returned will not be followed,
+    /// with the redirect itself returned as the response.
+    ///
+    /// This is the default, unless `follow_redirects` is set on the [`TestServerConfig`](crate::TestServerConfig).
+    pub fn do_not_follow_redirects(mut self) -> Self {
+        self.config.is_following_redirects = false;
+        self
+    }
+
     /// Adds query parameters to be sent with this request.
     pub fn add_query_param<V>(self, key: &str, value: V) -> Self
     where
@@ -341,12 +426,119 @@ impl TestRequest {
         self
     }
 
+    /// Sets the `Last-Event-ID` header, replacing any already set.
+    ///
+    /// This is used when reconnecting to a stream of Server-Sent Events,
+    /// to resume from the last event received.
+    /// See [`TestSseReader::last_event_id()`](crate::TestSseReader::last_event_id()).
+    pub fn last_event_id(mut self, last_event_id: &str) -> Self {
+        let header_value = HeaderValue::from_str(last_event_id)
+            .with_context(|| {
+                let request_format = &self.config.request_format;
+                format!("Invalid Last-Event-ID '{last_event_id}', for request {request_format}")
+            })
+            .unwrap();
+
+        self.headers
+            .retain(|(header_name, _)| header_name != LAST_EVENT_ID_HEADER);
+        self.headers
+            .push((HeaderName::from_static(LAST_EVENT_ID_HEADER), header_value));
+        self
+    }
+
+    /// Sets the `Authorization` header to use the bearer token given,
+    /// replacing any already set.
+    ///
+    /// This includes an `Authorization` header set on the [`TestServer`](crate::TestServer).
+    pub fn authorization_bearer<T>(self, token: T) -> Self
+    where
+        T: Display,
+    {
+        let header_value = bearer_authorization_header(token)
+            .with_context(|| {
+                let request_format = &self.config.request_format;
+                format!("Trying to set bearer authorization, for request {request_format}")
+            })
+            .unwrap();
+
+        self.set_authorization(header_value)
+    }
+
+    /// Sets the `Authorization` header to use HTTP Basic authentication,
+    /// with the username and password given.
+    /// This replaces any `Authorization` header already set.
+    pub fn authorization_basic<U, P>(self, username: U, password: P) -> Self
+    where
+        U: Display,
+        P: Display,
+    {
+        let header_value = basic_authorization_header(username, password)
+            .with_context(|| {
+                let request_format = &self.config.request_format;
+                format!("Trying to set basic authorization, for request {request_format}")
+            })
+            .unwrap();
+
+        self.set_authorization(header_value)
+    }
+
+    fn set_authorization(mut self, header_value: HeaderValue) -> Self {
+        self.headers
+            .retain(|(header_name, _)| header_name != header::AUTHORIZATION);
+        self.headers.push((header::AUTHORIZATION, header_value));
+        self
+    }
+
     /// Clears all headers set.
     pub fn clear_headers(mut self) -> Self {
         self.headers = vec![];
         self
     }
 
+    /// Sets the address the request appears to come from,
+    /// when read using [`axum::extract::ConnectInfo`].
+    ///
+    /// This only applies to the [`Transport::MockHttp`](crate::Transport::MockHttp) transport.
+    /// Over a real transport, the address of the real connection is used.
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::extract::ConnectInfo;
+    /// use ::axum::routing::get;
+    /// use ::axum::Router;
+    /// use ::std::net::SocketAddr;
+    ///
+    /// use ::axum_test::TestServer;
+    /// use ::axum_test::TestServerConfig;
+    /// use ::axum_test::Transport;
+    ///
+    /// let app = Router::new()
+    ///     .route(&"/ip", get(|ConnectInfo(addr): ConnectInfo<SocketAddr>| async move {
+    ///         addr.ip().to_string()
+    ///     }))
+    ///     .into_make_service_with_connect_info::<SocketAddr>();
+    ///
+    /// let config = TestServerConfig {
+    ///     transport: Some(Transport::MockHttp),
+    ///     ..TestServerConfig::default()
+    /// };
+    /// let server = TestServer::new_with_config(app, config)?;
+    ///
+    /// let client_address: SocketAddr = "203.0.113.7:4321".parse()?;
+    /// server.get(&"/ip")
+    ///     .client_address(client_address)
+    ///     .await
+    ///     .assert_text(&"203.0.113.7");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn client_address(mut self, client_address: SocketAddr) -> Self {
+        self.maybe_client_address = Some(client_address);
+        self
+    }
+
     /// Marks that this request is expected to always return a HTTP
     /// status code within the 2xx range (200 to 299).
     ///
@@ -354,6 +546,7 @@ impl TestRequest {
     /// then this will panic.
     ///
     /// ```rust
+    /// # #![allow(dependency_on_unit_never_type_fallback)]
     /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
     /// #
     /// use ::axum::Json;
@@ -396,37 +589,302 @@ impl TestRequest {
         self
     }
 
+    /// Sets how long to wait for the response,
+    /// replacing any default timeout set on the [`TestServerConfig`](crate::TestServerConfig).
+    ///
+    /// If the response takes longer, then the request will panic,
+    /// rather than leaving the test waiting forever.
+    /// This includes reading the response body,
+    /// and when following redirects, it covers all of them together.
+    /// For [`TestRequest::stream()`](crate::TestRequest::stream()) this only covers receiving the response head.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends the request, and returns the response as soon as its head arrives.
+    /// The body can then be read chunk by chunk, as it is streamed.
+    ///
+    /// This is for testing endpoints that stream, or never finish.
+    /// See [`TestStreamingResponse`](crate::TestStreamingResponse) for more.
+    ///
+    /// Expecting success or failure, and saving cookies,
+    /// are all applied to the response head.
+    pub async fn stream(self) -> TestStreamingResponse {
+        self.send_streaming()
+            .await
+            .context("Sending request failed")
+            .unwrap()
+    }
+
+    /// Sends the request as a WebSocket upgrade,
+    /// and returns the connection for sending and receiving messages.
+    ///
+    /// This needs the `TestServer` to be running with a HTTP transport,
+    /// and will panic when using the mock transport.
+    /// See [`TestWebSocket`](crate::TestWebSocket) for more.
+    ///
+    /// Expecting success or failure is ignored,
+    /// as the upgrade is always expected to return `101 Switching Protocols`.
+    #[cfg(feature = "ws")]
+    pub async fn into_websocket(self) -> TestWebSocket {
+        self.send_websocket()
+            .await
+            .context("Upgrading to WebSocket failed")
+            .unwrap()
+    }
+
     async fn send(mut self) -> Result<TestResponse> {
         let expected_state = self.expected_state;
-        let save_cookies = self.config.is_saving_cookies;
-        let body = self.body.unwrap_or(Body::empty());
-        let request_format = self.config.request_format;
+        let is_following_redirects = self.config.is_following_redirects;
+        let max_redirects = self.config.max_redirects;
+        let original_request_format = self.config.request_format.clone();
+
+        let mut request_format = self.config.request_format.clone();
+        let mut url =
+            Self::build_url_query_params(self.config.full_request_url.clone(), &self.query_params);
+        let mut body_bytes = self.body.take().unwrap_or_default();
+        let mut content_type = self.config.content_type.take();
+        let mut cookies = ::std::mem::take(&mut self.cookies);
+
+        // Cookies set by the redirects themselves, which are carried along like a browser.
+        let mut redirect_cookies = CookieStore::new();
+        let original_origin = url.origin();
+        let mut is_cross_origin = false;
+
+        // The timeout covers every redirect, rather than each one on its own.
+        let timeout_started_at = Instant::now();
+        let mut redirect_history = Vec::new();
+        let response = loop {
+            let method = request_format.method().clone();
+            let response = self
+                .send_once(
+                    request_format,
+                    url,
+                    body_bytes.clone(),
+                    content_type.clone(),
+                    cookies,
+                    timeout_started_at,
+                )
+                .await?;
+
+            if !is_following_redirects {
+                break response;
+            }
+
+            let response_url = response.request_url();
+            let maybe_redirect = Redirect::from_response(
+                &method,
+                &response_url,
+                response.status_code(),
+                response.headers(),
+            )
+            .with_context(|| {
+                format!("Following redirect, for request {original_request_format}")
+            })?;
+            let Some(redirect) = maybe_redirect else {
+                break response;
+            };
+
+            if redirect_history.len() >= max_redirects {
+                return Err(anyhow!(
+                    "Exceeded the maximum of {max_redirects} redirects, for request {original_request_format}"
+                ));
+            }
+
+            let now = ServerSharedState::cookie_clock(&mut self.server_state)?;
+            let set_cookies = response
+                .iter_headers_by_name(SET_COOKIE)
+                .filter_map(|header| header.to_str().ok())
+                .filter_map(|header| Cookie::parse(header.to_string()).ok());
+            for cookie in set_cookies {
+                redirect_cookies.add_from_response(cookie, &response_url, now);
+            }
+
+            // Credentials are only sent to the origin they were given for.
+            // Once redirected elsewhere, they are dropped for the rest of the redirects.
+            if redirect.url.origin() != original_origin {
+                is_cross_origin = true;
+            }
+            if is_cross_origin {
+                self.headers
+                    .retain(|(header_name, _)| !is_credentials_header(header_name));
+            }
+
+            cookies = CookieJar::new();
+            if !is_cross_origin {
+                if self.is_using_server_cookies {
+                    let server_cookies = ServerSharedState::find_cookies_for_url(
+                        &mut self.server_state,
+                        &redirect.url,
+                    )?;
+                    for cookie in server_cookies.iter() {
+                        cookies.add(cookie.clone());
+                    }
+                }
+                for cookie in self.added_cookies.iter() {
+                    cookies.add(cookie.clone());
+                }
+            }
+            for cookie in redirect_cookies.cookies_for_url(&redirect.url, now).iter() {
+                cookies.add(cookie.clone());
+            }
+
+            if !redirect.is_keeping_body {
+                body_bytes = Bytes::new();
+                content_type = None;
+                self.headers
+                    .retain(|(header_name, _)| *header_name != header::CONTENT_TYPE);
+            }
+
+            let redirect_path = match redirect.url.query() {
+                Some(query) => format!("{}?{query}", redirect.url.path()),
+                None => redirect.url.path().to_string(),
+            };
+            request_format = RequestPathFormatter::new(redirect.method, redirect_path);
+            url = redirect.url;
+            redirect_history.push(response);
+        };
+        let response = response.with_redirect_history(redirect_history);
 
-        let url = Self::build_url_query_params(self.config.full_request_url, &self.query_params);
+        // Assert if ok or not.
+        match expected_state {
+            ExpectedState::Success => response.assert_status_success(),
+            ExpectedState::Failure => response.assert_status_failure(),
+            ExpectedState::None => {}
+        }
+
+        #[cfg(feature = "json-schema")]
+        if response.status_code().is_success() && is_json_response(&response) {
+            let method = response.request_method();
+            // The most specific route wins, and then the first added.
+            let maybe_route_schema = self
+                .config
+                .route_json_schemas
+                .iter()
+                .filter(|(route, _)| route.is_match(method, response.request_url().path()))
+                .min_by_key(|(route, _)| Reverse(route.specificity()));
+
+            if let Some((route, schema)) = maybe_route_schema {
+                response.assert_json_schema_for_route(schema, Some(route));
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Sends a single request, without following redirects.
+    ///
+    /// The timeout is counted from `timeout_started_at`,
+    /// so it can be shared across redirects.
+    async fn send_once(
+        &mut self,
+        request_format: RequestPathFormatter,
+        url: Url,
+        body_bytes: Bytes,
+        content_type: Option<String>,
+        cookies: CookieJar,
+        timeout_started_at: Instant,
+    ) -> Result<TestResponse> {
         let request = Self::build_request(
             &request_format,
             &url,
-            body,
-            self.config.content_type,
-            self.cookies,
-            self.headers,
+            body_bytes.clone().into(),
+            content_type,
+            cookies,
+            self.headers.clone(),
+            self.maybe_client_address,
         )?;
 
-        let (parts, response_bytes) = {
-            let mut transport_locked = self.transport.as_ref().lock().map_err(|err| {
-                anyhow!(
-                    "Expect Response to succeed, for request {request_format}, received {err:?}"
-                )
-            })?;
-            transport_locked.send(request).await?
+        let maybe_har_request = self.config.is_recording_har.then(|| {
+            HarRequest::new(
+                request.method(),
+                &url,
+                request.version(),
+                request.headers(),
+                &body_bytes,
+            )
+        });
+        let started_at = SystemTime::now();
+        let started_instant = Instant::now();
+        #[cfg(feature = "openapi")]
+        let maybe_openapi_request_headers = self
+            .config
+            .maybe_openapi_contract
+            .as_ref()
+            .map(|_| request.headers().clone());
+
+        // Only mock transport handles the request within this future,
+        // so only it can have its events captured.
+        #[cfg(feature = "tracing")]
+        let maybe_log_capture =
+            (self.config.is_capturing_logs && self.transport.is_mock()).then(LogCapture::new);
+
+        let send_request = async {
+            let sending = self.transport.send(request);
+            #[cfg(feature = "tracing")]
+            let sending = LogCapture::capture_future(maybe_log_capture.as_ref(), sending);
+
+            sending.await.with_context(|| {
+                format!("Expect Response to succeed, for request {request_format}")
+            })
         };
+        let send_result = within_timeout(
+            self.config.timeout,
+            timeout_started_at,
+            &request_format,
+            send_request,
+        )
+        .await;
+
+        // Failed requests are recorded too, so timeouts and transport errors show up in the HAR.
+        if let Some(har_request) = maybe_har_request {
+            let har_response = match &send_result {
+                Ok((parts, response_bytes)) => HarResponse::new(parts, response_bytes),
+                Err(err) => HarResponse::from_error(err),
+            };
+            let har_entry = HarEntry::new(
+                started_at,
+                started_instant.elapsed(),
+                har_request,
+                har_response,
+            );
+            ServerSharedState::add_har_entry(&mut self.server_state, har_entry)?;
+        }
 
-        if save_cookies {
+        let (parts, response_bytes) = send_result?;
+
+        if self.config.is_saving_cookies {
             let cookie_headers = parts.headers.get_all(SET_COOKIE).into_iter();
-            ServerSharedState::add_cookies_by_header(&mut self.server_state, cookie_headers)?;
+            ServerSharedState::add_cookies_by_header(&mut self.server_state, &url, cookie_headers)?;
         }
 
         let response = TestResponse::new(request_format, url, parts, response_bytes);
+        #[cfg(feature = "tracing")]
+        let response = match maybe_log_capture {
+            Some(log_capture) => response.with_logs(log_capture.logs()),
+            None => response,
+        };
+
+        #[cfg(feature = "openapi")]
+        if let (Some(contract), Some(request_headers)) = (
+            &self.config.maybe_openapi_contract,
+            &maybe_openapi_request_headers,
+        ) {
+            let method = response.request_method().clone();
+            response.assert_openapi_contract(contract, &method, request_headers, &body_bytes);
+        }
+
+        Ok(response)
+    }
+
+    async fn send_streaming(self) -> Result<TestStreamingResponse> {
+        let expected_state = self.expected_state;
+        let request_format = self.config.request_format.clone();
+
+        let (url, response) = self.send_for_response().await?;
+        let (parts, response_body) = response.into_parts();
+        let response = TestStreamingResponse::new(request_format, url, parts, response_body);
 
         // Assert if ok or not.
         match expected_state {
@@ -438,6 +896,95 @@ impl TestRequest {
         Ok(response)
     }
 
+    /// Sends the request, and returns once the response head has arrived.
+    /// Cookies are saved from the response, if saving is turned on.
+    async fn send_for_response(mut self) -> Result<(Url, Response<BoxBody>)> {
+        let timeout_started_at = Instant::now();
+        let save_cookies = self.config.is_saving_cookies;
+        let body = self.body.unwrap_or_default();
+        let request_format = self.config.request_format;
+
+        let url = Self::build_url_query_params(self.config.full_request_url, &self.query_params);
+        let request = Self::build_request(
+            &request_format,
+            &url,
+            body.into(),
+            self.config.content_type,
+            self.cookies,
+            self.headers,
+            self.maybe_client_address,
+        )?;
+
+        let send_request = async {
+            self.transport
+                .send_streaming(request)
+                .await
+                .with_context(|| {
+                    format!("Expect Response to succeed, for request {request_format}")
+                })
+        };
+        let response = within_timeout(
+            self.config.timeout,
+            timeout_started_at,
+            &request_format,
+            send_request,
+        )
+        .await?;
+
+        if save_cookies {
+            let cookie_headers = response.headers().get_all(SET_COOKIE).into_iter();
+            ServerSharedState::add_cookies_by_header(&mut self.server_state, &url, cookie_headers)?;
+        }
+
+        Ok((url, response))
+    }
+
+    #[cfg(feature = "ws")]
+    async fn send_websocket(self) -> Result<TestWebSocket> {
+        let request_format = self.config.request_format.clone();
+        if self.transport.is_mock() {
+            return Err(anyhow!("WebSocket requires a HTTP transport, such as Transport::HttpRandomPort, but the TestServer is using mock transport, for request {request_format}"));
+        }
+
+        let websocket_key = generate_key();
+        let request = self
+            .add_header(header::CONNECTION, HeaderValue::from_static("upgrade"))
+            .add_header(header::UPGRADE, HeaderValue::from_static("websocket"))
+            .add_header(
+                header::SEC_WEBSOCKET_VERSION,
+                HeaderValue::from_static("13"),
+            )
+            .add_header(
+                header::SEC_WEBSOCKET_KEY,
+                HeaderValue::from_str(&websocket_key)?,
+            );
+
+        let (url, mut response) = request.send_for_response().await?;
+        let on_upgrade = ::hyper::upgrade::on(&mut response);
+        let (parts, _) = response.into_parts();
+        let upgrade_response = TestResponse::new(request_format.clone(), url, parts, Bytes::new());
+
+        let status_code = upgrade_response.status_code();
+        if status_code != StatusCode::SWITCHING_PROTOCOLS {
+            return Err(anyhow!("Expected WebSocket upgrade with status {}, received {}, for request {request_format}", StatusCode::SWITCHING_PROTOCOLS, status_code));
+        }
+
+        let expected_accept = derive_accept_key(websocket_key.as_bytes());
+        let maybe_accept = upgrade_response.maybe_header(header::SEC_WEBSOCKET_ACCEPT);
+        let is_accepted = maybe_accept
+            .as_ref()
+            .is_some_and(|accept| accept == expected_accept.as_str());
+        if !is_accepted {
+            return Err(anyhow!("Expected WebSocket upgrade with Sec-WebSocket-Accept {expected_accept}, received {maybe_accept:?}, for request {request_format}"));
+        }
+
+        let upgraded = on_upgrade.await.with_context(|| {
+            format!("Expect WebSocket upgrade to succeed, for request {request_format}")
+        })?;
+
+        Ok(TestWebSocket::new(request_format, upgrade_response, upgraded).await)
+    }
+
     fn build_url_query_params(mut url: Url, query_params: &QueryParamsStore) -> Url {
         // Add all the query params we have
         if query_params.has_content() {
@@ -454,6 +1001,7 @@ impl TestRequest {
         content_type: Option<String>,
         cookies: CookieJar,
         headers: Vec<(HeaderName, HeaderValue)>,
+        maybe_client_address: Option<SocketAddr>,
     ) -> Result<Request<Body>> {
         let mut request_builder = Request::builder()
             .uri(url.as_str())
@@ -466,9 +1014,10 @@ impl TestRequest {
             request_builder = request_builder.header(header_key, header_value);
         }
 
-        // Add all the cookies as headers
+        // Add all the cookies as headers.
+        // Only the name and value are sent, as browsers do.
         for cookie in cookies.iter() {
-            let cookie_raw = cookie.to_string();
+            let cookie_raw = cookie.stripped().to_string();
             let header_value = HeaderValue::from_str(&cookie_raw)?;
             request_builder = request_builder.header(header::COOKIE, header_value);
         }
@@ -478,6 +1027,11 @@ impl TestRequest {
             request_builder = request_builder.header(header_name, header_value);
         }
 
+        // Used by mocked connect info, to set where the request came from.
+        if let Some(client_address) = maybe_client_address {
+            request_builder = request_builder.extension(MockConnection::new(client_address));
+        }
+
         let request = request_builder.body(body).with_context(|| {
             format!("Expect valid hyper Request to be built, for request {request_format}")
         })?;
@@ -494,15 +1048,16 @@ impl TryFrom<TestRequest> for Request<Body> {
             test_request.config.full_request_url,
             &test_request.query_params,
         );
-        let body = test_request.body.unwrap_or(Body::empty());
+        let body = test_request.body.unwrap_or_default();
 
         TestRequest::build_request(
             &test_request.config.request_format,
             &url,
-            body,
+            body.into(),
             test_request.config.content_type,
             test_request.cookies,
             test_request.headers,
+            test_request.maybe_client_address,
         )
     }
 }
@@ -512,15 +1067,55 @@ impl IntoFuture for TestRequest {
     type IntoFuture = AutoFuture<TestResponse>;
 
     fn into_future(self) -> Self::IntoFuture {
-        AutoFuture::new(async {
-            self.send()
-                .await
-                .with_context(|| format!("Sending request failed"))
-                .unwrap()
-        })
+        AutoFuture::new(async { self.send().await.context("Sending request failed").unwrap() })
     }
 }
 
+/// Waits for the request to finish,
+/// returning an error if more than the timeout given has passed since `started_at`.
+async fn within_timeout<F, T>(
+    maybe_timeout: Option<Duration>,
+    started_at: Instant,
+    request_format: &RequestPathFormatter,
+    send_request: F,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let request_timeout = match maybe_timeout {
+        Some(request_timeout) => request_timeout,
+        None => return send_request.await,
+    };
+
+    let deadline = TokioInstant::from_std(started_at + request_timeout);
+    timeout_at(deadline, send_request)
+        .await
+        .unwrap_or_else(|_| {
+            let elapsed = started_at.elapsed();
+            Err(anyhow!(
+                "Request timed out after {elapsed:?} (timeout is {request_timeout:?}), for request {request_format}"
+            ))
+        })
+}
+
+/// Returns true if the response has a JSON content type,
+/// including types such as `application/problem+json`.
+#[cfg(feature = "json-schema")]
+fn is_json_response(response: &TestResponse) -> bool {
+    response
+        .maybe_header(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok().map(str::to_string))
+        .map(|content_type| content_type.contains("json"))
+        .unwrap_or(false)
+}
+
+/// Headers holding credentials, which are dropped when redirected to another origin.
+fn is_credentials_header(header_name: &HeaderName) -> bool {
+    *header_name == header::AUTHORIZATION
+        || *header_name == header::PROXY_AUTHORIZATION
+        || *header_name == header::COOKIE
+}
+
 fn build_content_type_header(
     content_type: &str,
     request_format: &RequestPathFormatter,
@@ -816,6 +1411,121 @@ mod test_text {
     }
 }
 
+#[cfg(test)]
+mod test_multipart {
+    use crate::multipart::MultipartForm;
+    use crate::multipart::Part;
+    use crate::TestServer;
+
+    use ::axum::extract::Multipart;
+    use ::axum::routing::post;
+    use ::axum::Json;
+    use ::axum::Router;
+    use ::serde::Deserialize;
+    use ::serde::Serialize;
+    use ::std::env::temp_dir;
+    use ::std::fs::write;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct UploadedPart {
+        name: String,
+        file_name: Option<String>,
+        content_type: Option<String>,
+        contents: String,
+    }
+
+    async fn post_upload(mut multipart: Multipart) -> Json<Vec<UploadedPart>> {
+        let mut parts = Vec::new();
+        while let Some(field) = multipart.next_field().await.unwrap() {
+            parts.push(UploadedPart {
+                name: field.name().unwrap().to_string(),
+                file_name: field.file_name().map(ToString::to_string),
+                content_type: field.content_type().map(ToString::to_string),
+                contents: field.text().await.unwrap(),
+            });
+        }
+
+        Json(parts)
+    }
+
+    #[tokio::test]
+    async fn it_should_send_text_and_file_parts() {
+        let app = Router::new().route("/upload", post(post_upload));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let form = MultipartForm::new().add_text("name", "Joe").add_part(
+            "notes",
+            Part::bytes("some notes".into())
+                .file_name("notes.md")
+                .mime_type("text/markdown"),
+        );
+
+        server
+            .post(&"/upload")
+            .multipart(form)
+            .await
+            .assert_json(&vec![
+                UploadedPart {
+                    name: "name".to_string(),
+                    file_name: None,
+                    content_type: None,
+                    contents: "Joe".to_string(),
+                },
+                UploadedPart {
+                    name: "notes".to_string(),
+                    file_name: Some("notes.md".to_string()),
+                    content_type: Some("text/markdown".to_string()),
+                    contents: "some notes".to_string(),
+                },
+            ]);
+    }
+
+    #[tokio::test]
+    async fn it_should_send_parts_read_from_disk() {
+        let path = temp_dir().join("axum-test-multipart-upload.txt");
+        write(&path, "from disk").unwrap();
+
+        let app = Router::new().route("/upload", post(post_upload));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let form = MultipartForm::new().add_file("upload", &path);
+
+        server
+            .post(&"/upload")
+            .multipart(form)
+            .await
+            .assert_json(&vec![UploadedPart {
+                name: "upload".to_string(),
+                file_name: Some("axum-test-multipart-upload.txt".to_string()),
+                content_type: Some("application/octet-stream".to_string()),
+                contents: "from disk".to_string(),
+            }]);
+    }
+
+    #[tokio::test]
+    async fn it_should_set_content_type_with_boundary() {
+        let app = Router::new().route(
+            "/content_type",
+            post(|headers: ::http::HeaderMap| async move {
+                headers[::http::header::CONTENT_TYPE]
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            }),
+        );
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let form = MultipartForm::new().add_text("name", "Joe");
+        let expected_content_type = form.content_type();
+
+        server
+            .post(&"/content_type")
+            .multipart(form)
+            .await
+            .assert_text(expected_content_type);
+    }
+}
+
 #[cfg(test)]
 mod test_expect_success {
     use crate::TestServer;
@@ -1283,6 +1993,100 @@ mod test_clear_headers {
     }
 }
 
+#[cfg(test)]
+mod test_authorization {
+    use super::*;
+
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::http::HeaderMap;
+
+    use crate::TestServer;
+
+    async fn get_authorization(headers: HeaderMap) -> String {
+        headers
+            .get_all(header::AUTHORIZATION)
+            .iter()
+            .map(|value| value.to_str().unwrap())
+            .collect::<Vec<&str>>()
+            .join(" | ")
+    }
+
+    #[tokio::test]
+    async fn it_should_send_bearer_token() {
+        let app = Router::new().route("/authorization", get(get_authorization));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/authorization")
+            .authorization_bearer("abc123")
+            .await
+            .assert_text("Bearer abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_replace_authorization_set_on_request() {
+        let app = Router::new().route("/authorization", get(get_authorization));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/authorization")
+            .add_header(header::AUTHORIZATION, HeaderValue::from_static("Basic old"))
+            .authorization_bearer("abc123")
+            .await
+            .assert_text("Bearer abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_replace_authorization_set_on_server() {
+        let app = Router::new().route("/authorization", get(get_authorization));
+        let mut server = TestServer::new(app).expect("Should create test server");
+        server.authorization_bearer("from-server");
+
+        server
+            .get(&"/authorization")
+            .authorization_bearer("from-request")
+            .await
+            .assert_text("Bearer from-request");
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_on_invalid_token() {
+        let app = Router::new().route("/authorization", get(get_authorization));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let _ = server
+            .get(&"/authorization")
+            .authorization_bearer("abc\n123");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_basic_credentials() {
+        let app = Router::new().route("/authorization", get(get_authorization));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/authorization")
+            .authorization_basic("Aladdin", "open sesame")
+            .await
+            .assert_text("Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
+    }
+
+    #[tokio::test]
+    async fn it_should_replace_bearer_token() {
+        let app = Router::new().route("/authorization", get(get_authorization));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/authorization")
+            .authorization_bearer("abc123")
+            .authorization_basic("Aladdin", "open sesame")
+            .await
+            .assert_text("Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
+    }
+}
+
 #[cfg(test)]
 mod test_add_query_params {
     use ::axum::extract::Query;
@@ -1534,3 +2338,462 @@ mod test_clear_query_params {
             .assert_text(&"has first? true, has second? true");
     }
 }
+
+#[cfg(test)]
+mod test_timeout {
+    use ::axum::response::Redirect;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::future::pending;
+    use ::std::time::Duration;
+    use ::tokio::time::sleep;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn get_pending() -> &'static str {
+        pending::<()>().await;
+        "never"
+    }
+
+    async fn get_slow() -> &'static str {
+        sleep(Duration::from_millis(50)).await;
+        "slow"
+    }
+
+    #[tokio::test]
+    async fn it_should_return_response_within_timeout() {
+        let app = Router::new().route("/slow", get(get_slow));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server
+            .get(&"/slow")
+            .timeout(Duration::from_secs(5))
+            .await
+            .assert_text(&"slow");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "(timeout is 20ms), for request GET /pending")]
+    async fn it_should_panic_when_request_times_out_when_mocked() {
+        let app = Router::new().route("/pending", get(get_pending));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server
+            .get(&"/pending")
+            .timeout(Duration::from_millis(20))
+            .await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "(timeout is 20ms), for request GET /pending")]
+    async fn it_should_panic_when_request_times_out_when_using_http() {
+        let app = Router::new().route("/pending", get(get_pending));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server
+            .get(&"/pending")
+            .timeout(Duration::from_millis(20))
+            .await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Request timed out after")]
+    async fn it_should_panic_when_request_exceeds_default_timeout() {
+        let app = Router::new().route("/pending", get(get_pending));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            default_timeout: Some(Duration::from_millis(20)),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/pending").await;
+    }
+
+    #[tokio::test]
+    async fn it_should_override_default_timeout() {
+        let app = Router::new().route("/slow", get(get_slow));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            default_timeout: Some(Duration::from_millis(1)),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server
+            .get(&"/slow")
+            .timeout(Duration::from_secs(5))
+            .await
+            .assert_text(&"slow");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "(timeout is 20ms), for request GET /pending")]
+    async fn it_should_panic_when_stream_times_out() {
+        let app = Router::new().route("/pending", get(get_pending));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server
+            .get(&"/pending")
+            .timeout(Duration::from_millis(20))
+            .stream()
+            .await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "(timeout is 80ms), for request GET /second")]
+    async fn it_should_panic_when_redirects_together_exceed_timeout() {
+        let app = Router::new()
+            .route("/first", get(get_slow_redirect_to_second))
+            .route("/second", get(get_slow_redirect_to_slow))
+            .route("/slow", get(get_slow));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server
+            .get(&"/first")
+            .follow_redirects()
+            .timeout(Duration::from_millis(80))
+            .await;
+    }
+
+    async fn get_slow_redirect_to_second() -> Redirect {
+        sleep(Duration::from_millis(50)).await;
+        Redirect::to("/second")
+    }
+
+    async fn get_slow_redirect_to_slow() -> Redirect {
+        sleep(Duration::from_millis(50)).await;
+        Redirect::to("/slow")
+    }
+}
+
+#[cfg(test)]
+mod test_follow_redirects {
+    use ::axum::body::Bytes;
+    use ::axum::http::header;
+    use ::axum::http::HeaderMap;
+    use ::axum::http::Method;
+    use ::axum::http::StatusCode;
+    use ::axum::response::Redirect;
+    use ::axum::routing::any;
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::axum_extra::extract::cookie::CookieJar as AxumCookieJar;
+    use ::cookie::Cookie;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+
+    async fn echo_request(method: Method, headers: HeaderMap, body: Bytes) -> String {
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .unwrap_or("none");
+        let body = String::from_utf8_lossy(&body);
+
+        format!("{method} content-type={content_type} body={body}")
+    }
+
+    async fn get_session(cookies: AxumCookieJar) -> String {
+        cookies
+            .get("session")
+            .map(|cookie| cookie.value().to_string())
+            .unwrap_or_else(|| "no-session".to_string())
+    }
+
+    async fn post_session_login() -> (StatusCode, [(header::HeaderName, &'static str); 2]) {
+        (
+            StatusCode::SEE_OTHER,
+            [
+                (header::SET_COOKIE, "session=abc; Path=/"),
+                (header::LOCATION, "/session"),
+            ],
+        )
+    }
+
+    async fn get_credentials(headers: HeaderMap) -> String {
+        [
+            header::AUTHORIZATION,
+            header::PROXY_AUTHORIZATION,
+            header::COOKIE,
+        ]
+        .iter()
+        .map(|name| {
+            let value = headers
+                .get(name)
+                .and_then(|header| header.to_str().ok())
+                .unwrap_or("none");
+            format!("{name}={value}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+
+    #[tokio::test]
+    async fn it_should_not_follow_redirects_by_default() {
+        let app = Router::new()
+            .route("/login", post(|| async { Redirect::to("/dashboard") }))
+            .route("/dashboard", get(|| async { "welcome!" }));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let response = server.post(&"/login").await;
+
+        response.assert_status(StatusCode::SEE_OTHER);
+        assert_eq!(response.redirect_history().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn it_should_follow_redirects_and_keep_history() {
+        let app = Router::new()
+            .route("/login", post(|| async { Redirect::to("/dashboard") }))
+            .route("/dashboard", get(|| async { "welcome!" }));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let response = server.post(&"/login").follow_redirects().await;
+
+        response.assert_text("welcome!");
+        assert_eq!(response.request_url().path(), "/dashboard");
+
+        let history = response.redirect_history();
+        assert_eq!(history.len(), 1);
+        history[0].assert_status(StatusCode::SEE_OTHER);
+        assert_eq!(history[0].request_url().path(), "/login");
+    }
+
+    #[tokio::test]
+    async fn it_should_follow_redirects_when_set_on_the_server() {
+        let app = Router::new()
+            .route("/login", post(|| async { Redirect::to("/dashboard") }))
+            .route("/dashboard", get(|| async { "welcome!" }));
+        let config = TestServerConfig::builder().follow_redirects().build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.post(&"/login").await.assert_text("welcome!");
+        server
+            .post(&"/login")
+            .do_not_follow_redirects()
+            .await
+            .assert_status(StatusCode::SEE_OTHER);
+    }
+
+    #[tokio::test]
+    async fn it_should_change_post_to_get_without_body_for_301_and_302() {
+        let app = Router::new()
+            .route(
+                "/moved",
+                any(|| async { (StatusCode::MOVED_PERMANENTLY, [(header::LOCATION, "/echo")]) }),
+            )
+            .route(
+                "/found",
+                any(|| async { (StatusCode::FOUND, [(header::LOCATION, "/echo")]) }),
+            )
+            .route("/echo", any(echo_request));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        for path in ["/moved", "/found"] {
+            server
+                .post(path)
+                .text(&"hello")
+                .follow_redirects()
+                .await
+                .assert_text("GET content-type=none body=");
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_method_and_body_for_other_methods_with_301_and_302() {
+        let app = Router::new()
+            .route(
+                "/found",
+                any(|| async { (StatusCode::FOUND, [(header::LOCATION, "/echo")]) }),
+            )
+            .route("/echo", any(echo_request));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .put(&"/found")
+            .text(&"hello")
+            .follow_redirects()
+            .await
+            .assert_text("PUT content-type=text/plain body=hello");
+    }
+
+    #[tokio::test]
+    async fn it_should_change_to_get_without_body_for_303() {
+        let app = Router::new()
+            .route("/see-other", any(|| async { Redirect::to("/echo") }))
+            .route("/echo", any(echo_request));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .put(&"/see-other")
+            .text(&"hello")
+            .follow_redirects()
+            .await
+            .assert_text("GET content-type=none body=");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_method_and_body_for_307_and_308() {
+        let app = Router::new()
+            .route("/temporary", any(|| async { Redirect::temporary("/echo") }))
+            .route("/permanent", any(|| async { Redirect::permanent("/echo") }))
+            .route("/echo", any(echo_request));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        for path in ["/temporary", "/permanent"] {
+            server
+                .post(path)
+                .text(&"hello")
+                .follow_redirects()
+                .await
+                .assert_text("POST content-type=text/plain body=hello");
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_carry_cookies_between_redirects() {
+        let app = Router::new()
+            .route("/session-login", post(post_session_login))
+            .route("/session", get(get_session));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let response = server.post(&"/session-login").follow_redirects().await;
+
+        response.assert_text("abc");
+
+        // Cookies are not saved to the server, without saving turned on.
+        server.get(&"/session").await.assert_text("no-session");
+    }
+
+    #[tokio::test]
+    async fn it_should_save_cookies_from_redirects_when_saving() {
+        let app = Router::new()
+            .route("/session-login", post(post_session_login))
+            .route("/session", get(get_session));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .post(&"/session-login")
+            .follow_redirects()
+            .do_save_cookies()
+            .await;
+
+        server.get(&"/session").await.assert_text("abc");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_server_cookies_for_each_redirect() {
+        let app = Router::new()
+            .route("/to-session", get(|| async { Redirect::to("/session") }))
+            .route("/session", get(get_session));
+        let mut server = TestServer::new(app).expect("Should create test server");
+        server.add_cookie(
+            Cookie::build(("session", "from-server"))
+                .path("/session")
+                .build(),
+        );
+
+        server
+            .get(&"/to-session")
+            .follow_redirects()
+            .await
+            .assert_text("from-server");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_request_cookies_for_each_redirect() {
+        let app = Router::new()
+            .route("/to-session", get(|| async { Redirect::to("/session") }))
+            .route("/session", get(get_session));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/to-session")
+            .add_cookie(Cookie::new("session", "from-request"))
+            .follow_redirects()
+            .await
+            .assert_text("from-request");
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_credentials_when_redirected_within_the_origin() {
+        let app = Router::new()
+            .route(
+                "/to-credentials",
+                get(|| async { Redirect::to("/credentials") }),
+            )
+            .route("/credentials", get(get_credentials));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/to-credentials")
+            .authorization_bearer("abc123")
+            .add_cookie(Cookie::new("session", "xyz"))
+            .follow_redirects()
+            .await
+            .assert_text("authorization=Bearer abc123 proxy-authorization=none cookie=session=xyz");
+    }
+
+    #[tokio::test]
+    async fn it_should_drop_credentials_when_redirected_to_another_origin() {
+        let app = Router::new()
+            .route(
+                "/to-other-origin",
+                get(|| async { Redirect::to("http://other.example/credentials") }),
+            )
+            .route("/credentials", get(get_credentials));
+        let mut server = TestServer::new(app).expect("Should create test server");
+        server.add_cookie(Cookie::new("server-session", "abc"));
+
+        let response = server
+            .get(&"/to-other-origin")
+            .authorization_bearer("abc123")
+            .add_header(
+                header::PROXY_AUTHORIZATION,
+                header::HeaderValue::from_static("Basic cHJveHk="),
+            )
+            .add_cookie(Cookie::new("session", "xyz"))
+            .follow_redirects()
+            .await;
+
+        response.assert_text("authorization=none proxy-authorization=none cookie=none");
+        assert_eq!(response.request_url().host_str(), Some("other.example"));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Exceeded the maximum of 2 redirects, for request GET /loop")]
+    async fn it_should_panic_when_exceeding_max_redirects() {
+        let app = Router::new().route("/loop", get(|| async { Redirect::to("/loop") }));
+        let config = TestServerConfig::builder()
+            .follow_redirects()
+            .max_redirects(2)
+            .build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/loop").await;
+    }
+}
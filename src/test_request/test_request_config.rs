@@ -1,13 +1,34 @@
+use ::std::net::SocketAddr;
+#[cfg(feature = "json-schema")]
+use ::std::sync::Arc;
+use ::std::time::Duration;
 use ::url::Url;
 
 use crate::internals::ExpectedState;
+#[cfg(feature = "openapi")]
+use crate::internals::OpenApiContract;
 use crate::internals::RequestPathFormatter;
+#[cfg(feature = "json-schema")]
+use crate::internals::RoutePattern;
+#[cfg(feature = "json-schema")]
+use crate::JsonSchema;
 
 #[derive(Debug, Clone)]
 pub struct TestRequestConfig {
     pub is_saving_cookies: bool,
+    pub is_recording_har: bool,
+    pub is_following_redirects: bool,
+    pub max_redirects: usize,
+    #[cfg(feature = "tracing")]
+    pub is_capturing_logs: bool,
     pub expected_state: ExpectedState,
     pub content_type: Option<String>,
     pub full_request_url: Url,
     pub request_format: RequestPathFormatter,
+    pub timeout: Option<Duration>,
+    pub client_address: Option<SocketAddr>,
+    #[cfg(feature = "json-schema")]
+    pub route_json_schemas: Arc<Vec<(RoutePattern, JsonSchema)>>,
+    #[cfg(feature = "openapi")]
+    pub maybe_openapi_contract: Option<Arc<OpenApiContract>>,
 }
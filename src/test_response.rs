@@ -5,21 +5,48 @@ use ::cookie::CookieJar;
 use ::http::header::AsHeaderName;
 use ::http::header::HeaderName;
 use ::http::header::SET_COOKIE;
+use ::http::header::WWW_AUTHENTICATE;
 use ::http::response::Parts;
 use ::http::HeaderMap;
 use ::http::HeaderValue;
+#[cfg(feature = "json-schema")]
+use ::http::Method;
 use ::http::StatusCode;
+use ::http::Version;
 use ::serde::de::DeserializeOwned;
+use ::serde::Serialize;
+use ::serde_json::Value;
 use ::std::convert::AsRef;
 use ::std::fmt::Debug;
 use ::std::fmt::Display;
+use ::std::panic::Location;
 use ::url::Url;
 
 #[cfg(feature = "pretty-assertions")]
 use ::pretty_assertions::{assert_eq, assert_ne};
 
+use crate::internals::assert_snapshot;
+use crate::internals::is_updating_snapshots;
+use crate::internals::render_snapshot;
+use crate::internals::snapshot_path;
+use crate::internals::AuthChallengeParser;
+use crate::internals::JsonContains;
+use crate::internals::JsonPath;
+#[cfg(feature = "openapi")]
+use crate::internals::OpenApiContract;
+#[cfg(feature = "openapi")]
+use crate::internals::OpenApiExchange;
 use crate::internals::RequestPathFormatter;
+#[cfg(feature = "json-schema")]
+use crate::internals::RoutePattern;
+use crate::internals::SseParser;
 use crate::internals::StatusCodeFormatter;
+use crate::AuthChallenge;
+use crate::JsonContainsConfig;
+#[cfg(feature = "json-schema")]
+use crate::JsonSchema;
+use crate::SnapshotConfig;
+use crate::SseEvent;
 
 ///
 /// The `TestResponse` is the result of a request created using a [`TestServer`](crate::TestServer).
@@ -127,7 +154,11 @@ pub struct TestResponse {
     full_request_url: Url,
     headers: HeaderMap<HeaderValue>,
     status_code: StatusCode,
+    version: Version,
     response_body: Bytes,
+    redirect_history: Vec<TestResponse>,
+    #[cfg(feature = "tracing")]
+    logs: String,
 }
 
 impl TestResponse {
@@ -142,10 +173,30 @@ impl TestResponse {
             full_request_url,
             headers: parts.headers,
             status_code: parts.status,
+            version: parts.version,
             response_body,
+            redirect_history: Vec::new(),
+            #[cfg(feature = "tracing")]
+            logs: String::new(),
         }
     }
 
+    pub(crate) fn with_redirect_history(mut self, redirect_history: Vec<TestResponse>) -> Self {
+        self.redirect_history = redirect_history;
+        self
+    }
+
+    #[cfg(feature = "json-schema")]
+    pub(crate) fn request_method(&self) -> &Method {
+        self.request_format.method()
+    }
+
+    #[cfg(feature = "tracing")]
+    pub(crate) fn with_logs(mut self, logs: String) -> Self {
+        self.logs = logs;
+        self
+    }
+
     /// Returns the underlying response, extracted as a UTF-8 string.
     ///
     /// # Example
@@ -238,6 +289,112 @@ impl TestResponse {
             .unwrap()
     }
 
+    /// Finds the value at the [JSONPath](https://goessner.net/articles/JsonPath/) given,
+    /// within the response JSON, and deserializes it into the type given.
+    ///
+    /// Paths containing wildcards (i.e. `$.items[*].id`) or descendant searches (i.e. `$..id`),
+    /// return an array of every value matched.
+    ///
+    /// This will panic if the value at the path cannot be found,
+    /// if deserialization fails, or if the path is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::Json;
+    /// use ::axum::routing::Router;
+    /// use ::axum::routing::get;
+    /// use ::serde_json::json;
+    ///
+    /// use ::axum_test::TestServer;
+    ///
+    /// let app = Router::new()
+    ///     .route(&"/todos", get(|| async {
+    ///         Json(json!({
+    ///             "items": [{ "id": 123, "description": "buy milk" }],
+    ///         }))
+    ///     }));
+    ///
+    /// let server = TestServer::new(app)?;
+    /// let response = server.get(&"/todos").await;
+    ///
+    /// let id = response.json_path::<u32>(&"$.items[0].id");
+    /// let descriptions = response.json_path::<Vec<String>>(&"$.items[*].description");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn json_path<T>(&self, path: &str) -> T
+    where
+        T: DeserializeOwned,
+    {
+        let value = self.expect_json_path(path);
+        self.deserialize_json_path(path, value)
+    }
+
+    /// Finds the value at the JSONPath given, within the response JSON,
+    /// and deserializes it into the type given.
+    ///
+    /// Returns `None` if the value at the path cannot be found.
+    ///
+    /// See [`TestResponse::json_path()`](crate::TestResponse::json_path()) for more.
+    #[must_use]
+    pub fn maybe_json_path<T>(&self, path: &str) -> Option<T>
+    where
+        T: DeserializeOwned,
+    {
+        let (document, json_path) = self.parse_json_path(path);
+
+        json_path
+            .find(&document)
+            .map(|value| self.deserialize_json_path(path, value))
+    }
+
+    fn parse_json_path(&self, path: &str) -> (Value, JsonPath) {
+        let json_path = JsonPath::parse(path)
+            .with_context(|| {
+                let request_format = &self.request_format;
+
+                format!("Parsing JSON path '{path}', for request {request_format}")
+            })
+            .unwrap();
+
+        (self.json::<Value>(), json_path)
+    }
+
+    #[track_caller]
+    fn expect_json_path(&self, path: &str) -> Value {
+        let (document, json_path) = self.parse_json_path(path);
+
+        match json_path.find(&document) {
+            Some(value) => value,
+            None => {
+                let request_format = &self.request_format;
+                let surrounding = format_json_path_surrounding(&json_path, &document);
+                let captured_logs = self.format_captured_logs();
+
+                panic!("JSON path '{path}' not found, for request {request_format}\n{surrounding}{captured_logs}")
+            }
+        }
+    }
+
+    fn deserialize_json_path<T>(&self, path: &str, value: Value) -> T
+    where
+        T: DeserializeOwned,
+    {
+        ::serde_json::from_value::<T>(value)
+            .with_context(|| {
+                let request_format = &self.request_format;
+
+                format!("Deserializing JSON path '{path}', for request {request_format}")
+            })
+            .unwrap()
+    }
+
     /// Deserializes the response, as an urlencoded Form, into the type given.
     ///
     /// If deserialization fails then this will panic.
@@ -292,6 +449,17 @@ impl TestResponse {
             .unwrap()
     }
 
+    /// Parses the response body as a `text/event-stream`,
+    /// returning all of the Server-Sent Events it contains.
+    ///
+    /// This is for streams which have finished.
+    /// For streams which stay open, use [`TestRequest::stream()`](crate::TestRequest::stream())
+    /// with a [`TestSseReader`](crate::TestSseReader).
+    #[must_use]
+    pub fn sse_events(&self) -> Vec<SseEvent> {
+        SseParser::parse_all(&self.response_body)
+    }
+
     /// Returns the raw underlying response as `Bytes`.
     #[must_use]
     pub fn as_bytes<'a>(&'a self) -> &'a Bytes {
@@ -311,6 +479,81 @@ impl TestResponse {
         self.status_code
     }
 
+    /// The HTTP protocol version used for the response.
+    ///
+    /// This will be `HTTP/2.0` when using the [`Transport::Http2`](crate::Transport::Http2) transport.
+    #[must_use]
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the [`tracing`](::tracing) events emitted whilst handling the request,
+    /// with one event per line.
+    ///
+    /// This is empty unless `capture_logs` is set on the [`TestServerConfig`](crate::TestServerConfig),
+    /// and the server is using mock transport.
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::Router;
+    /// use ::axum::routing::get;
+    /// use ::axum_test::TestServer;
+    /// use ::axum_test::TestServerConfig;
+    ///
+    /// let app = Router::new().route(&"/todo", get(|| async {
+    ///     ::tracing::info!("fetching todo");
+    ///     "hello!"
+    /// }));
+    /// let config = TestServerConfig::builder().capture_logs().build();
+    /// let server = TestServer::new_with_config(app, config)?;
+    ///
+    /// let response = server.get(&"/todo").await;
+    /// assert!(response.logs().contains("fetching todo"));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tracing")]
+    #[must_use]
+    pub fn logs(&self) -> &str {
+        &self.logs
+    }
+
+    /// Returns the redirect responses which were followed to reach this response,
+    /// in the order they were received.
+    ///
+    /// This is empty unless redirects are followed,
+    /// using [`TestRequest::follow_redirects()`](crate::TestRequest::follow_redirects()),
+    /// or setting `follow_redirects` on the [`TestServerConfig`](crate::TestServerConfig).
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::Router;
+    /// use ::axum::response::Redirect;
+    /// use ::axum::routing::get;
+    /// use ::axum::routing::post;
+    /// use ::axum_test::TestServer;
+    ///
+    /// let app = Router::new()
+    ///     .route(&"/login", post(|| async { Redirect::to("/dashboard") }))
+    ///     .route(&"/dashboard", get(|| async { "welcome!" }));
+    /// let server = TestServer::new(app)?;
+    ///
+    /// let response = server.post(&"/login").follow_redirects().await;
+    ///
+    /// response.assert_text("welcome!");
+    /// assert_eq!(response.redirect_history().len(), 1);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn redirect_history(&self) -> &[TestResponse] {
+        &self.redirect_history
+    }
+
     /// The full URL that was used to produce this response.
     #[must_use]
     pub fn request_url(&self) -> Url {
@@ -444,6 +687,32 @@ impl TestResponse {
         })
     }
 
+    /// Returns all of the challenges from the `WWW-Authenticate` headers in the response,
+    /// in the order they were sent.
+    ///
+    /// If a header cannot be parsed, then this will panic.
+    #[must_use]
+    pub fn www_authenticate(&self) -> Vec<AuthChallenge> {
+        let request_format = &self.request_format;
+
+        self.iter_headers_by_name(WWW_AUTHENTICATE)
+            .flat_map(|header| {
+                let header_str = header
+                    .to_str()
+                    .with_context(|| {
+                        format!("Reading header 'WWW-Authenticate' as string, for request {request_format}")
+                    })
+                    .unwrap();
+
+                AuthChallengeParser::parse(header_str)
+                    .with_context(|| {
+                        format!("Parsing 'WWW-Authenticate' header, for request {request_format}")
+                    })
+                    .unwrap()
+            })
+            .collect()
+    }
+
     /// This performs an assertion comparing the whole body of the response,
     /// against the text provided.
     #[track_caller]
@@ -452,7 +721,14 @@ impl TestResponse {
         C: AsRef<str>,
     {
         let other_contents = other.as_ref();
-        assert_eq!(other_contents, &self.text());
+        let request_format = &self.request_format;
+        let captured_logs = self.format_captured_logs();
+
+        assert_eq!(
+            other_contents,
+            &self.text(),
+            "Expected text to match, for request {request_format}{captured_logs}"
+        );
     }
 
     /// Deserializes the contents of the request as JSON,
@@ -465,97 +741,558 @@ impl TestResponse {
     where
         T: DeserializeOwned + PartialEq<T> + Debug,
     {
-        assert_eq!(*other, self.json::<T>());
+        let request_format = &self.request_format;
+        let captured_logs = self.format_captured_logs();
+
+        assert_eq!(
+            *other,
+            self.json::<T>(),
+            "Expected JSON to match, for request {request_format}{captured_logs}"
+        );
     }
 
-    /// Deserializes the contents of the request as an url encoded form,
-    /// and asserts it matches the value given.
+    /// Asserts the response JSON contains the JSON given.
     ///
-    /// If `other` does not match, or the response cannot be deserialized,
-    /// then this will panic.
+    /// Every field in `expected` must be in the response, with an equal value.
+    /// The response may contain extra fields which are not expected.
+    /// Arrays must be the same length, with each item containing the item expected.
+    ///
+    /// On failure this will panic listing each difference by its JSONPath.
+    ///
+    /// For ignoring array order, ignoring values such as generated ids,
+    /// or comparing floats within a tolerance,
+    /// use [`TestResponse::assert_json_contains_with_config()`](crate::TestResponse::assert_json_contains_with_config()).
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::Json;
+    /// use ::axum::routing::Router;
+    /// use ::axum::routing::get;
+    /// use ::serde_json::json;
+    ///
+    /// use ::axum_test::TestServer;
+    ///
+    /// let app = Router::new()
+    ///     .route(&"/todo", get(|| async {
+    ///         Json(json!({ "id": 123, "description": "buy milk", "done": false }))
+    ///     }));
+    ///
+    /// let server = TestServer::new(app)?;
+    /// server.get(&"/todo").await.assert_json_contains(&json!({
+    ///     "description": "buy milk",
+    /// }));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
     #[track_caller]
-    pub fn assert_form<T>(&self, other: &T)
+    pub fn assert_json_contains<T>(&self, expected: &T)
     where
-        T: DeserializeOwned + PartialEq<T> + Debug,
+        T: Serialize + ?Sized,
     {
-        assert_eq!(*other, self.form::<T>());
+        self.assert_json_contains_with_config(expected, JsonContainsConfig::default())
     }
 
-    /// Assert that the status code is **within** the 2xx range.
-    /// i.e. The range from 200-299.
+    /// Asserts the response JSON contains the JSON given,
+    /// using the [`JsonContainsConfig`](crate::JsonContainsConfig) to adjust the comparison.
+    ///
+    /// See [`TestResponse::assert_json_contains()`](crate::TestResponse::assert_json_contains()) for more.
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::Json;
+    /// use ::axum::routing::Router;
+    /// use ::axum::routing::get;
+    /// use ::serde_json::json;
+    ///
+    /// use ::axum_test::JsonContainsConfig;
+    /// use ::axum_test::TestServer;
+    ///
+    /// let app = Router::new()
+    ///     .route(&"/todos", get(|| async {
+    ///         Json(json!([
+    ///             { "id": 456, "description": "walk the dog" },
+    ///             { "id": 123, "description": "buy milk" },
+    ///         ]))
+    ///     }));
+    ///
+    /// let server = TestServer::new(app)?;
+    /// let config = JsonContainsConfig::builder()
+    ///     .ignore_array_order()
+    ///     .ignore_path(&"$[*].id")
+    ///     .build();
+    ///
+    /// server.get(&"/todos").await.assert_json_contains_with_config(
+    ///     &json!([
+    ///         { "id": 1, "description": "buy milk" },
+    ///         { "id": 2, "description": "walk the dog" },
+    ///     ]),
+    ///     config,
+    /// );
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
     #[track_caller]
-    pub fn assert_status_success(&self) {
-        let status_code = self.status_code.as_u16();
-        let received_debug = StatusCodeFormatter(self.status_code);
+    pub fn assert_json_contains_with_config<T>(&self, expected: &T, config: JsonContainsConfig)
+    where
+        T: Serialize + ?Sized,
+    {
         let request_format = &self.request_format;
+        let mut expected = ::serde_json::to_value(expected)
+            .with_context(|| format!("Serializing expected JSON, for request {request_format}"))
+            .unwrap();
+        let mut received = self.json::<Value>();
 
-        assert!(
-            200 <= status_code && status_code <= 299,
-            "Expect status code within 2xx range, got {received_debug}, for request {request_format}"
-        );
-    }
+        for ignored_path in &config.ignored_paths {
+            let json_path = JsonPath::parse(ignored_path)
+                .with_context(|| {
+                    format!(
+                        "Parsing ignored JSON path '{ignored_path}', for request {request_format}"
+                    )
+                })
+                .unwrap();
 
-    /// Assert that the status code is **outside** the 2xx range.
-    /// i.e. A status code less than 200, or 300 or more.
-    #[track_caller]
-    pub fn assert_status_failure(&self) {
-        let status_code = self.status_code.as_u16();
-        let received_debug = StatusCodeFormatter(self.status_code);
-        let request_format = &self.request_format;
+            json_path.remove_from(&mut expected);
+            json_path.remove_from(&mut received);
+        }
 
-        assert!(
-            status_code < 200 || 299 < status_code,
-            "Expect status code outside 2xx range, got {received_debug}, for request {request_format}",
-        );
-    }
+        let json_contains = JsonContains {
+            ignore_array_order: config.ignore_array_order,
+            float_tolerance: config.float_tolerance,
+        };
+        let differences = json_contains.differences(&expected, &received);
 
-    /// Assert the response status code is 400.
-    #[track_caller]
-    pub fn assert_status_bad_request(&self) {
-        self.assert_status(StatusCode::BAD_REQUEST)
-    }
+        if !differences.is_empty() {
+            let differences_count = differences.len();
+            let differences_list: String = differences
+                .iter()
+                .map(|difference| format!("\n    {difference}"))
+                .collect();
 
-    /// Assert the response status code is 404.
-    #[track_caller]
-    pub fn assert_status_not_found(&self) {
-        self.assert_status(StatusCode::NOT_FOUND)
-    }
+            let captured_logs = self.format_captured_logs();
 
-    /// Assert the response status code is 401.
-    #[track_caller]
-    pub fn assert_status_unauthorized(&self) {
-        self.assert_status(StatusCode::UNAUTHORIZED)
+            panic!("Response JSON does not contain expected JSON, found {differences_count} differences, for request {request_format}{differences_list}{captured_logs}");
+        }
     }
 
-    /// Assert the response status code is 403.
+    /// Asserts the response JSON is valid for the [JSON Schema](https://json-schema.org/) given,
+    /// using draft 2020-12.
+    ///
+    /// The schema can be a `serde_json::Value`, a path to a JSON file,
+    /// or a [`JsonSchema`](crate::JsonSchema).
+    ///
+    /// If the response is not valid, this will panic listing every violation
+    /// along with the path to the value in the response.
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::Json;
+    /// use ::axum::routing::Router;
+    /// use ::axum::routing::get;
+    /// use ::serde_json::json;
+    ///
+    /// use ::axum_test::TestServer;
+    ///
+    /// let app = Router::new()
+    ///     .route(&"/todo", get(|| async {
+    ///         Json(json!({ "id": 123, "description": "buy milk" }))
+    ///     }));
+    ///
+    /// let server = TestServer::new(app)?;
+    /// server.get(&"/todo").await.assert_json_schema(json!({
+    ///     "type": "object",
+    ///     "required": ["id", "description"],
+    ///     "properties": {
+    ///         "id": { "type": "integer" },
+    ///         "description": { "type": "string" },
+    ///     },
+    /// }));
+    ///
+    /// // Or from a file ...
+    /// use ::std::path::Path;
+    /// server.get(&"/todo").await.assert_json_schema(Path::new("schemas/todo.json"));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "json-schema")]
     #[track_caller]
-    pub fn assert_status_forbidden(&self) {
-        self.assert_status(StatusCode::FORBIDDEN)
+    pub fn assert_json_schema<S>(&self, schema: S)
+    where
+        S: Into<JsonSchema>,
+    {
+        self.assert_json_schema_for_route(&schema.into(), None)
     }
 
-    /// Assert the response status code is 200.
+    #[cfg(feature = "json-schema")]
     #[track_caller]
-    pub fn assert_status_ok(&self) {
-        self.assert_status(StatusCode::OK)
+    pub(crate) fn assert_json_schema_for_route(
+        &self,
+        schema: &JsonSchema,
+        maybe_route: Option<&RoutePattern>,
+    ) {
+        let request_format = &self.request_format;
+        let received = self.json::<Value>();
+        let violations = schema
+            .violations(&received)
+            .with_context(|| format!("Validating JSON schema, for request {request_format}"))
+            .unwrap();
+
+        if !violations.is_empty() {
+            let violations_count = violations.len();
+            let schema_format = match maybe_route {
+                Some(route) => format!("schema for route '{route}'"),
+                None => "schema".to_string(),
+            };
+            let violations_list: String = violations
+                .iter()
+                .map(|violation| format!("\n    {violation}"))
+                .collect();
+
+            let captured_logs = self.format_captured_logs();
+
+            panic!("Response JSON does not match {schema_format}, found {violations_count} violations, for request {request_format}{violations_list}{captured_logs}");
+        }
     }
 
-    /// Assert the response status code is **not** 200.
+    #[cfg(feature = "openapi")]
     #[track_caller]
-    pub fn assert_status_not_ok(&self) {
-        self.assert_not_status(StatusCode::OK)
+    pub(crate) fn assert_openapi_contract(
+        &self,
+        contract: &OpenApiContract,
+        method: &Method,
+        request_headers: &HeaderMap<HeaderValue>,
+        request_body: &Bytes,
+    ) {
+        let exchange = OpenApiExchange {
+            method,
+            url: &self.full_request_url,
+            request_headers,
+            request_body,
+            status_code: self.status_code,
+            response_headers: &self.headers,
+            response_body: &self.response_body,
+        };
+        let violations = contract.violations(&exchange);
+
+        if !violations.is_empty() {
+            let request_format = &self.request_format;
+            let violations_count = violations.len();
+            let violations_list: String = violations
+                .iter()
+                .map(|violation| format!("\n    {violation}"))
+                .collect();
+
+            let captured_logs = self.format_captured_logs();
+
+            panic!("Request and response do not match the OpenAPI spec, found {violations_count} violations, for request {request_format}{violations_list}{captured_logs}");
+        }
     }
 
-    /// Assert the response status code matches the one given.
-    #[track_caller]
-    pub fn assert_status(&self, expected_status_code: StatusCode) {
-        let status_code = self.status_code.as_u16();
-        let received_debug = StatusCodeFormatter(self.status_code);
+    /// Asserts the value at the JSONPath given, within the response JSON,
+    /// matches the value given.
+    ///
+    /// If the path is not found, or the value does not match,
+    /// then this will panic showing the document surrounding the path.
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// # use ::axum::Json;
+    /// # use ::axum::routing::Router;
+    /// # use ::axum::routing::get;
+    /// # use ::serde_json::json;
+    /// # use ::axum_test::TestServer;
+    /// #
+    /// # let app = Router::new()
+    /// #     .route(&"/todos", get(|| async {
+    /// #         Json(json!({
+    /// #             "items": [{ "id": 123, "description": "buy milk" }],
+    /// #         }))
+    /// #     }));
+    /// #
+    /// # let server = TestServer::new(app)?;
+    /// let response = server.get(&"/todos").await;
+    ///
+    /// response.assert_json_path(&"$.items[0].description", &"buy milk");
+    /// response.assert_json_path(&"$.items[*].id", &json!([123]));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn assert_json_path<T>(&self, path: &str, expected: &T)
+    where
+        T: Serialize + ?Sized,
+    {
+        let request_format = &self.request_format;
+        let received = self.expect_json_path(path);
+        let expected = ::serde_json::to_value(expected)
+            .with_context(|| {
+                format!("Serializing expected value for JSON path '{path}', for request {request_format}")
+            })
+            .unwrap();
+
+        if expected != received {
+            let (document, json_path) = self.parse_json_path(path);
+            let surrounding = format_json_path_surrounding(&json_path, &document);
+            let captured_logs = self.format_captured_logs();
+
+            assert_eq!(
+                expected, received,
+                "JSON path '{path}' does not match, for request {request_format}\n{surrounding}{captured_logs}"
+            );
+        }
+    }
+
+    /// Asserts there is a value at the JSONPath given, within the response JSON.
+    ///
+    /// Paths containing wildcards must match at least one value.
+    #[track_caller]
+    pub fn assert_json_path_exists(&self, path: &str) {
+        let received = self.expect_json_path(path);
+
+        if received.as_array().map(Vec::is_empty).unwrap_or(false) {
+            let (document, json_path) = self.parse_json_path(path);
+            if !json_path.is_definite() {
+                let request_format = &self.request_format;
+                let surrounding = format_json_path_surrounding(&json_path, &document);
+                let captured_logs = self.format_captured_logs();
+
+                panic!(
+                    "JSON path '{path}' matched nothing, for request {request_format}\n{surrounding}{captured_logs}"
+                );
+            }
+        }
+    }
+
+    /// Asserts the array, object, or string at the JSONPath given
+    /// has the length given.
+    ///
+    /// For paths containing wildcards, this is the number of values matched.
+    #[track_caller]
+    pub fn assert_json_path_len(&self, path: &str, expected_len: usize) {
+        let request_format = &self.request_format;
+        let received = self.expect_json_path(path);
+        let received_len = match &received {
+            Value::Array(items) => items.len(),
+            Value::Object(fields) => fields.len(),
+            Value::String(text) => text.chars().count(),
+            _ => {
+                let captured_logs = self.format_captured_logs();
+                panic!("JSON path '{path}' is not an array, object, or string, received {received}, for request {request_format}{captured_logs}")
+            }
+        };
+
+        if expected_len != received_len {
+            let (document, json_path) = self.parse_json_path(path);
+            let surrounding = format_json_path_surrounding(&json_path, &document);
+            let captured_logs = self.format_captured_logs();
+
+            assert_eq!(
+                expected_len, received_len,
+                "JSON path '{path}' has length {received_len}, expected {expected_len}, received {received}, for request {request_format}\n{surrounding}{captured_logs}"
+            );
+        }
+    }
+
+    /// Deserializes the contents of the request as an url encoded form,
+    /// and asserts it matches the value given.
+    ///
+    /// If `other` does not match, or the response cannot be deserialized,
+    /// then this will panic.
+    #[track_caller]
+    pub fn assert_form<T>(&self, other: &T)
+    where
+        T: DeserializeOwned + PartialEq<T> + Debug,
+    {
+        let request_format = &self.request_format;
+        let captured_logs = self.format_captured_logs();
+
+        assert_eq!(
+            *other,
+            self.form::<T>(),
+            "Expected form to match, for request {request_format}{captured_logs}"
+        );
+    }
+
+    /// Asserts the response matches the snapshot with the name given.
+    ///
+    /// The snapshot holds the status code, the `Content-Type` header,
+    /// and the body. JSON bodies are stored pretty printed, and others as text.
+    /// They are stored in a `snapshots` directory next to the source file of the test.
+    ///
+    /// If the snapshot is missing this will panic,
+    /// and if the response differs this will panic showing the difference.
+    /// Run the tests with `AXUM_TEST_UPDATE_SNAPSHOTS=1` to write new snapshots,
+    /// and to accept the new responses, replacing the snapshots stored.
+    ///
+    /// For redacting dynamic values, or storing other headers,
+    /// use [`TestResponse::assert_snapshot_with_config()`](crate::TestResponse::assert_snapshot_with_config()).
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::Json;
+    /// use ::axum::routing::Router;
+    /// use ::axum::routing::get;
+    /// use ::serde_json::json;
+    ///
+    /// use ::axum_test::TestServer;
+    ///
+    /// let app = Router::new()
+    ///     .route(&"/todo", get(|| async {
+    ///         Json(json!({ "description": "buy milk" }))
+    ///     }));
+    ///
+    /// let server = TestServer::new(app)?;
+    /// server.get(&"/todo").await.assert_snapshot(&"get_todo");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn assert_snapshot(&self, name: &str) {
+        self.assert_snapshot_with_config(name, SnapshotConfig::default())
+    }
+
+    /// Asserts the response matches the snapshot with the name given,
+    /// using the [`SnapshotConfig`](crate::SnapshotConfig) to choose
+    /// which headers are stored, what is redacted, and where it is stored.
+    ///
+    /// See [`TestResponse::assert_snapshot()`](crate::TestResponse::assert_snapshot()) for more.
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::Json;
+    /// use ::axum::routing::Router;
+    /// use ::axum::routing::get;
+    /// use ::serde_json::json;
+    ///
+    /// use ::axum_test::SnapshotConfig;
+    /// use ::axum_test::TestServer;
+    ///
+    /// let app = Router::new()
+    ///     .route(&"/todo", get(|| async {
+    ///         Json(json!({ "id": 123, "description": "buy milk" }))
+    ///     }));
+    ///
+    /// let server = TestServer::new(app)?;
+    /// let config = SnapshotConfig::builder()
+    ///     .redact(&"/id")
+    ///     .build();
+    ///
+    /// server.get(&"/todo").await.assert_snapshot_with_config(&"get_todo", config);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn assert_snapshot_with_config(&self, name: &str, config: SnapshotConfig) {
+        let caller_file = Location::caller().file();
+        let path = snapshot_path(caller_file, config.directory.as_deref(), name);
+        let received = render_snapshot(
+            self.status_code,
+            &self.headers,
+            &config.headers,
+            &self.response_body,
+            &config.redactions,
+        );
+
+        let captured_logs = self.format_captured_logs();
+
+        assert_snapshot(
+            &path,
+            name,
+            &received,
+            is_updating_snapshots(),
+            &captured_logs,
+        );
+    }
+
+    /// Assert that the status code is **within** the 2xx range.
+    /// i.e. The range from 200-299.
+    #[track_caller]
+    pub fn assert_status_success(&self) {
+        let status_code = self.status_code.as_u16();
+        let received_debug = StatusCodeFormatter(self.status_code);
+        let request_format = &self.request_format;
+        let captured_logs = self.format_captured_logs();
+
+        assert!(
+            200 <= status_code && status_code <= 299,
+            "Expect status code within 2xx range, got {received_debug}, for request {request_format}{captured_logs}"
+        );
+    }
+
+    /// Assert that the status code is **outside** the 2xx range.
+    /// i.e. A status code less than 200, or 300 or more.
+    #[track_caller]
+    pub fn assert_status_failure(&self) {
+        let status_code = self.status_code.as_u16();
+        let received_debug = StatusCodeFormatter(self.status_code);
+        let request_format = &self.request_format;
+        let captured_logs = self.format_captured_logs();
+
+        assert!(
+            status_code < 200 || 299 < status_code,
+            "Expect status code outside 2xx range, got {received_debug}, for request {request_format}{captured_logs}",
+        );
+    }
+
+    /// Assert the response status code is 400.
+    #[track_caller]
+    pub fn assert_status_bad_request(&self) {
+        self.assert_status(StatusCode::BAD_REQUEST)
+    }
+
+    /// Assert the response status code is 404.
+    #[track_caller]
+    pub fn assert_status_not_found(&self) {
+        self.assert_status(StatusCode::NOT_FOUND)
+    }
+
+    /// Assert the response status code is 401.
+    #[track_caller]
+    pub fn assert_status_unauthorized(&self) {
+        self.assert_status(StatusCode::UNAUTHORIZED)
+    }
+
+    /// Assert the response status code is 403.
+    #[track_caller]
+    pub fn assert_status_forbidden(&self) {
+        self.assert_status(StatusCode::FORBIDDEN)
+    }
+
+    /// Assert the response status code is 200.
+    #[track_caller]
+    pub fn assert_status_ok(&self) {
+        self.assert_status(StatusCode::OK)
+    }
+
+    /// Assert the response status code is **not** 200.
+    #[track_caller]
+    pub fn assert_status_not_ok(&self) {
+        self.assert_not_status(StatusCode::OK)
+    }
+
+    /// Assert the response status code matches the one given.
+    #[track_caller]
+    pub fn assert_status(&self, expected_status_code: StatusCode) {
+        let status_code = self.status_code.as_u16();
+        let received_debug = StatusCodeFormatter(self.status_code);
         let expected_debug = StatusCodeFormatter(expected_status_code);
         let request_format = &self.request_format;
+        let captured_logs = self.format_captured_logs();
 
         assert_eq!(
             expected_status_code, status_code,
-            "Expected status code {expected_debug}, got {received_debug}, for request {request_format}",
+            "Expected status code {expected_debug}, got {received_debug}, for request {request_format}{captured_logs}",
         );
     }
 
@@ -564,13 +1301,95 @@ impl TestResponse {
     pub fn assert_not_status(&self, expected_status_code: StatusCode) {
         let expected_debug = StatusCodeFormatter(expected_status_code);
         let request_format = &self.request_format;
+        let captured_logs = self.format_captured_logs();
 
         assert_ne!(
             expected_status_code,
             self.status_code(),
-            "Expected status code to not be {expected_debug}, it is, for request {request_format}",
+            "Expected status code to not be {expected_debug}, it is, for request {request_format}{captured_logs}",
+        );
+    }
+
+    /// Asserts the response has a `WWW-Authenticate` challenge for the scheme given,
+    /// such as `Bearer` or `Basic`.
+    ///
+    /// The scheme is matched case-insensitively.
+    #[track_caller]
+    pub fn assert_www_authenticate_scheme(&self, scheme: &str) {
+        self.expect_www_authenticate_challenge(scheme);
+    }
+
+    /// Asserts the `WWW-Authenticate` challenge for the scheme given
+    /// has the `realm` given.
+    #[track_caller]
+    pub fn assert_www_authenticate_realm(&self, scheme: &str, realm: &str) {
+        self.assert_www_authenticate_param(scheme, "realm", realm)
+    }
+
+    /// Asserts the `WWW-Authenticate` challenge for the scheme given
+    /// has the `error` given, such as `invalid_token` for a `Bearer` challenge.
+    #[track_caller]
+    pub fn assert_www_authenticate_error(&self, scheme: &str, error: &str) {
+        self.assert_www_authenticate_param(scheme, "error", error)
+    }
+
+    /// Asserts the `WWW-Authenticate` challenge for the scheme given
+    /// has a parameter matching the name and value given.
+    ///
+    /// The parameter name is matched case-insensitively.
+    #[track_caller]
+    pub fn assert_www_authenticate_param(&self, scheme: &str, name: &str, expected: &str) {
+        let challenge = self.expect_www_authenticate_challenge(scheme);
+        let received = challenge.param(name);
+        let request_format = &self.request_format;
+        let captured_logs = self.format_captured_logs();
+
+        assert_eq!(
+            Some(expected),
+            received,
+            "Expected WWW-Authenticate '{scheme}' parameter '{name}' to match, received challenge '{challenge}', for request {request_format}{captured_logs}",
+        );
+    }
+
+    #[track_caller]
+    fn expect_www_authenticate_challenge(&self, scheme: &str) -> AuthChallenge {
+        let challenges = self.www_authenticate();
+        if let Some(challenge) = challenges
+            .iter()
+            .find(|challenge| challenge.is_scheme(scheme))
+        {
+            return challenge.clone();
+        }
+
+        let request_format = &self.request_format;
+        let captured_logs = self.format_captured_logs();
+        let received = if challenges.is_empty() {
+            "no challenges".to_string()
+        } else {
+            challenges
+                .iter()
+                .map(|challenge| format!("'{challenge}'"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        panic!(
+            "Expected WWW-Authenticate challenge for scheme '{scheme}', received {received}, for request {request_format}{captured_logs}"
         );
     }
+
+    /// Formats the captured logs for adding to the end of assertion failures.
+    ///
+    /// This is empty when no logs were captured.
+    fn format_captured_logs(&self) -> String {
+        #[cfg(feature = "tracing")]
+        if !self.logs.is_empty() {
+            let logs = self.logs.trim_end();
+            return format!("\n\nCaptured logs:\n{logs}");
+        }
+
+        String::new()
+    }
 }
 
 impl From<TestResponse> for Bytes {
@@ -579,6 +1398,97 @@ impl From<TestResponse> for Bytes {
     }
 }
 
+fn format_json_path_surrounding(json_path: &JsonPath, document: &Value) -> String {
+    let (surrounding_path, surrounding) = json_path.find_surrounding(document);
+    let surrounding_json = ::serde_json::to_string_pretty(&surrounding).unwrap_or_default();
+
+    format!("surrounding document at {surrounding_path}:\n{surrounding_json}")
+}
+
+#[cfg(test)]
+mod test_version {
+    use ::axum::routing::get;
+    use ::axum::routing::Router;
+    use ::http::Version;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+
+    async fn route_get_version(version: Version) -> String {
+        format!("{version:?}")
+    }
+
+    #[tokio::test]
+    async fn it_should_be_http_1_1_when_using_mock_transport() {
+        let router = Router::new().route(&"/version", get(route_get_version));
+        let config = TestServerConfig::builder().mock_transport().build();
+        let server = TestServer::new_with_config(router, config).unwrap();
+
+        let response = server.get(&"/version").await;
+
+        assert_eq!(response.version(), Version::HTTP_11);
+    }
+
+    #[tokio::test]
+    async fn it_should_be_http_1_1_when_using_http_transport() {
+        let router = Router::new().route(&"/version", get(route_get_version));
+        let config = TestServerConfig::builder().http_transport().build();
+        let server = TestServer::new_with_config(router, config).unwrap();
+
+        let response = server.get(&"/version").await;
+
+        assert_eq!(response.version(), Version::HTTP_11);
+        response.assert_text(&"HTTP/1.1");
+    }
+
+    #[tokio::test]
+    async fn it_should_be_http_2_when_using_http2_transport() {
+        let router = Router::new().route(&"/version", get(route_get_version));
+        let config = TestServerConfig::builder().http2_transport().build();
+        let server = TestServer::new_with_config(router, config).unwrap();
+
+        let response = server.get(&"/version").await;
+
+        assert_eq!(response.version(), Version::HTTP_2);
+        response.assert_text(&"HTTP/2.0");
+    }
+}
+
+#[cfg(test)]
+mod test_sse_events {
+    use ::axum::response::sse::Event;
+    use ::axum::response::sse::Sse;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::futures_util::stream::iter;
+    use ::futures_util::stream::Stream;
+    use ::std::convert::Infallible;
+
+    use crate::TestServer;
+
+    async fn get_events() -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+        Sse::new(iter(vec![
+            Ok(Event::default().event("first").id("1").data("hello")),
+            Ok(Event::default().data("multi\nline")),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn it_should_return_all_events_from_finished_stream() {
+        let app = Router::new().route("/events", get(get_events));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let events = server.get(&"/events").await.sse_events();
+
+        assert_eq!(events.len(), 2);
+        events[0].assert_event(&"first");
+        events[0].assert_data(&"hello");
+        assert_eq!(events[0].id(), Some("1"));
+        events[1].assert_event(&"message");
+        events[1].assert_data(&"multi\nline");
+    }
+}
+
 #[cfg(test)]
 mod test_assert_success {
     use ::axum::routing::get;
@@ -1003,3 +1913,773 @@ mod test_text {
         assert_eq!(response, "hello!");
     }
 }
+
+#[cfg(test)]
+mod test_assert_snapshot {
+    use crate::SnapshotConfig;
+    use crate::TestServer;
+
+    use ::axum::routing::get;
+    use ::axum::routing::Router;
+    use ::axum::Json;
+    use ::serde_json::json;
+    use ::serde_json::Value;
+    use ::std::env::temp_dir;
+    use ::std::fs::create_dir_all;
+    use ::std::fs::remove_dir_all;
+    use ::std::fs::write;
+    use ::std::path::PathBuf;
+
+    async fn route_get_todo() -> Json<Value> {
+        Json(json!({
+            "id": 123,
+            "description": "buy milk",
+            "tags": [{ "id": 1, "name": "shopping" }],
+        }))
+    }
+
+    fn new_snapshot_dir(name: &str) -> PathBuf {
+        let directory = temp_dir().join("axum-test-snapshots").join(name);
+        let _ = remove_dir_all(&directory);
+        directory
+    }
+
+    #[tokio::test]
+    async fn it_should_match_snapshot_stored_next_to_test() {
+        let app = Router::new().route(&"/todo", get(route_get_todo));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server.get(&"/todo").await.assert_snapshot(&"get_todo");
+    }
+
+    #[tokio::test]
+    async fn it_should_match_snapshot_with_redactions() {
+        let directory = new_snapshot_dir("redactions");
+        let config = SnapshotConfig::builder()
+            .directory(&directory)
+            .redact(&"/id")
+            .redact(&"/tags/*/id")
+            .build();
+        create_dir_all(&directory).unwrap();
+        write(
+            directory.join("test_response__get_todo.snap"),
+            r#"status: 200 (OK)
+content-type: application/json
+
+{
+  "description": "buy milk",
+  "id": "[redacted]",
+  "tags": [
+    {
+      "id": "[redacted]",
+      "name": "shopping"
+    }
+  ]
+}
+"#,
+        )
+        .unwrap();
+
+        let app = Router::new().route(&"/todo", get(route_get_todo));
+        let server = TestServer::new(app).expect("Should create test server");
+        server
+            .get(&"/todo")
+            .await
+            .assert_snapshot_with_config(&"get_todo", config);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Snapshot 'get_todo' is missing")]
+    async fn it_should_panic_when_snapshot_is_missing() {
+        let directory = new_snapshot_dir("missing");
+        let config = SnapshotConfig::builder().directory(&directory).build();
+
+        let app = Router::new().route(&"/todo", get(route_get_todo));
+        let server = TestServer::new(app).expect("Should create test server");
+        server
+            .get(&"/todo")
+            .await
+            .assert_snapshot_with_config(&"get_todo", config);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Snapshot 'get_todo' does not match the response")]
+    async fn it_should_panic_when_response_differs_from_snapshot() {
+        let directory = new_snapshot_dir("differs");
+        let config = SnapshotConfig::builder()
+            .directory(&directory)
+            .without_headers()
+            .build();
+        create_dir_all(&directory).unwrap();
+        write(
+            directory.join("test_response__get_todo.snap"),
+            "status: 404 (Not Found)\n\n",
+        )
+        .unwrap();
+
+        let app = Router::new().route(&"/todo", get(route_get_todo));
+        let server = TestServer::new(app).expect("Should create test server");
+        server
+            .get(&"/todo")
+            .await
+            .assert_snapshot_with_config(&"get_todo", config);
+    }
+}
+
+#[cfg(test)]
+mod test_json_path {
+    use crate::TestServer;
+
+    use ::axum::routing::get;
+    use ::axum::routing::Router;
+    use ::axum::Json;
+    use ::serde_json::json;
+    use ::serde_json::Value;
+
+    async fn route_get_todos() -> Json<Value> {
+        Json(json!({
+            "items": [
+                { "id": 1, "description": "buy milk" },
+                { "id": 2, "description": "walk the dog" },
+            ],
+        }))
+    }
+
+    #[tokio::test]
+    async fn it_should_return_value_at_path() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let id = server
+            .get(&"/todos")
+            .await
+            .json_path::<u32>(&"$.items[1].id");
+
+        assert_eq!(id, 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_return_all_values_matching_wildcards() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let descriptions = server
+            .get(&"/todos")
+            .await
+            .json_path::<Vec<String>>(&"$.items[*].description");
+
+        assert_eq!(descriptions, vec!["buy milk", "walk the dog"]);
+    }
+
+    #[tokio::test]
+    async fn it_should_return_none_from_maybe_json_path_when_missing() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let maybe_id = server
+            .get(&"/todos")
+            .await
+            .maybe_json_path::<u32>(&"$.items[5].id");
+
+        assert_eq!(maybe_id, None);
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "JSON path '$.items[5].id' not found, for request GET /todos\nsurrounding document at $.items:"
+    )]
+    async fn it_should_panic_when_path_is_missing() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let _ = server
+            .get(&"/todos")
+            .await
+            .json_path::<u32>(&"$.items[5].id");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Parsing JSON path 'items', for request GET /todos")]
+    async fn it_should_panic_when_path_is_invalid() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let _ = server.get(&"/todos").await.json_path::<u32>(&"items");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Deserializing response from JSON")]
+    async fn it_should_panic_when_response_is_not_json() {
+        let app = Router::new().route(&"/text", get(|| async { "hello!" }));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let _ = server.get(&"/text").await.json_path::<u32>(&"$.id");
+    }
+}
+
+#[cfg(test)]
+mod test_assert_json_path {
+    use crate::TestServer;
+
+    use ::axum::routing::get;
+    use ::axum::routing::Router;
+    use ::axum::Json;
+    use ::serde_json::json;
+    use ::serde_json::Value;
+
+    async fn route_get_todos() -> Json<Value> {
+        Json(json!({
+            "items": [
+                { "id": 1, "description": "buy milk", "tags": [] },
+                { "id": 2, "description": "walk the dog", "tags": ["pets"] },
+            ],
+        }))
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_value_matches() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+        let response = server.get(&"/todos").await;
+
+        response.assert_json_path(&"$.items[0].description", &"buy milk");
+        response.assert_json_path(&"$.items[-1].tags", &json!(["pets"]));
+        response.assert_json_path(&"$..id", &[1, 2]);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "JSON path '$.items[0].description' does not match")]
+    async fn it_should_panic_when_value_differs() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/todos")
+            .await
+            .assert_json_path(&"$.items[0].description", &"buy bread");
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_path_exists() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+        let response = server.get(&"/todos").await;
+
+        response.assert_json_path_exists(&"$.items[1].tags[0]");
+        response.assert_json_path_exists(&"$.items[0].tags");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "JSON path '$.items[0].tags[0]' not found")]
+    async fn it_should_panic_when_path_does_not_exist() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/todos")
+            .await
+            .assert_json_path_exists(&"$.items[0].tags[0]");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "JSON path '$.items[*].missing' matched nothing")]
+    async fn it_should_panic_when_wildcard_matches_nothing() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/todos")
+            .await
+            .assert_json_path_exists(&"$.items[*].missing");
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_length_matches() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+        let response = server.get(&"/todos").await;
+
+        response.assert_json_path_len(&"$.items", 2);
+        response.assert_json_path_len(&"$.items[0]", 3);
+        response.assert_json_path_len(&"$.items[0].description", 8);
+        response.assert_json_path_len(&"$.items[*].tags[*]", 1);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "JSON path '$.items' has length 2, expected 3")]
+    async fn it_should_panic_when_length_differs() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/todos")
+            .await
+            .assert_json_path_len(&"$.items", 3);
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "JSON path '$.items[0].id' is not an array, object, or string, received 1"
+    )]
+    async fn it_should_panic_when_length_of_number_is_asserted() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/todos")
+            .await
+            .assert_json_path_len(&"$.items[0].id", 1);
+    }
+}
+
+#[cfg(test)]
+mod test_assert_json_contains {
+    use crate::JsonContainsConfig;
+    use crate::TestServer;
+
+    use ::axum::routing::get;
+    use ::axum::routing::Router;
+    use ::axum::Json;
+    use ::serde::Serialize;
+    use ::serde_json::json;
+    use ::serde_json::Value;
+
+    async fn route_get_todos() -> Json<Value> {
+        Json(json!({
+            "total": 2,
+            "average_score": 0.6666,
+            "items": [
+                { "id": 456, "description": "walk the dog", "created_at": "2023-09-01" },
+                { "id": 123, "description": "buy milk", "created_at": "2023-09-02" },
+            ],
+        }))
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_expected_fields_match() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server.get(&"/todos").await.assert_json_contains(&json!({
+            "total": 2,
+            "items": [
+                { "description": "walk the dog" },
+                { "description": "buy milk" },
+            ],
+        }));
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_given_serializable_struct() {
+        #[derive(Serialize)]
+        struct ExpectedTodos {
+            total: u32,
+        }
+
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/todos")
+            .await
+            .assert_json_contains(&ExpectedTodos { total: 2 });
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Response JSON does not contain expected JSON, found 2 differences, for request GET /todos\n    $.items[0].description expected \"buy milk\", received \"walk the dog\"\n    $.items[1].description expected \"walk the dog\", received \"buy milk\""
+    )]
+    async fn it_should_panic_listing_differences_by_path() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server.get(&"/todos").await.assert_json_contains(&json!({
+            "items": [
+                { "description": "buy milk" },
+                { "description": "walk the dog" },
+            ],
+        }));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "$.missing expected true, received (missing)")]
+    async fn it_should_panic_when_field_is_missing() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/todos")
+            .await
+            .assert_json_contains(&json!({ "missing": true }));
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_with_array_order_ignored_paths_and_float_tolerance() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+        let config = JsonContainsConfig::builder()
+            .ignore_array_order()
+            .ignore_path(&"$.items[*].id")
+            .ignore_path(&"$..created_at")
+            .float_tolerance(0.001)
+            .build();
+
+        server
+            .get(&"/todos")
+            .await
+            .assert_json_contains_with_config(
+                &json!({
+                    "average_score": 0.667,
+                    "items": [
+                        { "id": 1, "description": "buy milk", "created_at": "today" },
+                        { "id": 2, "description": "walk the dog", "created_at": "today" },
+                    ],
+                }),
+                config,
+            );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Parsing ignored JSON path 'items', for request GET /todos")]
+    async fn it_should_panic_when_ignored_path_is_invalid() {
+        let app = Router::new().route(&"/todos", get(route_get_todos));
+        let server = TestServer::new(app).expect("Should create test server");
+        let config = JsonContainsConfig::builder().ignore_path(&"items").build();
+
+        server
+            .get(&"/todos")
+            .await
+            .assert_json_contains_with_config(&json!({}), config);
+    }
+}
+
+#[cfg(feature = "json-schema")]
+#[cfg(test)]
+mod test_assert_json_schema {
+    use crate::JsonSchema;
+    use crate::TestServer;
+
+    use ::axum::routing::get;
+    use ::axum::routing::Router;
+    use ::axum::Json;
+    use ::serde_json::json;
+    use ::serde_json::Value;
+    use ::std::env::temp_dir;
+    use ::std::fs::write;
+
+    async fn route_get_todo() -> Json<Value> {
+        Json(json!({
+            "id": "123",
+            "description": "buy milk",
+            "tags": ["shopping", 5],
+        }))
+    }
+
+    fn todo_schema(id_type: &str) -> Value {
+        json!({
+            "type": "object",
+            "required": ["id", "description"],
+            "properties": {
+                "id": { "type": id_type },
+                "description": { "type": "string" },
+                "tags": { "type": "array" },
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_json_matches_schema() {
+        let app = Router::new().route(&"/todo", get(route_get_todo));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/todo")
+            .await
+            .assert_json_schema(todo_schema(&"string"));
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Response JSON does not match schema, found 2 violations, for request GET /todo\n    /id \"123\" is not of type \"integer\"\n    /tags/1 5 is not of type \"string\""
+    )]
+    async fn it_should_panic_listing_every_violation() {
+        let app = Router::new().route(&"/todo", get(route_get_todo));
+        let server = TestServer::new(app).expect("Should create test server");
+        let mut schema = todo_schema(&"integer");
+        schema["properties"]["tags"]["items"] = json!({ "type": "string" });
+
+        server.get(&"/todo").await.assert_json_schema(&schema);
+    }
+
+    #[tokio::test]
+    async fn it_should_read_schema_from_file() {
+        let schema_path = temp_dir().join("axum-test-todo-schema.json");
+        write(&schema_path, todo_schema(&"string").to_string()).unwrap();
+
+        let app = Router::new().route(&"/todo", get(route_get_todo));
+        let server = TestServer::new(app).expect("Should create test server");
+        server
+            .get(&"/todo")
+            .await
+            .assert_json_schema(schema_path.as_path());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Reading JSON schema file, from")]
+    async fn it_should_panic_when_schema_file_does_not_exist() {
+        let app = Router::new().route(&"/todo", get(route_get_todo));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/todo")
+            .await
+            .assert_json_schema(JsonSchema::from_file(&"/does/not/exist.json"));
+    }
+}
+
+#[cfg(feature = "tracing")]
+#[cfg(test)]
+mod test_logs {
+    use ::axum::http::StatusCode;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::tracing::error;
+    use ::tracing::info;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+
+    async fn route_get_todo() -> &'static str {
+        info!(todo_id = 1, "fetching todo");
+        "hello!"
+    }
+
+    async fn route_get_broken() -> StatusCode {
+        error!("database is down");
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    #[tokio::test]
+    async fn it_should_capture_logs_for_each_request() {
+        let app = Router::new()
+            .route("/todo", get(route_get_todo))
+            .route("/broken", get(route_get_broken));
+        let config = TestServerConfig::builder().capture_logs().build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let todo_response = server.get(&"/todo").await;
+        let broken_response = server.get(&"/broken").await;
+
+        assert_eq!(
+            todo_response.logs(),
+            " INFO axum_test::test_response::test_logs: fetching todo todo_id=1\n"
+        );
+        assert_eq!(
+            broken_response.logs(),
+            "ERROR axum_test::test_response::test_logs: database is down\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_not_capture_logs_by_default() {
+        let app = Router::new().route("/todo", get(route_get_todo));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let response = server.get(&"/todo").await;
+
+        assert_eq!(response.logs(), "");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_capture_logs_with_http_transport() {
+        let app = Router::new().route("/todo", get(route_get_todo));
+        let config = TestServerConfig::builder()
+            .capture_logs()
+            .http_transport()
+            .build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let response = server.get(&"/todo").await;
+
+        assert_eq!(response.logs(), "");
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Expected status code 200 (OK), got 500 (Internal Server Error), for request GET /broken
+
+Captured logs:
+ERROR axum_test::test_response::test_logs: database is down"
+    )]
+    async fn it_should_include_logs_when_assertions_fail() {
+        let app = Router::new().route("/broken", get(route_get_broken));
+        let config = TestServerConfig::builder().capture_logs().build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/broken").await.assert_status_ok();
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Expect status code within 2xx range, got 500 (Internal Server Error), for request GET /broken
+
+Captured logs:
+ERROR axum_test::test_response::test_logs: database is down"
+    )]
+    async fn it_should_include_logs_when_expect_success_fails() {
+        let app = Router::new().route("/broken", get(route_get_broken));
+        let config = TestServerConfig::builder().capture_logs().build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/broken").expect_success().await;
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Expect status code outside 2xx range, got 200 (OK), for request GET /todo
+
+Captured logs:
+ INFO axum_test::test_response::test_logs: fetching todo todo_id=1"
+    )]
+    async fn it_should_include_logs_when_expect_failure_fails() {
+        let app = Router::new().route("/todo", get(route_get_todo));
+        let config = TestServerConfig::builder().capture_logs().build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/todo").expect_failure().await;
+    }
+}
+
+#[cfg(test)]
+mod test_www_authenticate {
+    use ::axum::http::header::WWW_AUTHENTICATE;
+    use ::axum::http::HeaderMap;
+    use ::axum::http::HeaderValue;
+    use ::axum::http::StatusCode;
+    use ::axum::response::IntoResponse;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::TestServer;
+
+    async fn route_unauthorized() -> impl IntoResponse {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            WWW_AUTHENTICATE,
+            HeaderValue::from_static(r#"Basic realm="simple""#),
+        );
+        headers.append(
+            WWW_AUTHENTICATE,
+            HeaderValue::from_static(
+                r#"Bearer realm="api", error="invalid_token", error_description="The token expired""#,
+            ),
+        );
+
+        (StatusCode::UNAUTHORIZED, headers)
+    }
+
+    async fn route_ok() -> &'static str {
+        "ok"
+    }
+
+    #[tokio::test]
+    async fn it_should_return_challenges_from_all_headers() {
+        let app = Router::new().route("/unauthorized", get(route_unauthorized));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let challenges = server
+            .get(&"/unauthorized")
+            .expect_failure()
+            .await
+            .www_authenticate();
+
+        assert_eq!(challenges.len(), 2);
+        assert_eq!(challenges[0].scheme(), "Basic");
+        assert_eq!(challenges[0].realm(), Some("simple"));
+        assert_eq!(challenges[1].scheme(), "Bearer");
+        assert_eq!(challenges[1].error(), Some("invalid_token"));
+        assert_eq!(challenges[1].error_description(), Some("The token expired"));
+    }
+
+    #[tokio::test]
+    async fn it_should_return_no_challenges_without_header() {
+        let app = Router::new().route("/ok", get(route_ok));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let challenges = server.get(&"/ok").await.www_authenticate();
+
+        assert_eq!(challenges, vec![]);
+    }
+
+    #[tokio::test]
+    async fn it_should_assert_scheme_realm_and_error() {
+        let app = Router::new().route("/unauthorized", get(route_unauthorized));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        let response = server.get(&"/unauthorized").expect_failure().await;
+
+        response.assert_www_authenticate_scheme("basic");
+        response.assert_www_authenticate_scheme("Bearer");
+        response.assert_www_authenticate_realm("Basic", "simple");
+        response.assert_www_authenticate_realm("Bearer", "api");
+        response.assert_www_authenticate_error("Bearer", "invalid_token");
+        response.assert_www_authenticate_param("Bearer", "Error_Description", "The token expired");
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = r#"Expected WWW-Authenticate challenge for scheme 'Digest', received 'Basic realm="simple"', 'Bearer realm="api", error="invalid_token", error_description="The token expired"', for request GET /unauthorized"#
+    )]
+    async fn it_should_panic_when_scheme_is_missing() {
+        let app = Router::new().route("/unauthorized", get(route_unauthorized));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/unauthorized")
+            .expect_failure()
+            .await
+            .assert_www_authenticate_scheme("Digest");
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Expected WWW-Authenticate challenge for scheme 'Bearer', received no challenges, for request GET /ok"
+    )]
+    async fn it_should_panic_when_there_are_no_challenges() {
+        let app = Router::new().route("/ok", get(route_ok));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/ok")
+            .await
+            .assert_www_authenticate_scheme("Bearer");
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = r#"Expected WWW-Authenticate 'Bearer' parameter 'error' to match, received challenge 'Bearer realm="api", error="invalid_token", error_description="The token expired"', for request GET /unauthorized"#
+    )]
+    async fn it_should_panic_when_error_does_not_match() {
+        let app = Router::new().route("/unauthorized", get(route_unauthorized));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/unauthorized")
+            .expect_failure()
+            .await
+            .assert_www_authenticate_error("Bearer", "insufficient_scope");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = r#"Expected WWW-Authenticate 'Basic' parameter 'charset' to match"#)]
+    async fn it_should_panic_when_param_is_missing() {
+        let app = Router::new().route("/unauthorized", get(route_unauthorized));
+        let server = TestServer::new(app).expect("Should create test server");
+
+        server
+            .get(&"/unauthorized")
+            .expect_failure()
+            .await
+            .assert_www_authenticate_param("Basic", "charset", "UTF-8");
+    }
+}
@@ -1,22 +1,52 @@
 use ::anyhow::Context;
 use ::anyhow::Result;
+use ::axum::response::IntoResponse;
+use ::axum::BoxError;
+use ::axum::Router;
 use ::cookie::Cookie;
 use ::cookie::CookieJar;
+use ::http::header::AUTHORIZATION;
 use ::http::HeaderName;
 use ::http::HeaderValue;
 use ::http::Method;
+use ::http::Request;
+use ::hyper::Body;
 use ::serde::Serialize;
+use ::std::any::type_name;
+use ::std::any::Any;
+use ::std::fmt::Display;
+use ::std::net::SocketAddr;
+use ::std::path::Path;
 use ::std::sync::Arc;
 use ::std::sync::Mutex;
+use ::std::time::Duration;
+use ::std::time::SystemTime;
+use ::tower::Service;
 use ::url::Url;
 
+use crate::internals::basic_authorization_header;
+use crate::internals::bearer_authorization_header;
+#[cfg(unix)]
+use crate::internals::new_unix_socket_path;
 use crate::internals::ExpectedState;
+#[cfg(feature = "openapi")]
+use crate::internals::OpenApiContract;
+#[cfg(feature = "json-schema")]
+use crate::internals::RoutePattern;
+#[cfg(feature = "tls")]
+use crate::internals::TlsSetup;
+use crate::transport_layer::BoxedService;
 use crate::transport_layer::IntoTransportLayer;
 use crate::transport_layer::TransportLayer;
 use crate::transport_layer::TransportLayerBuilder;
+use crate::HarReplay;
+#[cfg(feature = "json-schema")]
+use crate::JsonSchema;
 use crate::TestRequest;
 use crate::TestRequestConfig;
 use crate::TestServerConfig;
+#[cfg(feature = "ws")]
+use crate::TestWebSocket;
 use crate::Transport;
 
 mod server_shared_state;
@@ -64,11 +94,23 @@ const DEFAULT_URL_ADDRESS: &'static str = "http://localhost";
 #[derive(Debug)]
 pub struct TestServer {
     state: Arc<Mutex<ServerSharedState>>,
-    transport: Arc<Mutex<Box<dyn TransportLayer>>>,
+    transport: Arc<Box<dyn TransportLayer>>,
     save_cookies: bool,
+    is_recording_har: bool,
+    #[cfg(feature = "tracing")]
+    is_capturing_logs: bool,
     expected_state: ExpectedState,
     default_content_type: Option<String>,
+    default_timeout: Option<Duration>,
+    default_client_address: Option<SocketAddr>,
     is_http_path_restricted: bool,
+    is_following_redirects: bool,
+    max_redirects: usize,
+    #[cfg(feature = "json-schema")]
+    route_json_schemas: Arc<Vec<(RoutePattern, JsonSchema)>>,
+    #[cfg(feature = "openapi")]
+    maybe_openapi_contract: Option<Arc<OpenApiContract>>,
+    maybe_state: Option<Box<dyn Any + Send + Sync>>,
 }
 
 impl TestServer {
@@ -94,7 +136,27 @@ impl TestServer {
     where
         A: IntoTransportLayer,
     {
-        let shared_state = ServerSharedState::new();
+        #[cfg(feature = "json-schema")]
+        let route_json_schemas = config
+            .route_json_schemas
+            .into_iter()
+            .map(|(route, schema)| {
+                let route_pattern = RoutePattern::parse(&route)
+                    .with_context(|| format!("Parsing route for JSON schema, '{route}'"))?;
+
+                Ok((route_pattern, schema))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        #[cfg(feature = "openapi")]
+        let maybe_openapi_contract = config
+            .openapi_spec
+            .map(|path| OpenApiContract::from_file(&path))
+            .transpose()?
+            .map(Arc::new);
+
+        let is_recording_har = config.record_har.is_some();
+        let shared_state = ServerSharedState::new(config.record_har);
         let shared_state_mutex = Mutex::new(shared_state);
         let state = Arc::new(shared_state_mutex);
 
@@ -102,21 +164,40 @@ impl TestServer {
             None => {
                 let builder = TransportLayerBuilder::new(None, None);
                 let transport = app.into_default_transport(builder)?;
-                Arc::new(Mutex::new(transport))
+                Arc::new(transport)
             }
             Some(Transport::HttpRandomPort) => {
                 let builder = TransportLayerBuilder::new(None, None);
                 let transport = app.into_http_transport_layer(builder)?;
-                Arc::new(Mutex::new(transport))
+                Arc::new(transport)
             }
             Some(Transport::HttpIpPort { ip, port }) => {
                 let builder = TransportLayerBuilder::new(ip, port);
                 let transport = app.into_http_transport_layer(builder)?;
-                Arc::new(Mutex::new(transport))
+                Arc::new(transport)
+            }
+            Some(Transport::Http2) => {
+                let builder = TransportLayerBuilder::new(None, None).http2_only();
+                let transport = app.into_http_transport_layer(builder)?;
+                Arc::new(transport)
+            }
+            #[cfg(feature = "tls")]
+            Some(Transport::Https) => {
+                let tls_setup = TlsSetup::new_self_signed()?;
+                let builder = TransportLayerBuilder::new(None, None).tls(tls_setup);
+                let transport = app.into_http_transport_layer(builder)?;
+                Arc::new(transport)
+            }
+            #[cfg(unix)]
+            Some(Transport::UnixSocket { path }) => {
+                let socket_path = path.unwrap_or_else(new_unix_socket_path);
+                let builder = TransportLayerBuilder::new(None, None).unix_socket(socket_path);
+                let transport = app.into_http_transport_layer(builder)?;
+                Arc::new(transport)
             }
             Some(Transport::MockHttp) => {
                 let transport = app.into_mock_transport_layer()?;
-                Arc::new(Mutex::new(transport))
+                Arc::new(transport)
             }
         };
 
@@ -129,14 +210,174 @@ impl TestServer {
             state,
             transport,
             save_cookies: config.save_cookies,
+            is_recording_har,
+            #[cfg(feature = "tracing")]
+            is_capturing_logs: config.capture_logs,
             expected_state,
             default_content_type: config.default_content_type,
+            default_timeout: config.default_timeout,
+            default_client_address: config.default_client_address,
             is_http_path_restricted: config.restrict_requests_with_http_schema,
+            is_following_redirects: config.follow_redirects,
+            max_redirects: config.max_redirects,
+            #[cfg(feature = "json-schema")]
+            route_json_schemas: Arc::new(route_json_schemas),
+            #[cfg(feature = "openapi")]
+            maybe_openapi_contract,
+            maybe_state: None,
         };
 
         Ok(this)
     }
 
+    /// This will run the given Axum `Router`, using the state provided.
+    ///
+    /// A clone of the state is kept by the `TestServer`.
+    /// It can be retrieved using [`TestServer::state()`](crate::TestServer::state()),
+    /// to seed or inspect it around requests.
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::extract::State;
+    /// use ::axum::routing::get;
+    /// use ::axum::Router;
+    /// use ::std::sync::Arc;
+    /// use ::std::sync::Mutex;
+    ///
+    /// use ::axum_test::TestServer;
+    ///
+    /// type Todos = Arc<Mutex<Vec<String>>>;
+    ///
+    /// async fn get_todo_count(State(todos): State<Todos>) -> String {
+    ///     todos.lock().unwrap().len().to_string()
+    /// }
+    ///
+    /// let app = Router::new()
+    ///     .route(&"/todos/count", get(get_todo_count));
+    ///
+    /// let server = TestServer::new_with_state(app, Todos::default())?;
+    /// server.state::<Todos>().lock().unwrap().push("buy milk".to_string());
+    ///
+    /// server.get(&"/todos/count").await.assert_text("1");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_state<S>(router: Router<S>, state: S) -> Result<Self>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        Self::new_with_state_and_config(router, state, TestServerConfig::default())
+    }
+
+    /// This is the same as [`TestServer::new_with_state()`],
+    /// however you can customise the configuration.
+    pub fn new_with_state_and_config<S>(
+        router: Router<S>,
+        state: S,
+        config: TestServerConfig,
+    ) -> Result<Self>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let app: Router<()> = router.with_state(state.clone());
+        let mut this = Self::new_with_config(app, config)?;
+        this.maybe_state = Some(Box::new(state));
+
+        Ok(this)
+    }
+
+    /// This will run any [`tower::Service`](::tower::Service) handling requests,
+    /// where the response implements [`IntoResponse`](::axum::response::IntoResponse).
+    ///
+    /// This allows apps to be tested exactly as they are deployed,
+    /// such as a `Router` wrapped in layers using [`tower::ServiceBuilder`](::tower::ServiceBuilder).
+    ///
+    /// Errors returned by the service are returned as errors when sending the request,
+    /// rather than being turned into a response.
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::Router;
+    /// use ::axum::http::HeaderValue;
+    /// use ::axum::response::Response;
+    /// use ::axum::routing::get;
+    /// use ::axum_test::TestServer;
+    /// use ::tower::ServiceBuilder;
+    ///
+    /// let router = Router::new()
+    ///     .route(&"/ping", get(|| async { "pong!" }));
+    /// let app = ServiceBuilder::new()
+    ///     .map_response(|mut response: Response| {
+    ///         response.headers_mut().insert("x-layered", HeaderValue::from_static("yes"));
+    ///         response
+    ///     })
+    ///     .service(router);
+    ///
+    /// let server = TestServer::new_service(app)?;
+    ///
+    /// let response = server.get(&"/ping").await;
+    /// response.assert_text("pong!");
+    /// assert_eq!(response.header("x-layered"), "yes");
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_service<S>(service: S) -> Result<Self>
+    where
+        S: Service<Request<Body>> + Clone + Send + 'static,
+        S::Response: IntoResponse + 'static,
+        S::Error: Into<BoxError> + 'static,
+        S::Future: Send + 'static,
+    {
+        Self::new_service_with_config(service, TestServerConfig::default())
+    }
+
+    /// This is the same as [`TestServer::new_service()`],
+    /// however you can customise the configuration.
+    pub fn new_service_with_config<S>(service: S, config: TestServerConfig) -> Result<Self>
+    where
+        S: Service<Request<Body>> + Clone + Send + 'static,
+        S::Response: IntoResponse + 'static,
+        S::Error: Into<BoxError> + 'static,
+        S::Future: Send + 'static,
+    {
+        Self::new_with_config(BoxedService::new(service), config)
+    }
+
+    /// Returns the state given to [`TestServer::new_with_state()`],
+    /// if it was created with state, and is of the type given.
+    #[must_use]
+    pub fn maybe_state<S>(&self) -> Option<&S>
+    where
+        S: 'static,
+    {
+        self.maybe_state
+            .as_ref()
+            .and_then(|state| state.downcast_ref::<S>())
+    }
+
+    /// Returns the state given to [`TestServer::new_with_state()`].
+    ///
+    /// This is a clone of the state used by the application,
+    /// so shared values (such as those behind an `Arc`) can be seeded or inspected.
+    ///
+    /// If the `TestServer` was not created with state,
+    /// or the type given does not match, then this will panic.
+    #[must_use]
+    pub fn state<S>(&self) -> &S
+    where
+        S: 'static,
+    {
+        let state_type = type_name::<S>();
+
+        self.maybe_state::<S>()
+            .with_context(|| format!("Cannot find state of type {state_type}, for TestServer"))
+            .unwrap()
+    }
+
     /// Creates a HTTP GET request to the path.
     pub fn get(&self, path: &str) -> TestRequest {
         self.method(Method::GET, path)
@@ -164,31 +405,176 @@ impl TestServer {
 
     /// Creates a HTTP request, to the method and path provided.
     pub fn method(&self, method: Method, path: &str) -> TestRequest {
-        let config = self.test_request_config(method.clone(), path);
+        let config = self.test_request_config(method, path);
+
+        self.request_with_config(config)
+    }
+
+    pub(crate) fn request_with_config(&self, config: TestRequestConfig) -> TestRequest {
+        let request_format = config.request_format.clone();
         let maybe_request = TestRequest::new(self.state.clone(), self.transport.clone(), config);
 
         maybe_request
             .with_context(|| {
-                format!("Trying to create internal request, for request {method} {path}")
+                format!("Trying to create internal request, for request {request_format}")
             })
             .unwrap()
     }
 
+    /// Connects to the path as a WebSocket,
+    /// returning a [`TestWebSocket`](crate::TestWebSocket) for sending and receiving messages.
+    ///
+    /// This requires the server to be running with a HTTP transport,
+    /// by setting the [`TestServerConfig`](crate::TestServerConfig) `transport` field to `Transport::HttpRandomPort` or `Transport::HttpIpPort`.
+    /// It will panic when using mock transport (the default).
+    ///
+    /// To add headers or cookies to the upgrade request,
+    /// use [`TestRequest::into_websocket()`](crate::TestRequest::into_websocket()) instead.
+    #[cfg(feature = "ws")]
+    pub async fn get_websocket(&self, path: &str) -> TestWebSocket {
+        self.get(path).into_websocket().await
+    }
+
+    /// Replays the requests in a HAR file against this server,
+    /// comparing each response against the one recorded.
+    ///
+    /// HAR files can be exported from browser dev tools and many proxies,
+    /// or recorded using the `record_har` setting on the [`TestServerConfig`](crate::TestServerConfig).
+    ///
+    /// Awaiting the returned [`HarReplay`](crate::HarReplay) sends the requests,
+    /// and returns a report of any differences found.
+    /// This will panic if the file cannot be read.
+    pub fn replay_har<P>(&self, path: P) -> HarReplay<'_>
+    where
+        P: AsRef<Path>,
+    {
+        HarReplay::new(self, path.as_ref())
+    }
+
+    /// Writes out the requests recorded so far to the HAR file,
+    /// set using the `record_har` setting on the [`TestServerConfig`](crate::TestServerConfig).
+    ///
+    /// This also happens when the `TestServer` is dropped,
+    /// where any error is ignored.
+    /// Call this to have the error returned instead.
+    ///
+    /// This does nothing if HAR recording is not turned on.
+    pub fn save_har(&mut self) -> Result<()> {
+        ServerSharedState::save_har(&mut self.state)
+    }
+
+    /// Returns the operations in the OpenAPI spec which no request has exercised so far,
+    /// i.e. `GET /users/{id} (getUser)`.
+    ///
+    /// This is empty when no spec is set on the [`TestServerConfig`](crate::TestServerConfig).
+    #[cfg(feature = "openapi")]
+    #[must_use]
+    pub fn unexercised_openapi_operations(&self) -> Vec<String> {
+        self.maybe_openapi_contract
+            .as_ref()
+            .map(|contract| contract.unexercised_operations())
+            .unwrap_or_default()
+    }
+
+    /// Asserts every operation in the OpenAPI spec has been exercised by a request,
+    /// panicking with those which have not.
+    ///
+    /// Call this at the end of a test, after all of the requests have been sent.
+    /// This always passes when no spec is set on the [`TestServerConfig`](crate::TestServerConfig).
+    #[cfg(feature = "openapi")]
+    #[track_caller]
+    pub fn assert_all_openapi_operations_exercised(&self) {
+        let unexercised_operations = self.unexercised_openapi_operations();
+        if !unexercised_operations.is_empty() {
+            let count = unexercised_operations.len();
+            let operations = unexercised_operations.join("\n    ");
+            panic!("OpenAPI operations never exercised, found {count}:\n    {operations}");
+        }
+    }
+
     /// Returns the local web address for the test server,
     /// if an address is available.
     ///
     /// The address is available when running as a real web server,
     /// by setting the [`TestServerConfig`](crate::TestServerConfig) `transport` field to `Transport::HttpRandomPort` or `Transport::HttpRandomPort`.
+    /// When using `Transport::Https` this will be an `https://` address.
     ///
     /// This will return `None` when there is mock HTTP transport (the default).
     pub fn server_address(&self) -> Option<Url> {
         self.url()
     }
 
+    /// Returns true if the server is still running.
+    ///
+    /// It will return false after calling [`TestServer::shutdown()`](crate::TestServer::shutdown()),
+    /// or if the server has crashed.
+    /// When the server has crashed, the next request will fail with the reason why.
+    pub fn is_running(&self) -> bool {
+        self.transport.is_running()
+    }
+
+    /// Stops the server, and waits for it to finish.
+    ///
+    /// Any requests made afterwards will fail.
+    pub async fn shutdown(&self) {
+        self.transport.shutdown().await
+    }
+
+    /// Gracefully stops the server, and waits for it to finish.
+    ///
+    /// Requests already in flight are allowed to complete.
+    /// New requests will fail, and when running over HTTP this
+    /// includes the connection error returned by the server.
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::Router;
+    /// use ::axum::routing::get;
+    /// use ::std::future::IntoFuture;
+    /// use ::std::time::Duration;
+    /// use ::tokio::time::sleep;
+    ///
+    /// use ::axum_test::TestServer;
+    /// use ::axum_test::TestServerConfig;
+    /// use ::axum_test::Transport;
+    ///
+    /// let app = Router::new()
+    ///     .route(&"/slow", get(|| async {
+    ///         sleep(Duration::from_millis(100)).await;
+    ///         "done"
+    ///     }));
+    ///
+    /// let config = TestServerConfig {
+    ///     transport: Some(Transport::HttpRandomPort),
+    ///     ..TestServerConfig::default()
+    /// };
+    /// let server = TestServer::new_with_config(app, config)?;
+    ///
+    /// let (response, _) = ::tokio::join!(
+    ///     server.get(&"/slow").into_future(),
+    ///     async {
+    ///         sleep(Duration::from_millis(10)).await;
+    ///         server.graceful_shutdown().await
+    ///     },
+    /// );
+    ///
+    /// response.assert_text(&"done");
+    /// assert!(!server.is_running());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn graceful_shutdown(&self) {
+        self.transport.graceful_shutdown().await
+    }
+
     /// Adds a single cookie to be included on *all* future requests.
     ///
-    /// If a cookie with the same name already exists,
+    /// If a cookie with the same name and path already exists,
     /// then it will be replaced.
+    /// If the cookie has a `Path`, `Domain`, `Secure`, or expiry,
+    /// then it is only sent to requests which a browser would send it to.
     pub fn add_cookie(&mut self, cookie: Cookie) {
         ServerSharedState::add_cookie(&mut self.state, cookie)
             .with_context(|| format!("Trying to call add_cookie"))
@@ -197,7 +583,7 @@ impl TestServer {
 
     /// Adds extra cookies to be used on *all* future requests.
     ///
-    /// Any cookies which have the same name as the new cookies,
+    /// Any cookies which have the same name and path as the new cookies,
     /// will get replaced.
     pub fn add_cookies(&mut self, cookies: CookieJar) {
         ServerSharedState::add_cookies(&mut self.state, cookies)
@@ -212,6 +598,52 @@ impl TestServer {
             .unwrap()
     }
 
+    /// Sets the time used for cookie expiry, in place of the system time.
+    ///
+    /// This allows testing cookies which expire,
+    /// without waiting for them to expire.
+    ///
+    /// ```rust
+    /// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+    /// #
+    /// use ::axum::Router;
+    /// use ::axum_test::TestServer;
+    /// use ::std::time::Duration;
+    /// use ::std::time::SystemTime;
+    ///
+    /// let app = Router::new();
+    /// let mut server = TestServer::new(app)?;
+    ///
+    /// server.set_cookie_clock(SystemTime::now());
+    ///
+    /// // Cookies with a `Max-Age` of under an hour will no longer be sent.
+    /// server.advance_cookie_clock(Duration::from_secs(60 * 60));
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_cookie_clock(&mut self, now: SystemTime) {
+        ServerSharedState::set_cookie_clock(&mut self.state, Some(now))
+            .context("Trying to call set_cookie_clock")
+            .unwrap()
+    }
+
+    /// Moves the time used for cookie expiry forward by the duration given.
+    ///
+    /// If the cookie clock has not been set, this starts from the system time.
+    pub fn advance_cookie_clock(&mut self, duration: Duration) {
+        ServerSharedState::advance_cookie_clock(&mut self.state, duration)
+            .context("Trying to call advance_cookie_clock")
+            .unwrap()
+    }
+
+    /// Returns to using the system time for cookie expiry.
+    pub fn clear_cookie_clock(&mut self) {
+        ServerSharedState::set_cookie_clock(&mut self.state, None)
+            .context("Trying to call clear_cookie_clock")
+            .unwrap()
+    }
+
     /// Requests made using this `TestServer` will save their cookies for future requests to send.
     ///
     /// This behaviour is off by default.
@@ -274,6 +706,41 @@ impl TestServer {
             .unwrap()
     }
 
+    /// Sets the `Authorization` header sent with all future requests,
+    /// to use the bearer token given.
+    ///
+    /// This replaces any `Authorization` header already set.
+    pub fn authorization_bearer<T>(&mut self, token: T)
+    where
+        T: Display,
+    {
+        let header_value = bearer_authorization_header(token)
+            .context("Trying to call authorization_bearer")
+            .unwrap();
+
+        ServerSharedState::set_header(&mut self.state, AUTHORIZATION, header_value)
+            .context("Trying to call authorization_bearer")
+            .unwrap()
+    }
+
+    /// Sets the `Authorization` header sent with all future requests,
+    /// to use HTTP Basic authentication with the username and password given.
+    ///
+    /// This replaces any `Authorization` header already set.
+    pub fn authorization_basic<U, P>(&mut self, username: U, password: P)
+    where
+        U: Display,
+        P: Display,
+    {
+        let header_value = basic_authorization_header(username, password)
+            .context("Trying to call authorization_basic")
+            .unwrap();
+
+        ServerSharedState::set_header(&mut self.state, AUTHORIZATION, header_value)
+            .context("Trying to call authorization_basic")
+            .unwrap()
+    }
+
     /// Clears all headers set so far.
     pub fn clear_headers(&mut self) {
         ServerSharedState::clear_headers(&mut self.state)
@@ -282,12 +749,7 @@ impl TestServer {
     }
 
     pub(crate) fn url(&self) -> Option<Url> {
-        let locked = self
-            .transport
-            .lock()
-            .expect("Failed to lock TransportLayer");
-
-        locked.url().map(|url| url.clone())
+        self.transport.url().cloned()
     }
 
     pub(crate) fn test_request_config(&self, method: Method, path: &str) -> TestRequestConfig {
@@ -297,11 +759,34 @@ impl TestServer {
 
         TestRequestConfig {
             is_saving_cookies: self.save_cookies,
+            is_recording_har: self.is_recording_har,
+            is_following_redirects: self.is_following_redirects,
+            max_redirects: self.max_redirects,
+            #[cfg(feature = "tracing")]
+            is_capturing_logs: self.is_capturing_logs,
             expected_state: self.expected_state,
             content_type: self.default_content_type.clone(),
             full_request_url: build_url(url, path, self.is_http_path_restricted),
             request_format: RequestPathFormatter::new(method, path.to_string()),
+            timeout: self.default_timeout,
+            client_address: self.default_client_address,
+            #[cfg(feature = "json-schema")]
+            route_json_schemas: self.route_json_schemas.clone(),
+            #[cfg(feature = "openapi")]
+            maybe_openapi_contract: self.maybe_openapi_contract.clone(),
+        }
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        if !self.is_recording_har {
+            return;
         }
+
+        // Errors cannot be returned from here, and panicking could abort the test run.
+        // Call `save_har()` to handle them.
+        let _ = ServerSharedState::save_har(&mut self.state);
     }
 }
 
@@ -490,6 +975,403 @@ mod test_server_address {
         let is_match = address_regex.is_match(&server.server_address().unwrap().to_string());
         assert!(is_match);
     }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn it_should_return_https_address_when_using_https_transport() {
+        let app = Router::new();
+        let config = TestServerConfig {
+            transport: Some(Transport::Https),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let address_regex = Regex::new("^https://127\\.0\\.0\\.1:[0-9]+/$").unwrap();
+        let is_match = address_regex.is_match(&server.server_address().unwrap().to_string());
+        assert!(is_match);
+    }
+}
+
+#[cfg(test)]
+mod test_is_running {
+    use ::axum::Router;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    #[tokio::test]
+    async fn it_should_be_running_when_mocked() {
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server =
+            TestServer::new_with_config(Router::new(), config).expect("Should create test server");
+
+        assert!(server.is_running());
+    }
+
+    #[tokio::test]
+    async fn it_should_be_running_when_using_http() {
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server =
+            TestServer::new_with_config(Router::new(), config).expect("Should create test server");
+
+        assert!(server.is_running());
+    }
+}
+
+#[cfg(test)]
+mod test_shutdown {
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn get_ping() -> &'static str {
+        "pong!"
+    }
+
+    #[tokio::test]
+    async fn it_should_not_be_running_after_shutdown_when_mocked() {
+        let app = Router::new().route("/ping", get(get_ping));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.shutdown().await;
+
+        assert!(!server.is_running());
+    }
+
+    #[tokio::test]
+    async fn it_should_not_be_running_after_shutdown_when_using_http() {
+        let app = Router::new().route("/ping", get(get_ping));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.shutdown().await;
+
+        assert!(!server.is_running());
+    }
+
+    #[tokio::test]
+    async fn it_should_serve_requests_before_shutdown() {
+        let app = Router::new().route("/ping", get(get_ping));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/ping").await.assert_text(&"pong!");
+        server.shutdown().await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Test server has been shut down")]
+    async fn it_should_fail_requests_after_shutdown_when_mocked() {
+        let app = Router::new().route("/ping", get(get_ping));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.shutdown().await;
+
+        server.get(&"/ping").await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Test server has been shut down")]
+    async fn it_should_fail_requests_after_shutdown_when_using_http() {
+        let app = Router::new().route("/ping", get(get_ping));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.shutdown().await;
+
+        server.get(&"/ping").await;
+    }
+}
+
+#[cfg(test)]
+mod test_graceful_shutdown {
+    use ::axum::extract::State;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::future::IntoFuture;
+    use ::std::sync::Arc;
+    use ::std::time::Duration;
+    use ::tokio::join;
+    use ::tokio::pin;
+    use ::tokio::sync::Notify;
+    use ::tokio::time::timeout;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    #[derive(Clone, Default)]
+    struct SlowRequest {
+        started: Arc<Notify>,
+        release: Arc<Notify>,
+    }
+
+    async fn get_slow(State(slow_request): State<SlowRequest>) -> &'static str {
+        slow_request.started.notify_one();
+        slow_request.release.notified().await;
+
+        "done"
+    }
+
+    async fn assert_in_flight_request_completes(transport: Transport) {
+        let slow_request = SlowRequest::default();
+        let app = Router::new()
+            .route("/slow", get(get_slow))
+            .with_state(slow_request.clone());
+        let config = TestServerConfig {
+            transport: Some(transport),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let (response, _) = join!(server.get(&"/slow").into_future(), async {
+            slow_request.started.notified().await;
+            join!(server.graceful_shutdown(), async {
+                slow_request.release.notify_one();
+            });
+        });
+
+        response.assert_text(&"done");
+        assert!(!server.is_running());
+    }
+
+    async fn send_request_during_graceful_shutdown(transport: Transport) {
+        let slow_request = SlowRequest::default();
+        let app = Router::new()
+            .route("/slow", get(get_slow))
+            .with_state(slow_request.clone());
+        let config = TestServerConfig {
+            transport: Some(transport),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        join!(server.get(&"/slow").into_future(), async {
+            slow_request.started.notified().await;
+            join!(server.graceful_shutdown(), async {
+                server.get(&"/slow").await;
+                slow_request.release.notify_one();
+            });
+        });
+    }
+
+    #[tokio::test]
+    async fn it_should_complete_in_flight_requests_when_mocked() {
+        assert_in_flight_request_completes(Transport::MockHttp).await;
+    }
+
+    #[tokio::test]
+    async fn it_should_complete_in_flight_requests_when_using_http() {
+        assert_in_flight_request_completes(Transport::HttpRandomPort).await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Test server has been shut down")]
+    async fn it_should_fail_new_requests_when_mocked() {
+        send_request_during_graceful_shutdown(Transport::MockHttp).await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Test server is shutting down gracefully")]
+    async fn it_should_fail_new_requests_with_connection_error_when_using_http() {
+        send_request_during_graceful_shutdown(Transport::HttpRandomPort).await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Test server has been shut down")]
+    async fn it_should_fail_requests_after_graceful_shutdown_finishes() {
+        let app = Router::new()
+            .route("/slow", get(get_slow))
+            .with_state(SlowRequest::default());
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.graceful_shutdown().await;
+
+        server.get(&"/slow").await;
+    }
+
+    #[tokio::test]
+    async fn it_should_wait_for_streamed_bodies_to_be_dropped_when_mocked() {
+        let slow_request = SlowRequest::default();
+        let app = Router::new()
+            .route("/slow", get(get_slow))
+            .with_state(slow_request.clone());
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        slow_request.release.notify_one();
+        let response = server.get(&"/slow").stream().await;
+
+        let graceful_shutdown = server.graceful_shutdown();
+        pin!(graceful_shutdown);
+        let result = timeout(Duration::from_millis(50), &mut graceful_shutdown).await;
+        assert!(result.is_err(), "Should wait for the body to be dropped");
+
+        ::std::mem::drop(response);
+        graceful_shutdown.await;
+        assert!(!server.is_running());
+    }
+}
+
+#[cfg(test)]
+mod test_record_har {
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Json;
+    use ::axum::Router;
+    use ::cookie::Cookie;
+    use ::futures_util::FutureExt;
+    use ::serde_json::json;
+    use ::serde_json::Value;
+    use ::std::env::temp_dir;
+    use ::std::fs::read;
+    use ::std::fs::remove_file;
+    use ::std::fs::write;
+    use ::std::future::pending;
+    use ::std::future::IntoFuture;
+    use ::std::panic::AssertUnwindSafe;
+    use ::std::time::Duration;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+
+    #[tokio::test]
+    async fn it_should_write_har_file_when_dropped() {
+        let har_path = temp_dir().join("axum-test-it-should-write-har-file.har");
+        let _ = remove_file(&har_path);
+
+        let config = TestServerConfig::builder().record_har(&har_path).build();
+        let app = Router::new()
+            .route("/ping", get(|| async { "pong!" }))
+            .route(
+                "/echo",
+                post(|Json(body): Json<Value>| async { Json(body) }),
+            );
+        let server = TestServer::new_with_config(app, config).unwrap();
+        server
+            .get(&"/ping")
+            .add_query_param("name", "Joe")
+            .add_cookie(Cookie::new("session", "abc"))
+            .await;
+        server
+            .post(&"/echo")
+            .json(&json!({ "hello": "world" }))
+            .await;
+        ::std::mem::drop(server);
+
+        let har: Value = ::serde_json::from_slice(&read(&har_path).unwrap()).unwrap();
+        let entries = har["log"]["entries"].as_array().unwrap();
+
+        assert_eq!(har["log"]["version"], "1.2");
+        assert_eq!(entries.len(), 2);
+
+        let get_entry = &entries[0];
+        assert_eq!(get_entry["request"]["method"], "GET");
+        assert_eq!(
+            get_entry["request"]["url"],
+            "http://localhost/ping?name=Joe"
+        );
+        assert_eq!(
+            get_entry["request"]["queryString"],
+            json!([{ "name": "name", "value": "Joe" }])
+        );
+        assert_eq!(get_entry["request"]["cookies"][0]["name"], "session");
+        assert_eq!(get_entry["response"]["status"], 200);
+        assert_eq!(get_entry["response"]["content"]["text"], "pong!");
+        assert!(get_entry["time"].as_f64().unwrap() >= 0.0);
+
+        let post_entry = &entries[1];
+        assert_eq!(post_entry["request"]["method"], "POST");
+        assert_eq!(
+            post_entry["request"]["postData"]["mimeType"],
+            "application/json"
+        );
+        assert_eq!(
+            post_entry["request"]["postData"]["text"],
+            r#"{"hello":"world"}"#
+        );
+        assert_eq!(
+            post_entry["response"]["content"]["text"],
+            r#"{"hello":"world"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_record_requests_which_time_out() {
+        let har_path = temp_dir().join("axum-test-it-should-record-requests-which-time-out.har");
+        let _ = remove_file(&har_path);
+
+        let app = Router::new().route("/pending", get(|| pending::<()>()));
+        let config = TestServerConfig::builder().record_har(&har_path).build();
+        let mut server = TestServer::new_with_config(app, config).unwrap();
+        let result = server
+            .get(&"/pending")
+            .timeout(Duration::from_millis(10))
+            .into_future();
+        let _ = AssertUnwindSafe(result).catch_unwind().await;
+        server.save_har().unwrap();
+
+        let har: Value = ::serde_json::from_slice(&read(&har_path).unwrap()).unwrap();
+        let entry = &har["log"]["entries"][0];
+
+        assert_eq!(entry["request"]["url"], "http://localhost/pending");
+        assert_eq!(entry["response"]["status"], 0);
+        assert!(entry["response"]["_error"]
+            .as_str()
+            .unwrap()
+            .contains("Request timed out"));
+    }
+
+    #[tokio::test]
+    async fn it_should_return_error_from_save_har_when_it_cannot_be_written() {
+        let not_a_dir = temp_dir().join("axum-test-save-har-not-a-dir");
+        write(&not_a_dir, "").unwrap();
+        let har_path = not_a_dir.join("recording.har");
+
+        let config = TestServerConfig::builder().record_har(&har_path).build();
+        let app = Router::new().route("/ping", get(|| async { "pong!" }));
+        let mut server = TestServer::new_with_config(app, config).unwrap();
+        server.get(&"/ping").await;
+
+        assert!(server.save_har().is_err());
+
+        // Should not panic on drop.
+        ::std::mem::drop(server);
+    }
 }
 
 #[cfg(test)]
@@ -606,6 +1488,123 @@ mod test_clear_cookies {
     }
 }
 
+#[cfg(test)]
+mod test_saved_cookies {
+    use crate::TestServer;
+    use crate::TestServerConfig;
+
+    use ::axum::http::header::SET_COOKIE;
+    use ::axum::response::IntoResponse;
+    use ::axum::routing::get;
+    use ::axum::routing::post;
+    use ::axum::Router;
+    use ::axum_extra::extract::cookie::CookieJar as AxumCookieJar;
+    use ::std::time::Duration;
+    use ::std::time::SystemTime;
+
+    async fn route_get_cookies(cookies: AxumCookieJar) -> String {
+        let mut all_cookies = cookies
+            .iter()
+            .map(|cookie| format!("{}={}", cookie.name(), cookie.value()))
+            .collect::<Vec<String>>();
+        all_cookies.sort();
+
+        all_cookies.join(&", ")
+    }
+
+    async fn route_post_login() -> impl IntoResponse {
+        [(SET_COOKIE, "session=abc; Path=/; Max-Age=3600")]
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_cookies_deleted_by_the_server() {
+        let app = Router::new()
+            .route("/login", post(route_post_login))
+            .route(
+                "/logout",
+                post(|| async { [(SET_COOKIE, "session=; Path=/; Max-Age=0")] }),
+            )
+            .route("/cookies", get(route_get_cookies));
+        let config = TestServerConfig::builder().save_cookies().build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.post(&"/login").await;
+        server.get(&"/cookies").await.assert_text("session=abc");
+
+        server.post(&"/logout").await;
+        server.get(&"/cookies").await.assert_text("");
+    }
+
+    #[tokio::test]
+    async fn it_should_only_send_cookies_matching_the_path() {
+        let app = Router::new()
+            .route(
+                "/admin/login",
+                post(|| async { [(SET_COOKIE, "admin=xyz; Path=/admin")] }),
+            )
+            .route("/cookies", get(route_get_cookies))
+            .route("/admin/cookies", get(route_get_cookies));
+        let config = TestServerConfig::builder().save_cookies().build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.post(&"/admin/login").await;
+
+        server.get(&"/cookies").await.assert_text("");
+        server.get(&"/admin/cookies").await.assert_text("admin=xyz");
+    }
+
+    #[tokio::test]
+    async fn it_should_withhold_secure_cookies_without_tls() {
+        let app = Router::new()
+            .route(
+                "/secure-login",
+                post(|| async { [(SET_COOKIE, "secure-session=abc; Path=/; Secure")] }),
+            )
+            .route("/cookies", get(route_get_cookies));
+        let config = TestServerConfig::builder().save_cookies().build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.post(&"/secure-login").await;
+
+        server.get(&"/cookies").await.assert_text("");
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_cookies_expired_by_the_cookie_clock() {
+        let app = Router::new()
+            .route("/login", post(route_post_login))
+            .route("/cookies", get(route_get_cookies));
+        let config = TestServerConfig::builder().save_cookies().build();
+        let mut server =
+            TestServer::new_with_config(app, config).expect("Should create test server");
+        server.set_cookie_clock(SystemTime::now());
+
+        server.post(&"/login").await;
+        server.advance_cookie_clock(Duration::from_secs(3599));
+        server.get(&"/cookies").await.assert_text("session=abc");
+
+        server.advance_cookie_clock(Duration::from_secs(1));
+        server.get(&"/cookies").await.assert_text("");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_cookies_again_when_cookie_clock_is_cleared() {
+        let app = Router::new()
+            .route("/login", post(route_post_login))
+            .route("/cookies", get(route_get_cookies));
+        let config = TestServerConfig::builder().save_cookies().build();
+        let mut server =
+            TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.post(&"/login").await;
+        server.set_cookie_clock(SystemTime::now() + Duration::from_secs(7200));
+        server.get(&"/cookies").await.assert_text("");
+
+        server.clear_cookie_clock();
+        server.get(&"/cookies").await.assert_text("session=abc");
+    }
+}
+
 #[cfg(test)]
 mod test_add_header {
     use super::*;
@@ -688,45 +1687,115 @@ mod test_clear_headers {
 
     struct TestHeader(Vec<u8>);
 
-    #[async_trait]
-    impl<S: Sync> FromRequestParts<S> for TestHeader {
-        type Rejection = (StatusCode, &'static str);
+    #[async_trait]
+    impl<S: Sync> FromRequestParts<S> for TestHeader {
+        type Rejection = (StatusCode, &'static str);
+
+        async fn from_request_parts(
+            parts: &mut Parts,
+            _state: &S,
+        ) -> Result<TestHeader, Self::Rejection> {
+            parts
+                .headers
+                .get(HeaderName::from_static(TEST_HEADER_NAME))
+                .map(|v| TestHeader(v.as_bytes().to_vec()))
+                .ok_or((StatusCode::BAD_REQUEST, "Missing test header"))
+        }
+    }
+
+    async fn ping_header(TestHeader(header): TestHeader) -> Vec<u8> {
+        header
+    }
+
+    #[tokio::test]
+    async fn it_should_not_send_headers_cleared_by_server() {
+        // Build an application with a route.
+        let app = Router::new().route("/header", get(ping_header));
+
+        // Run the server.
+        let mut server = TestServer::new(app).expect("Should create test server");
+        server.add_header(
+            HeaderName::from_static(TEST_HEADER_NAME),
+            HeaderValue::from_static(TEST_HEADER_CONTENT),
+        );
+        server.clear_headers();
+
+        // Send a request with the header
+        let response = server.get(&"/header").await;
+
+        // Check it sent back the right text
+        response.assert_status_bad_request();
+        response.assert_text("Missing test header");
+    }
+}
+
+#[cfg(test)]
+mod test_authorization {
+    use super::*;
+
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::http::HeaderMap;
+
+    async fn get_authorization(headers: HeaderMap) -> String {
+        headers
+            .get_all(AUTHORIZATION)
+            .iter()
+            .map(|value| value.to_str().unwrap())
+            .collect::<Vec<&str>>()
+            .join(" | ")
+    }
+
+    #[tokio::test]
+    async fn it_should_send_bearer_token_with_all_requests() {
+        let app = Router::new().route("/authorization", get(get_authorization));
+        let mut server = TestServer::new(app).expect("Should create test server");
+        server.authorization_bearer("abc123");
+
+        server
+            .get(&"/authorization")
+            .await
+            .assert_text("Bearer abc123");
+        server
+            .get(&"/authorization")
+            .await
+            .assert_text("Bearer abc123");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_basic_credentials_with_all_requests() {
+        let app = Router::new().route("/authorization", get(get_authorization));
+        let mut server = TestServer::new(app).expect("Should create test server");
+        server.authorization_basic("Aladdin", "open sesame");
 
-        async fn from_request_parts(
-            parts: &mut Parts,
-            _state: &S,
-        ) -> Result<TestHeader, Self::Rejection> {
-            parts
-                .headers
-                .get(HeaderName::from_static(TEST_HEADER_NAME))
-                .map(|v| TestHeader(v.as_bytes().to_vec()))
-                .ok_or((StatusCode::BAD_REQUEST, "Missing test header"))
-        }
+        server
+            .get(&"/authorization")
+            .await
+            .assert_text("Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
     }
 
-    async fn ping_header(TestHeader(header): TestHeader) -> Vec<u8> {
-        header
+    #[tokio::test]
+    async fn it_should_replace_existing_authorization() {
+        let app = Router::new().route("/authorization", get(get_authorization));
+        let mut server = TestServer::new(app).expect("Should create test server");
+        server.add_header(AUTHORIZATION, HeaderValue::from_static("Bearer added"));
+        server.authorization_bearer("first");
+        server.authorization_basic("Aladdin", "open sesame");
+
+        server
+            .get(&"/authorization")
+            .await
+            .assert_text("Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
     }
 
     #[tokio::test]
-    async fn it_should_not_send_headers_cleared_by_server() {
-        // Build an application with a route.
-        let app = Router::new().route("/header", get(ping_header));
-
-        // Run the server.
+    async fn it_should_be_removed_by_clear_headers() {
+        let app = Router::new().route("/authorization", get(get_authorization));
         let mut server = TestServer::new(app).expect("Should create test server");
-        server.add_header(
-            HeaderName::from_static(TEST_HEADER_NAME),
-            HeaderValue::from_static(TEST_HEADER_CONTENT),
-        );
+        server.authorization_bearer("abc123");
         server.clear_headers();
 
-        // Send a request with the header
-        let response = server.get(&"/header").await;
-
-        // Check it sent back the right text
-        response.assert_status_bad_request();
-        response.assert_text("Missing test header");
+        server.get(&"/authorization").await.assert_text("");
     }
 }
 
@@ -1180,3 +2249,532 @@ mod test_expect_failure {
         server.get(&"/accepted").await;
     }
 }
+
+#[cfg(test)]
+mod test_concurrent_requests {
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    use ::axum::extract::State;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::sync::Arc;
+    use ::std::time::Duration;
+    use ::tokio::sync::Barrier;
+    use ::tokio::time::timeout;
+
+    /// Both requests must be inside this handler at the same time,
+    /// for either of them to get past the barrier.
+    async fn get_barrier(State(barrier): State<Arc<Barrier>>) -> &'static str {
+        match timeout(Duration::from_secs(5), barrier.wait()).await {
+            Ok(_) => "interleaved",
+            Err(_) => "serialized",
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_interleave_requests_using_mock_transport() {
+        let config = TestServerConfig::builder().mock_transport().build();
+        let app = Router::new()
+            .route("/barrier", get(get_barrier))
+            .with_state(Arc::new(Barrier::new(2)));
+        let server = TestServer::new_with_config(app, config).unwrap();
+
+        let (first, second) = ::tokio::join!(server.get(&"/barrier"), server.get(&"/barrier"));
+
+        first.assert_text(&"interleaved");
+        second.assert_text(&"interleaved");
+    }
+
+    #[tokio::test]
+    async fn it_should_interleave_requests_using_http_transport() {
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let app = Router::new()
+            .route("/barrier", get(get_barrier))
+            .with_state(Arc::new(Barrier::new(2)));
+        let server = TestServer::new_with_config(app, config).unwrap();
+
+        let (first, second) = ::tokio::join!(server.get(&"/barrier"), server.get(&"/barrier"));
+
+        first.assert_text(&"interleaved");
+        second.assert_text(&"interleaved");
+    }
+}
+
+#[cfg(test)]
+mod test_new_with_state {
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    use ::axum::extract::State;
+    use ::axum::routing::get;
+    use ::axum::routing::put;
+    use ::axum::Router;
+    use ::std::sync::Arc;
+    use ::std::sync::Mutex;
+
+    type Todos = Arc<Mutex<Vec<String>>>;
+
+    async fn get_todos(State(todos): State<Todos>) -> String {
+        todos.lock().unwrap().join(", ")
+    }
+
+    async fn put_todo(State(todos): State<Todos>, body: String) {
+        todos.lock().unwrap().push(body);
+    }
+
+    #[tokio::test]
+    async fn it_should_run_router_with_state_given() {
+        let todos = Todos::new(Mutex::new(vec!["buy milk".to_string()]));
+        let app = Router::new().route("/todos", get(get_todos));
+        let server = TestServer::new_with_state(app, todos).unwrap();
+
+        server.get(&"/todos").await.assert_text(&"buy milk");
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_seeding_state_before_requests() {
+        let app = Router::new().route("/todos", get(get_todos));
+        let server = TestServer::new_with_state(app, Todos::default()).unwrap();
+
+        server
+            .state::<Todos>()
+            .lock()
+            .unwrap()
+            .push("walk dog".to_string());
+
+        server.get(&"/todos").await.assert_text(&"walk dog");
+    }
+
+    #[tokio::test]
+    async fn it_should_allow_inspecting_state_after_requests() {
+        let app = Router::new().route("/todos", put(put_todo));
+        let server = TestServer::new_with_state(app, Todos::default()).unwrap();
+
+        server.put(&"/todos").text(&"feed cat").await;
+
+        let todos = server.state::<Todos>().lock().unwrap().clone();
+        assert_eq!(todos, vec!["feed cat".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn it_should_share_state_when_using_http_transport() {
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let app = Router::new().route("/todos", put(put_todo));
+        let server = TestServer::new_with_state_and_config(app, Todos::default(), config).unwrap();
+
+        server.put(&"/todos").text(&"feed cat").await;
+
+        let todos = server.state::<Todos>().lock().unwrap().clone();
+        assert_eq!(todos, vec!["feed cat".to_string()]);
+    }
+}
+
+#[cfg(test)]
+mod test_new_service {
+    use ::axum::response::Response;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::http::HeaderValue;
+    use ::http::Request;
+    use ::hyper::Body;
+    use ::tower::service_fn;
+    use ::tower::ServiceBuilder;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn get_ping() -> &'static str {
+        "pong!"
+    }
+
+    fn add_layered_header(mut response: Response) -> Response {
+        let header_value = HeaderValue::from_static("yes");
+        response.headers_mut().insert("x-layered", header_value);
+        response
+    }
+
+    #[tokio::test]
+    async fn it_should_run_layered_router_with_mock_transport() {
+        let router = Router::new().route("/ping", get(get_ping));
+        let app = ServiceBuilder::new()
+            .map_response(add_layered_header)
+            .service(router);
+
+        let server = TestServer::new_service(app).expect("Should create test server");
+
+        let response = server.get(&"/ping").await;
+        response.assert_text(&"pong!");
+        assert_eq!(response.header("x-layered"), "yes");
+    }
+
+    #[tokio::test]
+    async fn it_should_run_layered_router_with_http_transport() {
+        let router = Router::new().route("/ping", get(get_ping));
+        let app = ServiceBuilder::new()
+            .map_response(add_layered_header)
+            .service(router);
+
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server =
+            TestServer::new_service_with_config(app, config).expect("Should create test server");
+
+        let response = server.get(&"/ping").await;
+        response.assert_text(&"pong!");
+        assert_eq!(response.header("x-layered"), "yes");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "the service is down")]
+    async fn it_should_fail_request_when_service_errors() {
+        let app = service_fn(|_: Request<Body>| async {
+            Err::<String, _>(::std::io::Error::other("the service is down"))
+        });
+
+        let server = TestServer::new_service(app).expect("Should create test server");
+
+        let _ = server.get(&"/ping").await;
+    }
+}
+
+#[cfg(test)]
+mod test_state {
+    use crate::TestServer;
+
+    use ::axum::Router;
+
+    #[test]
+    fn it_should_return_none_when_created_without_state() {
+        let server = TestServer::new(Router::new()).unwrap();
+
+        assert!(server.maybe_state::<u32>().is_none());
+    }
+
+    #[test]
+    fn it_should_return_none_when_type_does_not_match() {
+        let server = TestServer::new_with_state(Router::new(), 123_u32).unwrap();
+
+        assert!(server.maybe_state::<String>().is_none());
+    }
+
+    #[test]
+    fn it_should_return_state_when_type_matches() {
+        let server = TestServer::new_with_state(Router::new(), 123_u32).unwrap();
+
+        assert_eq!(*server.state::<u32>(), 123);
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_should_panic_when_created_without_state() {
+        let server = TestServer::new(Router::new()).unwrap();
+
+        let _ = server.state::<u32>();
+    }
+}
+
+#[cfg(feature = "json-schema")]
+#[cfg(test)]
+mod test_route_json_schemas {
+    use ::axum::http::StatusCode;
+    use ::axum::routing::get;
+    use ::axum::Json;
+    use ::axum::Router;
+    use ::serde_json::json;
+    use ::serde_json::Value;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+
+    fn user_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["id", "name"],
+            "properties": {
+                "id": { "type": "integer" },
+                "name": { "type": "string" },
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_response_matches_route_schema() {
+        let app = Router::new().route(
+            "/users/:id",
+            get(|| async { Json(json!({ "id": 1, "name": "Joe" })) }),
+        );
+        let config = TestServerConfig::builder()
+            .json_schema_for_route(&"GET /users/:id", user_schema())
+            .build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/users/1").await.assert_status_ok();
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Response JSON does not match schema for route '/broken-users/{id}', found 2 violations, for request GET /broken-users/1"
+    )]
+    async fn it_should_panic_when_response_does_not_match_route_schema() {
+        let app = Router::new().route(
+            "/broken-users/:id",
+            get(|| async { Json(json!({ "id": "1" })) }),
+        );
+        let config = TestServerConfig::builder()
+            .json_schema_for_route(&"/broken-users/{id}", user_schema())
+            .build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/broken-users/1").await;
+    }
+
+    #[tokio::test]
+    async fn it_should_not_validate_unsuccessful_responses() {
+        let app = Router::new().route(
+            "/missing-users/:id",
+            get(|| async { (StatusCode::NOT_FOUND, Json(json!({ "error": "not found" }))) }),
+        );
+        let config = TestServerConfig::builder()
+            .json_schema_for_route(&"/missing-users/:id", user_schema())
+            .build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server
+            .get(&"/missing-users/1")
+            .await
+            .assert_status_not_found();
+    }
+
+    #[tokio::test]
+    async fn it_should_not_validate_responses_which_are_not_json() {
+        let app = Router::new().route("/text/:id", get(|| async { "hello!" }));
+        let config = TestServerConfig::builder()
+            .json_schema_for_route(&"/text/:id", user_schema())
+            .build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/text/1").await.assert_text("hello!");
+    }
+
+    #[tokio::test]
+    async fn it_should_use_most_specific_route_schema() {
+        let app = Router::new()
+            .route(
+                "/users/:id",
+                get(|| async { Json(json!({ "id": 1, "name": "Joe" })) }),
+            )
+            .route(
+                "/users/me",
+                get(|| async { Json(json!({ "email": "joe@example.com" })) }),
+            );
+        let me_schema = json!({
+            "type": "object",
+            "required": ["email"],
+        });
+        let config = TestServerConfig::builder()
+            .json_schema_for_route(&"GET /users/:id", user_schema())
+            .json_schema_for_route(&"GET /users/me", me_schema)
+            .build();
+        let server = TestServer::new_with_config(app, config).unwrap();
+
+        server.get(&"/users/me").await.assert_status_ok();
+        server.get(&"/users/1").await.assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn it_should_error_when_route_is_invalid() {
+        let config = TestServerConfig::builder()
+            .json_schema_for_route(&"users/:id", user_schema())
+            .build();
+
+        let result = TestServer::new_with_config(Router::new(), config);
+
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(feature = "openapi")]
+#[cfg(test)]
+mod test_openapi {
+    use ::axum::routing::get;
+    use ::axum::Json;
+    use ::axum::Router;
+    use ::serde_json::json;
+    use ::serde_json::Value;
+    use ::std::env::temp_dir;
+    use ::std::fs::write;
+    use ::std::path::PathBuf;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+
+    const SPEC_YAML: &'static str = r#"
+openapi: 3.0.3
+info:
+  title: Users
+  version: 1.0.0
+paths:
+  /users/{id}:
+    get:
+      operationId: getUser
+      parameters:
+        - name: id
+          in: path
+          required: true
+          schema:
+            type: integer
+      responses:
+        '200':
+          description: A user
+          content:
+            application/json:
+              schema:
+                type: object
+                required: [id, name]
+                properties:
+                  id:
+                    type: integer
+                  name:
+                    type: string
+  /users:
+    get:
+      operationId: listUsers
+      responses:
+        '200':
+          description: All users
+"#;
+
+    fn write_spec(name: &str) -> PathBuf {
+        let spec_path = temp_dir().join(format!("axum-test-{name}.yaml"));
+        write(&spec_path, SPEC_YAML).unwrap();
+        spec_path
+    }
+
+    async fn get_user() -> Json<Value> {
+        Json(json!({ "id": 1, "name": "Joe" }))
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_requests_match_the_spec() {
+        let app = Router::new().route("/users/:id", get(get_user));
+        let config = TestServerConfig::builder()
+            .openapi_spec(write_spec("it-should-pass-when-requests-match-the-spec"))
+            .build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/users/1").await.assert_status_ok();
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = r#"Request and response do not match the OpenAPI spec, found 1 violations, for request GET /users/abc
+    GET /users/{id} (getUser), path parameter 'id' (root) "abc" is not of type "integer""#
+    )]
+    async fn it_should_panic_when_requests_do_not_match_the_spec() {
+        let app = Router::new().route("/users/:id", get(get_user));
+        let config = TestServerConfig::builder()
+            .openapi_spec(write_spec(
+                "it-should-panic-when-requests-do-not-match-the-spec",
+            ))
+            .build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/users/abc").await;
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Request and response do not match the OpenAPI spec, found 1 violations, for request GET /broken-users/1
+    no operation in the OpenAPI spec matches GET /broken-users/1"
+    )]
+    async fn it_should_panic_when_requests_are_not_in_the_spec() {
+        let app = Router::new().route("/broken-users/:id", get(|| async { "hello!" }));
+        let config = TestServerConfig::builder()
+            .openapi_spec(write_spec(
+                "it-should-panic-when-requests-are-not-in-the-spec",
+            ))
+            .build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/broken-users/1").await;
+    }
+
+    #[tokio::test]
+    async fn it_should_list_unexercised_operations() {
+        let app = Router::new().route("/users/:id", get(get_user));
+        let config = TestServerConfig::builder()
+            .openapi_spec(write_spec("it-should-list-unexercised-operations"))
+            .build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        assert_eq!(
+            server.unexercised_openapi_operations(),
+            vec!["GET /users (listUsers)", "GET /users/{id} (getUser)"]
+        );
+
+        server.get(&"/users/1").await;
+
+        assert_eq!(
+            server.unexercised_openapi_operations(),
+            vec!["GET /users (listUsers)"]
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "OpenAPI operations never exercised, found 1:
+    GET /users (listUsers)")]
+    async fn it_should_panic_when_asserting_with_unexercised_operations() {
+        let app = Router::new().route("/users/:id", get(get_user));
+        let config = TestServerConfig::builder()
+            .openapi_spec(write_spec(
+                "it-should-panic-when-asserting-with-unexercised-operations",
+            ))
+            .build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/users/1").await;
+
+        server.assert_all_openapi_operations_exercised();
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_asserting_when_all_operations_are_exercised() {
+        let app = Router::new()
+            .route("/users/:id", get(get_user))
+            .route("/users", get(|| async { "all users" }));
+        let config = TestServerConfig::builder()
+            .openapi_spec(write_spec(
+                "it-should-pass-asserting-when-all-operations-are-exercised",
+            ))
+            .build();
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get(&"/users/1").await;
+        server.get(&"/users").await;
+
+        server.assert_all_openapi_operations_exercised();
+    }
+
+    #[tokio::test]
+    async fn it_should_error_when_spec_cannot_be_read() {
+        let config = TestServerConfig::builder()
+            .openapi_spec(temp_dir().join("axum-test-openapi-spec-does-not-exist.yaml"))
+            .build();
+
+        let result = TestServer::new_with_config(Router::new(), config);
+
+        assert!(result.is_err());
+    }
+}
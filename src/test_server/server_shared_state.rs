@@ -5,30 +5,63 @@ use ::cookie::CookieJar;
 use ::http::HeaderName;
 use ::http::HeaderValue;
 use ::serde::Serialize;
+use ::std::path::PathBuf;
 use ::std::sync::Arc;
 use ::std::sync::Mutex;
+use ::std::time::Duration;
+use ::std::time::SystemTime;
+use ::url::Url;
 
 use crate::internals::with_this_mut;
+use crate::internals::CookieStore;
+use crate::internals::HarEntry;
+use crate::internals::HarRecorder;
 use crate::internals::QueryParamsStore;
 
 #[derive(Debug)]
 pub(crate) struct ServerSharedState {
-    cookies: CookieJar,
+    cookies: CookieStore,
+    maybe_cookie_clock: Option<SystemTime>,
     query_params: QueryParamsStore,
     headers: Vec<(HeaderName, HeaderValue)>,
+    maybe_har_recorder: Option<HarRecorder>,
 }
 
 impl ServerSharedState {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(maybe_har_path: Option<PathBuf>) -> Self {
         Self {
-            cookies: CookieJar::new(),
+            cookies: CookieStore::new(),
+            maybe_cookie_clock: None,
             query_params: QueryParamsStore::new(),
             headers: Vec::new(),
+            maybe_har_recorder: maybe_har_path.map(HarRecorder::new),
         }
     }
 
-    pub(crate) fn cookies<'a>(&'a self) -> &'a CookieJar {
-        &self.cookies
+    /// Returns the cookies to send with a request to the url given.
+    pub(crate) fn cookies_for_url(&self, url: &Url) -> CookieJar {
+        self.cookies.cookies_for_url(url, self.now())
+    }
+
+    /// The time used for cookie expiry.
+    /// This is the system time, unless the cookie clock has been set.
+    fn now(&self) -> SystemTime {
+        self.maybe_cookie_clock.unwrap_or_else(SystemTime::now)
+    }
+
+    /// Returns the cookies to send with a request to the url given,
+    /// for when there is no lock already held on the state.
+    pub(crate) fn find_cookies_for_url(
+        this: &mut Arc<Mutex<Self>>,
+        url: &Url,
+    ) -> Result<CookieJar> {
+        with_this_mut(this, "find_cookies_for_url", |this| {
+            this.cookies_for_url(url)
+        })
+    }
+
+    pub(crate) fn cookie_clock(this: &mut Arc<Mutex<Self>>) -> Result<SystemTime> {
+        with_this_mut(this, "cookie_clock", |this| this.now())
     }
 
     pub(crate) fn query_params<'a>(&'a self) -> &'a QueryParamsStore {
@@ -39,11 +72,14 @@ impl ServerSharedState {
         &self.headers
     }
 
-    /// Adds the given cookies.
+    /// Stores the cookies from `Set-Cookie` headers,
+    /// returned by a request to the url given.
     ///
-    /// They will be stored over the top of the existing cookies.
+    /// They will be stored over the top of the existing cookies,
+    /// and cookies which have expired will be removed.
     pub(crate) fn add_cookies_by_header<'a, I>(
         this: &mut Arc<Mutex<Self>>,
+        request_url: &Url,
         cookie_headers: I,
     ) -> Result<()>
     where
@@ -57,20 +93,17 @@ impl ServerSharedState {
                     .unwrap();
 
                 let cookie: Cookie<'static> = Cookie::parse(cookie_header_str)?.into_owned();
-                this.cookies.add(cookie);
+                let now = this.now();
+                this.cookies.add_from_response(cookie, request_url, now);
             }
 
             Ok(()) as Result<()>
         })?
     }
 
-    /// Adds the given cookies.
-    ///
-    /// They will be stored over the top of the existing cookies.
+    /// Removes all stored cookies.
     pub(crate) fn clear_cookies(this: &mut Arc<Mutex<Self>>) -> Result<()> {
-        with_this_mut(this, "clear_cookies", |this| {
-            this.cookies = CookieJar::new();
-        })
+        with_this_mut(this, "clear_cookies", |this| this.cookies.clear())
     }
 
     /// Adds the given cookies.
@@ -78,15 +111,39 @@ impl ServerSharedState {
     /// They will be stored over the top of the existing cookies.
     pub(crate) fn add_cookies(this: &mut Arc<Mutex<Self>>, cookies: CookieJar) -> Result<()> {
         with_this_mut(this, "add_cookies", |this| {
+            let now = this.now();
             for cookie in cookies.iter() {
-                this.cookies.add(cookie.to_owned());
+                this.cookies.add(cookie.clone().into_owned(), now);
             }
         })
     }
 
     pub(crate) fn add_cookie(this: &mut Arc<Mutex<Self>>, cookie: Cookie) -> Result<()> {
         with_this_mut(this, "add_cookie", |this| {
-            this.cookies.add(cookie.into_owned());
+            let now = this.now();
+            this.cookies.add(cookie.into_owned(), now);
+        })
+    }
+
+    /// Sets the time used for cookie expiry, in place of the system time.
+    /// Passing `None` returns to using the system time.
+    pub(crate) fn set_cookie_clock(
+        this: &mut Arc<Mutex<Self>>,
+        maybe_now: Option<SystemTime>,
+    ) -> Result<()> {
+        with_this_mut(this, "set_cookie_clock", |this| {
+            this.maybe_cookie_clock = maybe_now;
+        })
+    }
+
+    /// Moves the time used for cookie expiry forward,
+    /// starting from the system time if the clock has not been set.
+    pub(crate) fn advance_cookie_clock(
+        this: &mut Arc<Mutex<Self>>,
+        duration: Duration,
+    ) -> Result<()> {
+        with_this_mut(this, "advance_cookie_clock", |this| {
+            this.maybe_cookie_clock = Some(this.now() + duration);
         })
     }
 
@@ -123,4 +180,35 @@ impl ServerSharedState {
     ) -> Result<()> {
         with_this_mut(this, "add_header", |this| this.headers.push((name, value)))
     }
+
+    /// Sets the header, replacing any already set with the same name.
+    pub(crate) fn set_header(
+        this: &mut Arc<Mutex<Self>>,
+        name: HeaderName,
+        value: HeaderValue,
+    ) -> Result<()> {
+        with_this_mut(this, "set_header", |this| {
+            this.headers.retain(|(header_name, _)| *header_name != name);
+            this.headers.push((name, value));
+        })
+    }
+
+    /// Records the entry, if HAR recording is turned on.
+    pub(crate) fn add_har_entry(this: &mut Arc<Mutex<Self>>, entry: HarEntry) -> Result<()> {
+        with_this_mut(this, "add_har_entry", |this| {
+            if let Some(har_recorder) = this.maybe_har_recorder.as_mut() {
+                har_recorder.add_entry(entry);
+            }
+        })
+    }
+
+    /// Writes out the HAR file, if HAR recording is turned on.
+    pub(crate) fn save_har(this: &mut Arc<Mutex<Self>>) -> Result<()> {
+        with_this_mut(this, "save_har", |this| {
+            match this.maybe_har_recorder.as_ref() {
+                Some(har_recorder) => har_recorder.save(),
+                None => Ok(()),
+            }
+        })?
+    }
 }
@@ -1,6 +1,14 @@
+use ::std::net::SocketAddr;
+use ::std::path::PathBuf;
+use ::std::time::Duration;
+
+#[cfg(feature = "json-schema")]
+use crate::JsonSchema;
 use crate::TestServerConfigBuilder;
 use crate::Transport;
 
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+
 /// This is for customising the [`TestServer`](crate::TestServer) on construction.
 ///
 /// It implements [`Default`] to ease building configurations:
@@ -43,7 +51,8 @@ pub struct TestServerConfig {
     /// or if it should run on a named or random IP address.
     ///
     /// The default is to use mocking, apart from services built using [`axum::extract::connect_info::IntoMakeServiceWithConnectInfo`](axum::extract::connect_info::IntoMakeServiceWithConnectInfo)
-    /// (this is because it needs a real TCP stream).
+    /// (these default to a real TCP stream, so handlers see the real client address).
+    /// They can still be mocked by setting this to [`Transport::MockHttp`](crate::Transport::MockHttp).
     pub transport: Option<Transport>,
 
     /// Set for the server to save cookies that are returned,
@@ -51,6 +60,10 @@ pub struct TestServerConfig {
     ///
     /// This is useful for automatically saving session cookies (and similar)
     /// like a browser would do.
+    /// Cookies follow the rules from [RFC 6265](https://datatracker.ietf.org/doc/html/rfc6265),
+    /// so they are removed when they expire or are deleted,
+    /// and only sent to requests with a matching path and domain.
+    /// `Secure` cookies are only sent when using `https`.
     ///
     /// **Defaults** to false (being turned off).
     pub save_cookies: bool,
@@ -84,10 +97,117 @@ pub struct TestServerConfig {
     /// **Defaults** to false (being turned off).
     pub restrict_requests_with_http_schema: bool,
 
+    /// Set for requests to follow redirects,
+    /// returning the response at the end of the redirects.
+    ///
+    /// The responses along the way are available using
+    /// [`TestResponse::redirect_history()`](crate::TestResponse::redirect_history()).
+    ///
+    /// This can be overridden on a per request basis using
+    /// [`TestRequest::follow_redirects()`](crate::TestRequest::follow_redirects())
+    /// and [`TestRequest::do_not_follow_redirects()`](crate::TestRequest::do_not_follow_redirects()).
+    ///
+    /// **Defaults** to false (being turned off).
+    pub follow_redirects: bool,
+
+    /// The most redirects a request will follow.
+    /// Requests which are redirected more times than this will fail.
+    ///
+    /// **Defaults** to 10.
+    pub max_redirects: usize,
+
     /// Set the default content type for all requests created by the `TestServer`.
     ///
     /// This overrides the default 'best efforts' approach of requests.
     pub default_content_type: Option<String>,
+
+    /// Set a timeout for all requests created by the `TestServer`.
+    /// Requests which take longer will fail,
+    /// rather than leaving the test waiting forever.
+    ///
+    /// This can be overridden on a per request basis using
+    /// [`TestRequest::timeout()`](crate::TestRequest::timeout()).
+    ///
+    /// **Defaults** to `None` (no timeout).
+    pub default_timeout: Option<Duration>,
+
+    /// Set the client address used by all mocked requests,
+    /// when building the `ConnectInfo` of a
+    /// [`IntoMakeServiceWithConnectInfo`](::axum::extract::connect_info::IntoMakeServiceWithConnectInfo).
+    ///
+    /// This can be overridden on a per request basis using
+    /// [`TestRequest::client_address()`](crate::TestRequest::client_address()).
+    ///
+    /// **Defaults** to `None`, which uses
+    /// [`DEFAULT_MOCK_CLIENT_ADDRESS`](crate::transport_layer::DEFAULT_MOCK_CLIENT_ADDRESS).
+    pub default_client_address: Option<SocketAddr>,
+
+    /// Set to record every request and response to a HAR file,
+    /// at the path given.
+    ///
+    /// The file is written when the `TestServer` is dropped,
+    /// including when a test panics.
+    /// Any error writing it there is ignored,
+    /// so call [`TestServer::save_har()`](crate::TestServer::save_har()) to check for them.
+    /// It uses the [HAR 1.2](http://www.softwareishard.com/blog/har-12-spec/) format,
+    /// which can be opened by browser dev tools and many HTTP tools.
+    ///
+    /// Responses read using [`TestRequest::stream()`](crate::TestRequest::stream()) are not recorded.
+    ///
+    /// **Defaults** to `None` (no recording).
+    pub record_har: Option<PathBuf>,
+
+    /// JSON Schemas to validate successful JSON responses against,
+    /// chosen by the route of the request.
+    ///
+    /// Routes are paths which may start with a method,
+    /// and may use Axum or OpenAPI style parameters
+    /// (i.e. `GET /users/:id` or `/users/{id}`).
+    /// When multiple routes match the request, the most specific is used
+    /// (i.e. `/users/me` over `/users/:id`),
+    /// and then the one added first.
+    ///
+    /// Responses with a status in the 2xx range, and a JSON content type, are validated.
+    /// If the body does not match the schema, the request will panic
+    /// listing every violation found.
+    ///
+    /// **Defaults** to none.
+    #[cfg(feature = "json-schema")]
+    pub route_json_schemas: Vec<(String, JsonSchema)>,
+
+    /// Set to check every request and response against an
+    /// [OpenAPI 3.x](https://spec.openapis.org/oas/v3.1.0) spec,
+    /// read from the JSON or YAML file at the path given.
+    ///
+    /// Each request is matched to an operation by its method and path template.
+    /// The parameters, request body, status code, response headers,
+    /// and response body are then validated against that operation.
+    /// Any mismatch will panic, listing every violation found.
+    ///
+    /// Operations which were never exercised are listed when the `TestServer` is dropped,
+    /// and can be retrieved using
+    /// [`TestServer::unexercised_openapi_operations()`](crate::TestServer::unexercised_openapi_operations()).
+    ///
+    /// Responses read using [`TestRequest::stream()`](crate::TestRequest::stream()) are not checked.
+    ///
+    /// **Defaults** to `None` (no checking).
+    #[cfg(feature = "openapi")]
+    pub openapi_spec: Option<PathBuf>,
+
+    /// Set to capture the [`tracing`](::tracing) events emitted whilst each request is handled.
+    ///
+    /// They are available using [`TestResponse::logs()`](crate::TestResponse::logs()),
+    /// and are included when assertions on the response fail.
+    /// This includes failures from [`TestRequest::expect_success()`](crate::TestRequest::expect_success())
+    /// and [`TestRequest::expect_failure()`](crate::TestRequest::expect_failure()).
+    ///
+    /// Events are only captured when using mock transport,
+    /// as real web servers handle requests on other tasks.
+    /// Whilst a request is being handled, its events are not sent to any other subscriber.
+    ///
+    /// **Defaults** to false (being turned off).
+    #[cfg(feature = "tracing")]
+    pub capture_logs: bool,
 }
 
 impl TestServerConfig {
@@ -114,7 +234,18 @@ impl Default for TestServerConfig {
             save_cookies: false,
             expect_success_by_default: false,
             restrict_requests_with_http_schema: false,
+            follow_redirects: false,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
             default_content_type: None,
+            default_timeout: None,
+            default_client_address: None,
+            record_har: None,
+            #[cfg(feature = "json-schema")]
+            route_json_schemas: vec![],
+            #[cfg(feature = "openapi")]
+            openapi_spec: None,
+            #[cfg(feature = "tracing")]
+            capture_logs: false,
         }
     }
 }
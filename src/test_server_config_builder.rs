@@ -1,5 +1,10 @@
 use ::std::net::IpAddr;
+use ::std::net::SocketAddr;
+use ::std::path::PathBuf;
+use ::std::time::Duration;
 
+#[cfg(feature = "json-schema")]
+use crate::JsonSchema;
 use crate::TestServerConfig;
 use crate::Transport;
 
@@ -51,6 +56,20 @@ impl TestServerConfigBuilder {
         self.transport(Transport::HttpIpPort { ip, port })
     }
 
+    pub fn http2_transport(self) -> Self {
+        self.transport(Transport::Http2)
+    }
+
+    #[cfg(feature = "tls")]
+    pub fn https_transport(self) -> Self {
+        self.transport(Transport::Https)
+    }
+
+    #[cfg(unix)]
+    pub fn unix_socket_transport(self, path: Option<PathBuf>) -> Self {
+        self.transport(Transport::UnixSocket { path })
+    }
+
     pub fn mock_transport(self) -> Self {
         self.transport(Transport::MockHttp)
     }
@@ -70,11 +89,70 @@ impl TestServerConfigBuilder {
         self
     }
 
+    pub fn follow_redirects(mut self) -> Self {
+        self.config.follow_redirects = true;
+        self
+    }
+
+    pub fn do_not_follow_redirects(mut self) -> Self {
+        self.config.follow_redirects = false;
+        self
+    }
+
+    pub fn max_redirects(mut self, max_redirects: usize) -> Self {
+        self.config.max_redirects = max_redirects;
+        self
+    }
+
     pub fn default_content_type(mut self, content_type: &str) -> Self {
         self.config.default_content_type = Some(content_type.to_string());
         self
     }
 
+    pub fn default_timeout(mut self, timeout: Duration) -> Self {
+        self.config.default_timeout = Some(timeout);
+        self
+    }
+
+    pub fn default_client_address(mut self, client_address: SocketAddr) -> Self {
+        self.config.default_client_address = Some(client_address);
+        self
+    }
+
+    pub fn record_har<P>(mut self, path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.config.record_har = Some(path.into());
+        self
+    }
+
+    #[cfg(feature = "json-schema")]
+    pub fn json_schema_for_route<S>(mut self, route: &str, schema: S) -> Self
+    where
+        S: Into<JsonSchema>,
+    {
+        self.config
+            .route_json_schemas
+            .push((route.to_string(), schema.into()));
+        self
+    }
+
+    #[cfg(feature = "openapi")]
+    pub fn openapi_spec<P>(mut self, path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.config.openapi_spec = Some(path.into());
+        self
+    }
+
+    #[cfg(feature = "tracing")]
+    pub fn capture_logs(mut self) -> Self {
+        self.config.capture_logs = true;
+        self
+    }
+
     pub fn expect_success_by_default(mut self) -> Self {
         self.config.expect_success_by_default = true;
         self
@@ -139,6 +217,36 @@ mod test_build {
         assert_eq!(config.transport, Some(Transport::HttpRandomPort));
     }
 
+    #[test]
+    fn it_should_use_http2_transport_when_set() {
+        let config = TestServerConfig::builder().http2_transport().build();
+
+        assert_eq!(config.transport, Some(Transport::Http2));
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn it_should_use_https_transport_when_set() {
+        let config = TestServerConfig::builder().https_transport().build();
+
+        assert_eq!(config.transport, Some(Transport::Https));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_should_use_unix_socket_transport_when_set() {
+        let config = TestServerConfig::builder()
+            .unix_socket_transport(Some(PathBuf::from("/tmp/my-app.sock")))
+            .build();
+
+        assert_eq!(
+            config.transport,
+            Some(Transport::UnixSocket {
+                path: Some(PathBuf::from("/tmp/my-app.sock")),
+            })
+        );
+    }
+
     #[test]
     fn it_should_use_http_transport_with_ip_port_when_set() {
         let config = TestServerConfig::builder()
@@ -154,6 +262,40 @@ mod test_build {
         );
     }
 
+    #[test]
+    fn it_should_follow_redirects_when_set() {
+        let config = TestServerConfig::builder().follow_redirects().build();
+
+        assert_eq!(config.follow_redirects, true);
+    }
+
+    #[test]
+    fn it_should_not_follow_redirects_when_set() {
+        let config = TestServerConfig::builder()
+            .follow_redirects()
+            .do_not_follow_redirects()
+            .build();
+
+        assert_eq!(config.follow_redirects, false);
+    }
+
+    #[test]
+    fn it_should_set_max_redirects_when_set() {
+        let config = TestServerConfig::builder().max_redirects(3).build();
+
+        assert_eq!(config.max_redirects, 3);
+    }
+
+    #[test]
+    fn it_should_set_default_client_address_when_set() {
+        let client_address = "203.0.113.7:4321".parse().unwrap();
+        let config = TestServerConfig::builder()
+            .default_client_address(client_address)
+            .build();
+
+        assert_eq!(config.default_client_address, Some(client_address));
+    }
+
     #[test]
     fn it_should_set_default_content_type_when_set() {
         let config = TestServerConfig::builder()
@@ -163,6 +305,67 @@ mod test_build {
         assert_eq!(config.default_content_type, Some("text/csv".to_string()));
     }
 
+    #[test]
+    fn it_should_set_default_timeout_when_set() {
+        let config = TestServerConfig::builder()
+            .default_timeout(Duration::from_secs(3))
+            .build();
+
+        assert_eq!(config.default_timeout, Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn it_should_set_record_har_when_set() {
+        let config = TestServerConfig::builder()
+            .record_har("target/requests.har")
+            .build();
+
+        assert_eq!(
+            config.record_har,
+            Some(PathBuf::from("target/requests.har"))
+        );
+    }
+
+    #[cfg(feature = "json-schema")]
+    #[test]
+    fn it_should_add_json_schemas_for_routes_in_order() {
+        let user_schema = JsonSchema::new(::serde_json::json!({ "type": "object" }));
+        let users_schema = JsonSchema::new(::serde_json::json!({ "type": "array" }));
+        let config = TestServerConfig::builder()
+            .json_schema_for_route(&"GET /users/:id", user_schema.clone())
+            .json_schema_for_route(&"GET /users", users_schema.clone())
+            .build();
+
+        assert_eq!(
+            config.route_json_schemas,
+            vec![
+                ("GET /users/:id".to_string(), user_schema),
+                ("GET /users".to_string(), users_schema),
+            ]
+        );
+    }
+
+    #[cfg(feature = "openapi")]
+    #[test]
+    fn it_should_set_openapi_spec_when_set() {
+        let config = TestServerConfig::builder()
+            .openapi_spec("tests/openapi.yaml")
+            .build();
+
+        assert_eq!(
+            config.openapi_spec,
+            Some(PathBuf::from("tests/openapi.yaml"))
+        );
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn it_should_capture_logs_when_set() {
+        let config = TestServerConfig::builder().capture_logs().build();
+
+        assert_eq!(config.capture_logs, true);
+    }
+
     #[test]
     fn it_should_set_expect_success_by_default_when_set() {
         let config = TestServerConfig::builder()
@@ -0,0 +1,429 @@
+use ::anyhow::anyhow;
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::serde::de::DeserializeOwned;
+use ::std::fmt::Debug;
+use ::std::time::Duration;
+use ::tokio::time::timeout;
+
+#[cfg(feature = "pretty-assertions")]
+use ::pretty_assertions::assert_eq;
+
+use crate::internals::SseParser;
+use crate::SseEvent;
+use crate::TestStreamingResponse;
+
+/// How long to wait for each event, unless changed using
+/// [`TestSseReader::event_timeout()`](crate::TestSseReader::event_timeout()).
+pub const DEFAULT_EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+///
+/// Reads Server-Sent Events from a streaming response, as they arrive.
+///
+/// This is created from a [`TestStreamingResponse`](crate::TestStreamingResponse),
+/// using [`TestStreamingResponse::into_sse_reader()`](crate::TestStreamingResponse::into_sse_reader()).
+///
+/// Each event is waited for up to a timeout, and will panic if it doesn't arrive in time.
+///
+/// ```rust
+/// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+/// #
+/// use ::axum::response::sse::Event;
+/// use ::axum::response::sse::Sse;
+/// use ::axum::routing::get;
+/// use ::axum::Router;
+/// use ::futures_util::stream::iter;
+/// use ::futures_util::stream::Stream;
+/// use ::std::convert::Infallible;
+///
+/// use ::axum_test::TestServer;
+///
+/// async fn get_events() -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+///     Sse::new(iter(vec![
+///         Ok(Event::default().event("greeting").data("hello")),
+///         Ok(Event::default().event("greeting").data("world")),
+///     ]))
+/// }
+///
+/// let app = Router::new().route(&"/events", get(get_events));
+/// let server = TestServer::new(app)?;
+///
+/// let mut events = server.get(&"/events")
+///     .stream()
+///     .await
+///     .into_sse_reader();
+///
+/// let event = events.next_event().await;
+/// event.assert_event(&"greeting");
+/// event.assert_data(&"hello");
+///
+/// events.assert_next_data(&"world").await;
+/// events.assert_end().await;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ## Resuming
+///
+/// The id of the last event received is available from [`TestSseReader::last_event_id()`](crate::TestSseReader::last_event_id()).
+/// This can be sent on a new request using [`TestRequest::last_event_id()`](crate::TestRequest::last_event_id()),
+/// to resume the stream like a browser would.
+///
+pub struct TestSseReader {
+    response: TestStreamingResponse,
+    parser: SseParser,
+    event_timeout: Duration,
+}
+
+impl TestSseReader {
+    pub(crate) fn new(response: TestStreamingResponse) -> Self {
+        Self {
+            response,
+            parser: SseParser::new(),
+            event_timeout: DEFAULT_EVENT_TIMEOUT,
+        }
+    }
+
+    /// Sets how long to wait for each event to arrive.
+    ///
+    /// **Defaults** to [`DEFAULT_EVENT_TIMEOUT`](crate::DEFAULT_EVENT_TIMEOUT).
+    pub fn event_timeout(mut self, event_timeout: Duration) -> Self {
+        self.event_timeout = event_timeout;
+        self
+    }
+
+    /// Returns the next event,
+    /// or `None` if the stream has finished.
+    ///
+    /// This will panic if the event does not arrive within the event timeout.
+    pub async fn maybe_next_event(&mut self) -> Option<SseEvent> {
+        self.try_next_event()
+            .await
+            .with_context(|| {
+                let request_format = self.response.request_format();
+                format!("Failed to read next SSE event, for request {request_format}")
+            })
+            .unwrap()
+    }
+
+    /// Returns the next event.
+    ///
+    /// This will panic if the stream has finished,
+    /// or if the event does not arrive within the event timeout.
+    pub async fn next_event(&mut self) -> SseEvent {
+        let maybe_event = self.maybe_next_event().await;
+        let request_format = self.response.request_format();
+
+        maybe_event
+            .with_context(|| {
+                format!("Expected next SSE event, but stream has finished, for request {request_format}")
+            })
+            .unwrap()
+    }
+
+    /// Returns the data of the next event, deserialized from Json.
+    ///
+    /// This will panic if the stream has finished,
+    /// if the event does not arrive within the event timeout,
+    /// or if the data cannot be deserialized.
+    pub async fn next_json<T>(&mut self) -> T
+    where
+        T: DeserializeOwned,
+    {
+        self.next_event().await.json::<T>()
+    }
+
+    /// The id of the last event received, which carries over between events.
+    ///
+    /// This is what a browser sends in the `Last-Event-ID` header when reconnecting.
+    #[must_use]
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.parser.last_event_id()
+    }
+
+    /// The last reconnection time sent by the server, using a `retry:` field.
+    #[must_use]
+    pub fn reconnection_time(&self) -> Option<Duration> {
+        self.parser.reconnection_time()
+    }
+
+    /// Returns the response the events are read from,
+    /// for checking the status code and headers.
+    #[must_use]
+    pub fn response(&self) -> &TestStreamingResponse {
+        &self.response
+    }
+
+    /// Assert the data of the next event matches the text given.
+    pub async fn assert_next_data<C>(&mut self, expected: C)
+    where
+        C: AsRef<str>,
+    {
+        let event = self.next_event().await;
+
+        assert_eq!(expected.as_ref(), event.data());
+    }
+
+    /// Assert the data of the next event, deserialized from Json,
+    /// matches the value given.
+    pub async fn assert_next_json<T>(&mut self, expected: &T)
+    where
+        T: DeserializeOwned + PartialEq<T> + Debug,
+    {
+        let data = self.next_json::<T>().await;
+
+        assert_eq!(*expected, data);
+    }
+
+    /// Assert the stream finishes, without sending any more events.
+    pub async fn assert_end(&mut self) {
+        let maybe_event = self.maybe_next_event().await;
+        let request_format = self.response.request_format();
+
+        assert!(
+            maybe_event.is_none(),
+            "Expected SSE stream to have finished, got event {maybe_event:?}, for request {request_format}"
+        );
+    }
+
+    async fn try_next_event(&mut self) -> Result<Option<SseEvent>> {
+        let event_timeout = self.event_timeout;
+
+        timeout(event_timeout, self.read_next_event())
+            .await
+            .map_err(|_| anyhow!("Timed out after {event_timeout:?} waiting for next SSE event"))?
+    }
+
+    async fn read_next_event(&mut self) -> Result<Option<SseEvent>> {
+        loop {
+            if let Some(event) = self.parser.next_event() {
+                return Ok(Some(event));
+            }
+
+            match self.response.try_read_chunk().await? {
+                Some(chunk) => self.parser.push(&chunk),
+                None => {
+                    self.parser.finish();
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+impl Debug for TestSseReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestSseReader")
+            .field("response", &self.response)
+            .field("event_timeout", &self.event_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test_next_event {
+    use ::axum::http::HeaderMap;
+    use ::axum::response::sse::Event;
+    use ::axum::response::sse::Sse;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::futures_util::stream::iter;
+    use ::futures_util::stream::pending;
+    use ::futures_util::stream::Stream;
+    use ::futures_util::stream::StreamExt;
+    use ::serde::Deserialize;
+    use ::serde::Serialize;
+    use ::std::convert::Infallible;
+    use ::std::time::Duration;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Count {
+        count: u32,
+    }
+
+    async fn get_counts(headers: HeaderMap) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+        let start = headers
+            .get("last-event-id")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u32>().ok())
+            .map(|last_id| last_id + 1)
+            .unwrap_or(0);
+
+        Sse::new(iter(start..).map(|count| {
+            Ok(Event::default()
+                .event("count")
+                .id(count.to_string())
+                .json_data(Count { count })
+                .unwrap())
+        }))
+    }
+
+    async fn get_one_then_hang() -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+        let first = iter(vec![Ok(Event::default().data("first"))]);
+
+        Sse::new(first.chain(pending()))
+    }
+
+    async fn get_finite() -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+        Sse::new(iter(vec![
+            Ok(Event::default().data("one")),
+            Ok(Event::default().comment("ignored")),
+            Ok(Event::default().data("two").retry(Duration::from_secs(3))),
+        ]))
+    }
+
+    async fn assert_reads_events_from_endless_stream(transport: Transport) {
+        let app = Router::new().route("/counts", get(get_counts));
+        let config = TestServerConfig {
+            transport: Some(transport),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut events = server.get(&"/counts").stream().await.into_sse_reader();
+
+        let event = events.next_event().await;
+        event.assert_event(&"count");
+        assert_eq!(event.id(), Some("0"));
+        event.assert_json(&Count { count: 0 });
+
+        events.assert_next_json(&Count { count: 1 }).await;
+        assert_eq!(events.next_json::<Count>().await, Count { count: 2 });
+        assert_eq!(events.last_event_id(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn it_should_read_events_from_endless_stream_when_mocked() {
+        assert_reads_events_from_endless_stream(Transport::MockHttp).await;
+    }
+
+    #[tokio::test]
+    async fn it_should_read_events_from_endless_stream_when_using_http() {
+        assert_reads_events_from_endless_stream(Transport::HttpRandomPort).await;
+    }
+
+    #[tokio::test]
+    async fn it_should_resume_from_last_event_id() {
+        let app = Router::new().route("/counts", get(get_counts));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut events = server.get(&"/counts").stream().await.into_sse_reader();
+        events.assert_next_json(&Count { count: 0 }).await;
+        events.assert_next_json(&Count { count: 1 }).await;
+        let last_event_id = events.last_event_id().unwrap().to_string();
+        ::std::mem::drop(events);
+
+        let mut resumed_events = server
+            .get(&"/counts")
+            .last_event_id(&last_event_id)
+            .stream()
+            .await
+            .into_sse_reader();
+
+        resumed_events.assert_next_json(&Count { count: 2 }).await;
+    }
+
+    #[tokio::test]
+    async fn it_should_replace_last_event_id_already_set() {
+        let app = Router::new().route("/counts", get(get_counts));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut events = server
+            .get(&"/counts")
+            .last_event_id(&"10")
+            .last_event_id(&"20")
+            .stream()
+            .await
+            .into_sse_reader();
+
+        events.assert_next_json(&Count { count: 21 }).await;
+    }
+
+    #[tokio::test]
+    async fn it_should_read_finite_stream_until_end() {
+        let app = Router::new().route("/finite", get(get_finite));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut events = server.get(&"/finite").stream().await.into_sse_reader();
+
+        events.assert_next_data(&"one").await;
+        let event = events.next_event().await;
+        event.assert_data(&"two");
+        assert_eq!(event.retry(), Some(Duration::from_secs(3)));
+        assert_eq!(events.reconnection_time(), Some(Duration::from_secs(3)));
+        events.assert_end().await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Timed out after 50ms waiting for next SSE event")]
+    async fn it_should_panic_when_event_does_not_arrive_in_time() {
+        let app = Router::new().route("/hang", get(get_one_then_hang));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut events = server
+            .get(&"/hang")
+            .stream()
+            .await
+            .into_sse_reader()
+            .event_timeout(Duration::from_millis(50));
+
+        events.assert_next_data(&"first").await;
+        events.next_event().await;
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Expected next SSE event, but stream has finished, for request GET /finite"
+    )]
+    async fn it_should_panic_when_stream_has_finished() {
+        let app = Router::new().route("/finite", get(get_finite));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut events = server.get(&"/finite").stream().await.into_sse_reader();
+        events.next_event().await;
+        events.next_event().await;
+        events.next_event().await;
+    }
+
+    #[tokio::test]
+    async fn it_should_have_event_stream_content_type() {
+        let app = Router::new().route("/finite", get(get_finite));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let events = server.get(&"/finite").stream().await.into_sse_reader();
+
+        assert_eq!(
+            events.response().header("content-type"),
+            "text/event-stream"
+        );
+    }
+}
@@ -0,0 +1,662 @@
+use ::anyhow::anyhow;
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::axum::body::BoxBody;
+use ::bytes::Bytes;
+use ::http::header::AsHeaderName;
+use ::http::response::Parts;
+use ::http::HeaderMap;
+use ::http::HeaderValue;
+use ::http::StatusCode;
+use ::http::Version;
+use ::hyper::body::HttpBody;
+use ::std::fmt::Debug;
+use ::std::fmt::Display;
+use ::std::time::Duration;
+use ::tokio::time::timeout;
+use ::url::Url;
+
+#[cfg(feature = "pretty-assertions")]
+use ::pretty_assertions::assert_eq;
+
+use crate::internals::RequestPathFormatter;
+use crate::internals::StatusCodeFormatter;
+use crate::TestSseReader;
+
+/// How long to wait for each chunk, unless changed using
+/// [`TestStreamingResponse::chunk_timeout()`](crate::TestStreamingResponse::chunk_timeout()).
+pub const DEFAULT_CHUNK_TIMEOUT: Duration = Duration::from_secs(5);
+
+///
+/// A response where the body is read as it arrives,
+/// rather than waiting for all of it.
+///
+/// This is created by calling [`TestRequest::stream()`](crate::TestRequest::stream()),
+/// and is for testing endpoints that stream, or never finish,
+/// such as long polling and progress feeds.
+///
+/// Each chunk is waited for up to a timeout, and will panic if it doesn't arrive in time.
+/// Dropping the `TestStreamingResponse` closes the connection,
+/// so a test can read the first few chunks and then stop.
+///
+/// ```rust
+/// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+/// #
+/// use ::axum::body::Body;
+/// use ::axum::response::Response;
+/// use ::axum::routing::get;
+/// use ::axum::Router;
+/// use ::std::time::Duration;
+///
+/// use ::axum_test::TestServer;
+///
+/// async fn get_counter() -> Response<Body> {
+///     let (mut sender, body) = Body::channel();
+///     ::tokio::spawn(async move {
+///         for i in 0.. {
+///             if sender.send_data(format!("{i}").into()).await.is_err() {
+///                 break;
+///             }
+///         }
+///     });
+///
+///     Response::new(body)
+/// }
+///
+/// let app = Router::new().route(&"/counter", get(get_counter));
+/// let server = TestServer::new(app)?;
+///
+/// let mut response = server.get(&"/counter")
+///     .stream()
+///     .await
+///     .chunk_timeout(Duration::from_secs(1));
+///
+/// response.assert_next_text_chunk(&"0").await;
+/// response.assert_next_text_chunk(&"1").await;
+/// response.assert_next_text_chunk(&"2").await;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+///
+pub struct TestStreamingResponse {
+    request_format: RequestPathFormatter,
+
+    /// This is the actual url that was used for the request.
+    full_request_url: Url,
+    headers: HeaderMap<HeaderValue>,
+    status_code: StatusCode,
+    version: Version,
+    response_body: BoxBody,
+    chunk_timeout: Duration,
+}
+
+impl TestStreamingResponse {
+    pub(crate) fn new(
+        request_format: RequestPathFormatter,
+        full_request_url: Url,
+        parts: Parts,
+        response_body: BoxBody,
+    ) -> Self {
+        Self {
+            request_format,
+            full_request_url,
+            headers: parts.headers,
+            status_code: parts.status,
+            version: parts.version,
+            response_body,
+            chunk_timeout: DEFAULT_CHUNK_TIMEOUT,
+        }
+    }
+
+    /// Sets how long to wait for each chunk to arrive.
+    ///
+    /// **Defaults** to [`DEFAULT_CHUNK_TIMEOUT`](crate::DEFAULT_CHUNK_TIMEOUT).
+    pub fn chunk_timeout(mut self, chunk_timeout: Duration) -> Self {
+        self.chunk_timeout = chunk_timeout;
+        self
+    }
+
+    /// Returns the next chunk of the body,
+    /// or `None` if the body has finished.
+    ///
+    /// This will panic if the chunk does not arrive within the chunk timeout.
+    pub async fn maybe_next_chunk(&mut self) -> Option<Bytes> {
+        self.try_next_chunk()
+            .await
+            .with_context(|| {
+                let request_format = &self.request_format;
+                format!("Failed to read next chunk, for request {request_format}")
+            })
+            .unwrap()
+    }
+
+    /// Returns the next chunk of the body.
+    ///
+    /// This will panic if the body has finished,
+    /// or if the chunk does not arrive within the chunk timeout.
+    pub async fn next_chunk(&mut self) -> Bytes {
+        let maybe_chunk = self.maybe_next_chunk().await;
+        let request_format = &self.request_format;
+
+        maybe_chunk
+            .with_context(|| {
+                format!("Expected next chunk, but body has finished, for request {request_format}")
+            })
+            .unwrap()
+    }
+
+    /// Returns the next chunk of the body, as text.
+    ///
+    /// This will panic if the body has finished,
+    /// or if the chunk does not arrive within the chunk timeout.
+    pub async fn next_text_chunk(&mut self) -> String {
+        let chunk = self.next_chunk().await;
+
+        String::from_utf8_lossy(&chunk).to_string()
+    }
+
+    /// Returns the next `count` chunks of the body.
+    ///
+    /// This will panic if the body finishes before they are all read,
+    /// or if any chunk does not arrive within the chunk timeout.
+    pub async fn next_chunks(&mut self, count: usize) -> Vec<Bytes> {
+        let mut chunks = Vec::with_capacity(count);
+        for _ in 0..count {
+            chunks.push(self.next_chunk().await);
+        }
+
+        chunks
+    }
+
+    /// Returns the status code of the response.
+    #[must_use]
+    pub fn status_code(&self) -> StatusCode {
+        self.status_code
+    }
+
+    /// Returns the HTTP version the response was sent with.
+    #[must_use]
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the URL the request was sent to.
+    #[must_use]
+    pub fn request_url(&self) -> Url {
+        self.full_request_url.clone()
+    }
+
+    /// Returns all of the headers contained in the response.
+    #[must_use]
+    pub fn headers(&self) -> &HeaderMap<HeaderValue> {
+        &self.headers
+    }
+
+    /// Finds a header with the given name.
+    /// If there are multiple headers with the same name,
+    /// then only the first will be returned.
+    ///
+    /// `None` is returned when no header was found.
+    #[must_use]
+    pub fn maybe_header<N>(&self, header_name: N) -> Option<HeaderValue>
+    where
+        N: AsHeaderName,
+    {
+        self.headers.get(header_name).map(|h| h.to_owned())
+    }
+
+    /// Finds a header with the given name.
+    /// If there are multiple headers with the same name,
+    /// then only the first will be returned.
+    ///
+    /// If no header is found, then this will panic.
+    #[must_use]
+    pub fn header<N>(&self, header_name: N) -> HeaderValue
+    where
+        N: AsHeaderName + Display + Clone,
+    {
+        let debug_header = header_name.clone();
+        self.maybe_header(header_name)
+            .with_context(|| {
+                let request_format = &self.request_format;
+
+                format!("Cannot find header {debug_header}, for request {request_format}",)
+            })
+            .unwrap()
+    }
+
+    /// Assert the next chunk of the body matches the bytes given.
+    pub async fn assert_next_chunk<C>(&mut self, expected: C)
+    where
+        C: AsRef<[u8]>,
+    {
+        let chunk = self.next_chunk().await;
+        let expected = Bytes::copy_from_slice(expected.as_ref());
+
+        assert_eq!(expected, chunk);
+    }
+
+    /// Assert the next chunk of the body matches the text given.
+    pub async fn assert_next_text_chunk<C>(&mut self, expected: C)
+    where
+        C: AsRef<str>,
+    {
+        let chunk = self.next_text_chunk().await;
+
+        assert_eq!(expected.as_ref(), chunk);
+    }
+
+    /// Assert the body finishes, without sending any more chunks.
+    pub async fn assert_end(&mut self) {
+        let maybe_chunk = self.maybe_next_chunk().await;
+        let request_format = &self.request_format;
+
+        assert!(
+            maybe_chunk.is_none(),
+            "Expected body to have finished, got chunk {maybe_chunk:?}, for request {request_format}"
+        );
+    }
+
+    /// Assert that the status code is **within** the 2xx range.
+    /// i.e. The range from 200-299.
+    #[track_caller]
+    pub fn assert_status_success(&self) {
+        let status_code = self.status_code.as_u16();
+        let received_debug = StatusCodeFormatter(self.status_code);
+        let request_format = &self.request_format;
+
+        assert!(
+            (200..=299).contains(&status_code),
+            "Expect status code within 2xx range, got {received_debug}, for request {request_format}"
+        );
+    }
+
+    /// Assert that the status code is **outside** the 2xx range.
+    /// i.e. A status code less than 200, or 300 or more.
+    #[track_caller]
+    pub fn assert_status_failure(&self) {
+        let status_code = self.status_code.as_u16();
+        let received_debug = StatusCodeFormatter(self.status_code);
+        let request_format = &self.request_format;
+
+        assert!(
+            !(200..=299).contains(&status_code),
+            "Expect status code outside 2xx range, got {received_debug}, for request {request_format}",
+        );
+    }
+
+    /// Assert the response status code matches the one given.
+    #[track_caller]
+    pub fn assert_status(&self, expected_status_code: StatusCode) {
+        let received_debug = StatusCodeFormatter(self.status_code);
+        let expected_debug = StatusCodeFormatter(expected_status_code);
+        let request_format = &self.request_format;
+
+        assert_eq!(
+            expected_status_code, self.status_code,
+            "Expected status code {expected_debug}, got {received_debug}, for request {request_format}",
+        );
+    }
+
+    /// Converts this into a reader of Server-Sent Events.
+    /// See [`TestSseReader`](crate::TestSseReader) for more.
+    pub fn into_sse_reader(self) -> TestSseReader {
+        TestSseReader::new(self)
+    }
+
+    pub(crate) fn request_format(&self) -> &RequestPathFormatter {
+        &self.request_format
+    }
+
+    /// Reads the next chunk, waiting as long as it takes.
+    pub(crate) async fn try_read_chunk(&mut self) -> Result<Option<Bytes>> {
+        match self.response_body.data().await {
+            None => Ok(None),
+            Some(Ok(chunk)) => Ok(Some(chunk)),
+            Some(Err(err)) => Err(anyhow!(err)),
+        }
+    }
+
+    async fn try_next_chunk(&mut self) -> Result<Option<Bytes>> {
+        let chunk_timeout = self.chunk_timeout;
+
+        timeout(chunk_timeout, self.try_read_chunk())
+            .await
+            .map_err(|_| anyhow!("Timed out after {chunk_timeout:?} waiting for next chunk"))?
+    }
+}
+
+impl Debug for TestStreamingResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestStreamingResponse")
+            .field("request_format", &self.request_format)
+            .field("full_request_url", &self.full_request_url)
+            .field("headers", &self.headers)
+            .field("status_code", &self.status_code)
+            .field("version", &self.version)
+            .field("chunk_timeout", &self.chunk_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test_next_chunk {
+    use ::axum::body::Body;
+    use ::axum::response::Response;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::future::pending;
+    use ::std::time::Duration;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn get_counter() -> Response<Body> {
+        let (mut sender, body) = Body::channel();
+        ::tokio::spawn(async move {
+            for i in 0.. {
+                if sender.send_data(format!("{i}").into()).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Response::new(body)
+    }
+
+    async fn get_one_chunk_then_hang() -> Response<Body> {
+        let (mut sender, body) = Body::channel();
+        ::tokio::spawn(async move {
+            sender.send_data("first".into()).await.unwrap();
+            pending::<()>().await;
+        });
+
+        Response::new(body)
+    }
+
+    async fn get_finite() -> &'static str {
+        "all of it"
+    }
+
+    #[tokio::test]
+    async fn it_should_read_chunks_from_endless_stream_when_mocked() {
+        let app = Router::new().route("/counter", get(get_counter));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut response = server.get(&"/counter").stream().await;
+
+        assert_eq!(response.next_text_chunk().await, "0");
+        assert_eq!(response.next_text_chunk().await, "1");
+        assert_eq!(response.next_text_chunk().await, "2");
+    }
+
+    #[tokio::test]
+    async fn it_should_read_chunks_from_endless_stream_when_using_http() {
+        let app = Router::new().route("/counter", get(get_counter));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut response = server.get(&"/counter").stream().await;
+
+        assert_eq!(response.next_text_chunk().await, "0");
+        assert_eq!(response.next_text_chunk().await, "1");
+        assert_eq!(response.next_text_chunk().await, "2");
+    }
+
+    #[tokio::test]
+    async fn it_should_read_first_n_chunks() {
+        let app = Router::new().route("/counter", get(get_counter));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut response = server.get(&"/counter").stream().await;
+        let chunks = response.next_chunks(3).await;
+
+        assert_eq!(chunks, vec!["0", "1", "2"]);
+    }
+
+    #[tokio::test]
+    async fn it_should_return_none_when_body_has_finished() {
+        let app = Router::new().route("/finite", get(get_finite));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut response = server.get(&"/finite").stream().await;
+
+        assert_eq!(response.maybe_next_chunk().await.unwrap(), "all of it");
+        assert_eq!(response.maybe_next_chunk().await, None);
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Expected next chunk, but body has finished, for request GET /finite"
+    )]
+    async fn it_should_panic_when_body_has_finished() {
+        let app = Router::new().route("/finite", get(get_finite));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut response = server.get(&"/finite").stream().await;
+        response.next_chunk().await;
+        response.next_chunk().await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Timed out after 50ms waiting for next chunk")]
+    async fn it_should_panic_when_chunk_does_not_arrive_in_time_when_mocked() {
+        let app = Router::new().route("/hang", get(get_one_chunk_then_hang));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut response = server
+            .get(&"/hang")
+            .stream()
+            .await
+            .chunk_timeout(Duration::from_millis(50));
+
+        response.assert_next_text_chunk(&"first").await;
+        response.next_chunk().await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Timed out after 50ms waiting for next chunk")]
+    async fn it_should_panic_when_chunk_does_not_arrive_in_time_when_using_http() {
+        let app = Router::new().route("/hang", get(get_one_chunk_then_hang));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut response = server
+            .get(&"/hang")
+            .stream()
+            .await
+            .chunk_timeout(Duration::from_millis(50));
+
+        response.assert_next_text_chunk(&"first").await;
+        response.next_chunk().await;
+    }
+}
+
+#[cfg(test)]
+mod test_assert_next_chunk {
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::TestServer;
+
+    async fn get_bytes() -> Vec<u8> {
+        vec![1, 2, 3]
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_chunk_matches() {
+        let server = TestServer::new(Router::new().route("/bytes", get(get_bytes))).unwrap();
+
+        let mut response = server.get(&"/bytes").stream().await;
+
+        response.assert_next_chunk([1, 2, 3]).await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_when_chunk_differs() {
+        let server = TestServer::new(Router::new().route("/bytes", get(get_bytes))).unwrap();
+
+        let mut response = server.get(&"/bytes").stream().await;
+
+        response.assert_next_chunk([3, 2, 1]).await;
+    }
+}
+
+#[cfg(test)]
+mod test_assert_end {
+    use ::axum::body::Body;
+    use ::axum::response::Response;
+    use ::axum::routing::get;
+    use ::axum::Router;
+
+    use crate::TestServer;
+
+    async fn get_two_chunks() -> Response<Body> {
+        let (mut sender, body) = Body::channel();
+        ::tokio::spawn(async move {
+            sender.send_data("one".into()).await.unwrap();
+            sender.send_data("two".into()).await.unwrap();
+        });
+
+        Response::new(body)
+    }
+
+    #[tokio::test]
+    async fn it_should_pass_when_body_ends() {
+        let server = TestServer::new(Router::new().route("/two", get(get_two_chunks))).unwrap();
+
+        let mut response = server.get(&"/two").stream().await;
+
+        response.assert_next_text_chunk(&"one").await;
+        response.assert_next_text_chunk(&"two").await;
+        response.assert_end().await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Expected body to have finished")]
+    async fn it_should_panic_when_body_has_more_chunks() {
+        let server = TestServer::new(Router::new().route("/two", get(get_two_chunks))).unwrap();
+
+        let mut response = server.get(&"/two").stream().await;
+
+        response.assert_next_text_chunk(&"one").await;
+        response.assert_end().await;
+    }
+}
+
+#[cfg(test)]
+mod test_drop {
+    use ::axum::body::Body;
+    use ::axum::extract::State;
+    use ::axum::response::Response;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::sync::Arc;
+    use ::std::time::Duration;
+    use ::tokio::sync::Notify;
+    use ::tokio::time::timeout;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn get_until_disconnected(State(disconnected): State<Arc<Notify>>) -> Response<Body> {
+        let (mut sender, body) = Body::channel();
+        ::tokio::spawn(async move {
+            while sender.send_data("tick".into()).await.is_ok() {}
+            disconnected.notify_one();
+        });
+
+        Response::new(body)
+    }
+
+    async fn assert_stream_is_closed_on_drop(transport: Transport) {
+        let disconnected = Arc::new(Notify::new());
+        let app = Router::new()
+            .route("/ticks", get(get_until_disconnected))
+            .with_state(disconnected.clone());
+        let config = TestServerConfig {
+            transport: Some(transport),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut response = server.get(&"/ticks").stream().await;
+        response.assert_next_text_chunk(&"tick").await;
+        ::std::mem::drop(response);
+
+        timeout(Duration::from_secs(5), disconnected.notified())
+            .await
+            .expect("Server should see the stream closed");
+    }
+
+    #[tokio::test]
+    async fn it_should_close_stream_when_dropped_when_mocked() {
+        assert_stream_is_closed_on_drop(Transport::MockHttp).await;
+    }
+
+    #[tokio::test]
+    async fn it_should_close_stream_when_dropped_when_using_http() {
+        assert_stream_is_closed_on_drop(Transport::HttpRandomPort).await;
+    }
+}
+
+#[cfg(test)]
+mod test_status_code {
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::http::StatusCode;
+
+    use crate::TestServer;
+
+    #[tokio::test]
+    async fn it_should_return_status_and_headers_before_body_is_read() {
+        let app = Router::new().route(
+            "/created",
+            get(|| async { (StatusCode::CREATED, [("x-custom", "yes")], "body") }),
+        );
+        let server = TestServer::new(app).unwrap();
+
+        let response = server.get(&"/created").stream().await;
+
+        response.assert_status(StatusCode::CREATED);
+        assert_eq!(response.header("x-custom"), "yes");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Expect status code within 2xx range")]
+    async fn it_should_panic_when_expecting_success_on_failure() {
+        let server = TestServer::new(Router::new()).unwrap();
+
+        let _ = server.get(&"/missing").expect_success().stream().await;
+    }
+}
@@ -0,0 +1,635 @@
+use ::anyhow::anyhow;
+use ::anyhow::Context;
+use ::anyhow::Result;
+use ::bytes::Bytes;
+use ::futures_util::sink::SinkExt;
+use ::futures_util::stream::StreamExt;
+use ::hyper::upgrade::Upgraded;
+use ::serde::de::DeserializeOwned;
+use ::serde::Serialize;
+use ::std::fmt::Debug;
+use ::std::fmt::Display;
+use ::std::time::Duration;
+use ::tokio::time::timeout;
+use ::tokio_tungstenite::tungstenite::error::ProtocolError;
+use ::tokio_tungstenite::tungstenite::protocol::Role;
+use ::tokio_tungstenite::tungstenite::Error as WsError;
+use ::tokio_tungstenite::tungstenite::Message;
+use ::tokio_tungstenite::WebSocketStream;
+
+#[cfg(feature = "pretty-assertions")]
+use ::pretty_assertions::assert_eq;
+
+use crate::internals::RequestPathFormatter;
+use crate::TestResponse;
+use crate::WsCloseFrame;
+use crate::WsMessage;
+use crate::WS_CLOSE_CODE_NORMAL;
+
+/// How long to wait for each message, unless changed using
+/// [`TestWebSocket::message_timeout()`](crate::TestWebSocket::message_timeout()).
+pub const DEFAULT_MESSAGE_TIMEOUT: Duration = Duration::from_secs(5);
+
+///
+/// A WebSocket connection to the [`TestServer`](crate::TestServer),
+/// for sending and receiving messages.
+///
+/// This is created by calling [`TestServer::get_websocket()`](crate::TestServer::get_websocket()),
+/// or [`TestRequest::into_websocket()`](crate::TestRequest::into_websocket()) to customise the upgrade request.
+///
+/// WebSockets need a real connection to upgrade,
+/// so the `TestServer` must be running with a HTTP transport.
+/// Using the mock transport will panic.
+///
+/// Each message is waited for up to a timeout, and will panic if it doesn't arrive in time.
+/// Ping and pong messages are answered automatically, and skipped when reading.
+///
+/// ```rust
+/// # async fn test() -> Result<(), Box<dyn ::std::error::Error>> {
+/// #
+/// use ::axum::extract::ws::WebSocket;
+/// use ::axum::extract::ws::WebSocketUpgrade;
+/// use ::axum::response::Response;
+/// use ::axum::routing::get;
+/// use ::axum::Router;
+///
+/// use ::axum_test::TestServer;
+/// use ::axum_test::TestServerConfig;
+/// use ::axum_test::Transport;
+///
+/// async fn get_echo(ws: WebSocketUpgrade) -> Response {
+///     ws.on_upgrade(|mut socket: WebSocket| async move {
+///         while let Some(Ok(message)) = socket.recv().await {
+///             if socket.send(message).await.is_err() {
+///                 break;
+///             }
+///         }
+///     })
+/// }
+///
+/// let app = Router::new().route(&"/echo", get(get_echo));
+/// let config = TestServerConfig {
+///     transport: Some(Transport::HttpRandomPort),
+///     ..TestServerConfig::default()
+/// };
+/// let server = TestServer::new_with_config(app, config)?;
+///
+/// let mut websocket = server.get_websocket(&"/echo").await;
+/// websocket.send_text(&"hello!").await;
+/// websocket.assert_next_text(&"hello!").await;
+/// websocket.close().await;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+///
+pub struct TestWebSocket {
+    request_format: RequestPathFormatter,
+    upgrade_response: TestResponse,
+    stream: WebSocketStream<Upgraded>,
+    message_timeout: Duration,
+}
+
+impl TestWebSocket {
+    pub(crate) async fn new(
+        request_format: RequestPathFormatter,
+        upgrade_response: TestResponse,
+        upgraded: Upgraded,
+    ) -> Self {
+        let stream = WebSocketStream::from_raw_socket(upgraded, Role::Client, None).await;
+
+        Self {
+            request_format,
+            upgrade_response,
+            stream,
+            message_timeout: DEFAULT_MESSAGE_TIMEOUT,
+        }
+    }
+
+    /// Sets how long to wait for each message to arrive.
+    ///
+    /// **Defaults** to [`DEFAULT_MESSAGE_TIMEOUT`](crate::DEFAULT_MESSAGE_TIMEOUT).
+    pub fn message_timeout(mut self, message_timeout: Duration) -> Self {
+        self.message_timeout = message_timeout;
+        self
+    }
+
+    /// Returns the response from upgrading the connection,
+    /// for checking the status code and headers.
+    ///
+    /// The body of this response is always empty.
+    #[must_use]
+    pub fn upgrade_response(&self) -> &TestResponse {
+        &self.upgrade_response
+    }
+
+    /// Sends a message to the server.
+    pub async fn send_message(&mut self, message: WsMessage) {
+        self.stream
+            .send(message.into_tungstenite())
+            .await
+            .with_context(|| {
+                let request_format = &self.request_format;
+                format!("Failed to send WebSocket message, for request {request_format}")
+            })
+            .unwrap()
+    }
+
+    /// Sends a text message to the server.
+    pub async fn send_text<T>(&mut self, raw_text: T)
+    where
+        T: Display,
+    {
+        let text = format!("{}", raw_text);
+
+        self.send_message(WsMessage::Text(text)).await
+    }
+
+    /// Sends a binary message to the server.
+    pub async fn send_binary(&mut self, message_bytes: Bytes) {
+        self.send_message(WsMessage::Binary(message_bytes)).await
+    }
+
+    /// Sends a text message to the server, serialized as Json.
+    pub async fn send_json<J>(&mut self, body: &J)
+    where
+        J: ?Sized + Serialize,
+    {
+        let text = ::serde_json::to_string(body)
+            .with_context(|| {
+                let request_format = &self.request_format;
+                format!("Serializing WebSocket message to Json, for request {request_format}")
+            })
+            .unwrap();
+
+        self.send_message(WsMessage::Text(text)).await
+    }
+
+    /// Returns the next message,
+    /// or `None` if the connection has closed.
+    ///
+    /// A close message from the server is returned,
+    /// and then `None` is returned after that.
+    ///
+    /// This will panic if the message does not arrive within the message timeout.
+    pub async fn maybe_next_message(&mut self) -> Option<WsMessage> {
+        self.try_next_message()
+            .await
+            .with_context(|| {
+                let request_format = &self.request_format;
+                format!("Failed to read next WebSocket message, for request {request_format}")
+            })
+            .unwrap()
+    }
+
+    /// Returns the next message.
+    ///
+    /// This will panic if the connection has closed,
+    /// or if the message does not arrive within the message timeout.
+    pub async fn next_message(&mut self) -> WsMessage {
+        let maybe_message = self.maybe_next_message().await;
+        let request_format = &self.request_format;
+
+        maybe_message
+            .with_context(|| {
+                format!("Expected next WebSocket message, but connection has closed, for request {request_format}")
+            })
+            .unwrap()
+    }
+
+    /// Returns the next message, which is expected to be text.
+    ///
+    /// This will panic if a different kind of message is received.
+    pub async fn next_text(&mut self) -> String {
+        match self.next_message().await {
+            WsMessage::Text(text) => text,
+            other => {
+                let request_format = &self.request_format;
+                panic!("Expected WebSocket text message, received {other:?}, for request {request_format}")
+            }
+        }
+    }
+
+    /// Returns the next message, which is expected to be binary.
+    ///
+    /// This will panic if a different kind of message is received.
+    pub async fn next_binary(&mut self) -> Bytes {
+        match self.next_message().await {
+            WsMessage::Binary(message_bytes) => message_bytes,
+            other => {
+                let request_format = &self.request_format;
+                panic!("Expected WebSocket binary message, received {other:?}, for request {request_format}")
+            }
+        }
+    }
+
+    /// Reads the next text message as Json,
+    /// and deserializes it into the type given.
+    ///
+    /// This will panic if a different kind of message is received,
+    /// or if the message cannot be deserialized.
+    pub async fn next_json<T>(&mut self) -> T
+    where
+        T: DeserializeOwned,
+    {
+        let text = self.next_text().await;
+
+        ::serde_json::from_str::<T>(&text)
+            .with_context(|| {
+                let request_format = &self.request_format;
+                format!("Deserializing WebSocket message as Json, for request {request_format}, received: {text}")
+            })
+            .unwrap()
+    }
+
+    /// Returns the close frame sent by the server,
+    /// when it closes the connection.
+    ///
+    /// This is `None` if the server closed without giving a code.
+    /// This will panic if a different kind of message is received.
+    pub async fn next_close(&mut self) -> Option<WsCloseFrame> {
+        match self.next_message().await {
+            WsMessage::Close(maybe_close_frame) => maybe_close_frame,
+            other => {
+                let request_format = &self.request_format;
+                panic!("Expected WebSocket close message, received {other:?}, for request {request_format}")
+            }
+        }
+    }
+
+    /// Assert the next message is text, and matches the text given.
+    pub async fn assert_next_text<C>(&mut self, expected: C)
+    where
+        C: AsRef<str>,
+    {
+        let text = self.next_text().await;
+
+        assert_eq!(expected.as_ref(), text);
+    }
+
+    /// Assert the next message is binary, and matches the bytes given.
+    pub async fn assert_next_binary<C>(&mut self, expected: C)
+    where
+        C: AsRef<[u8]>,
+    {
+        let message_bytes = self.next_binary().await;
+
+        assert_eq!(expected.as_ref(), &message_bytes[..]);
+    }
+
+    /// Assert the next message, deserialized from Json,
+    /// matches the value given.
+    pub async fn assert_next_json<T>(&mut self, expected: &T)
+    where
+        T: DeserializeOwned + PartialEq<T> + Debug,
+    {
+        let message = self.next_json::<T>().await;
+
+        assert_eq!(*expected, message);
+    }
+
+    /// Assert the server closes the connection, using the close code given.
+    ///
+    /// Close codes are listed in [RFC 6455](https://www.rfc-editor.org/rfc/rfc6455#section-7.4.1),
+    /// for example `1000` for a normal closure.
+    pub async fn assert_next_close(&mut self, expected_code: u16) {
+        let maybe_close_frame = self.next_close().await;
+        let request_format = &self.request_format;
+        let close_frame = maybe_close_frame
+            .with_context(|| {
+                format!("Expected WebSocket close code {expected_code}, but connection closed without one, for request {request_format}")
+            })
+            .unwrap();
+
+        assert_eq!(expected_code, close_frame.code());
+    }
+
+    /// Closes the connection normally,
+    /// and waits for the server to finish closing it.
+    pub async fn close(self) {
+        self.close_with(WS_CLOSE_CODE_NORMAL, "").await
+    }
+
+    /// Closes the connection with the close code and reason given,
+    /// and waits for the server to finish closing it.
+    pub async fn close_with(mut self, code: u16, reason: &str) {
+        let close_frame = WsCloseFrame::new(code, reason);
+
+        self.try_close(close_frame)
+            .await
+            .with_context(|| {
+                let request_format = &self.request_format;
+                format!("Failed to close WebSocket, for request {request_format}")
+            })
+            .unwrap()
+    }
+
+    async fn try_close(&mut self, close_frame: WsCloseFrame) -> Result<()> {
+        match self
+            .stream
+            .close(Some(close_frame.into_tungstenite()))
+            .await
+        {
+            // The server has already closed the connection.
+            Err(
+                WsError::ConnectionClosed
+                | WsError::AlreadyClosed
+                | WsError::Protocol(ProtocolError::SendAfterClosing),
+            ) => {}
+            result => result?,
+        }
+
+        while self.try_next_message().await?.is_some() {}
+
+        Ok(())
+    }
+
+    async fn try_next_message(&mut self) -> Result<Option<WsMessage>> {
+        let message_timeout = self.message_timeout;
+
+        timeout(message_timeout, self.read_next_message())
+            .await
+            .map_err(|_| {
+                anyhow!("Timed out after {message_timeout:?} waiting for next WebSocket message")
+            })?
+    }
+
+    async fn read_next_message(&mut self) -> Result<Option<WsMessage>> {
+        while let Some(maybe_message) = self.stream.next().await {
+            match maybe_message {
+                Ok(Message::Ping(_) | Message::Pong(_)) => continue,
+                Ok(message) => return Ok(Some(WsMessage::from_tungstenite(message))),
+                // Servers often drop the connection without a close message,
+                // such as when the handler returns.
+                Err(WsError::Protocol(ProtocolError::ResetWithoutClosingHandshake)) => break,
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl Debug for TestWebSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestWebSocket")
+            .field("request_format", &self.request_format)
+            .field("upgrade_response", &self.upgrade_response)
+            .field("message_timeout", &self.message_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test_next_message {
+    use ::axum::extract::ws::CloseFrame;
+    use ::axum::extract::ws::Message;
+    use ::axum::extract::ws::WebSocket;
+    use ::axum::extract::ws::WebSocketUpgrade;
+    use ::axum::extract::Query;
+    use ::axum::response::IntoResponse;
+    use ::axum::response::Response;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::bytes::Bytes;
+    use ::serde::Deserialize;
+    use ::serde::Serialize;
+    use ::std::borrow::Cow;
+    use ::std::time::Duration;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Greeting {
+        name: String,
+    }
+
+    async fn get_echo(ws: WebSocketUpgrade) -> Response {
+        let response = ws.on_upgrade(|mut socket: WebSocket| async move {
+            while let Some(Ok(message)) = socket.recv().await {
+                if socket.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        ([("x-echo", "true")], response).into_response()
+    }
+
+    async fn get_greeting(ws: WebSocketUpgrade, Query(greeting): Query<Greeting>) -> Response {
+        ws.on_upgrade(|mut socket: WebSocket| async move {
+            let message = format!("hello {}", greeting.name);
+            let _ = socket.send(Message::Text(message)).await;
+        })
+    }
+
+    async fn get_close(ws: WebSocketUpgrade) -> Response {
+        ws.on_upgrade(|mut socket: WebSocket| async move {
+            let close_frame = CloseFrame {
+                code: 4000,
+                reason: Cow::from("goodbye"),
+            };
+            let _ = socket.send(Message::Close(Some(close_frame))).await;
+            while let Some(Ok(_)) = socket.recv().await {}
+        })
+    }
+
+    async fn get_silent(ws: WebSocketUpgrade) -> Response {
+        ws.on_upgrade(|mut socket: WebSocket| async move {
+            while let Some(Ok(_)) = socket.recv().await {}
+        })
+    }
+
+    #[tokio::test]
+    async fn it_should_send_and_receive_text() {
+        let app = Router::new().route("/echo", get(get_echo));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut websocket = server.get_websocket(&"/echo").await;
+        websocket.send_text(&"hello!").await;
+        websocket.assert_next_text(&"hello!").await;
+        websocket.close().await;
+    }
+
+    #[tokio::test]
+    async fn it_should_send_and_receive_binary() {
+        let app = Router::new().route("/echo", get(get_echo));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut websocket = server.get_websocket(&"/echo").await;
+        websocket.send_binary(Bytes::from_static(&[1, 2, 3])).await;
+        websocket.assert_next_binary(&[1, 2, 3]).await;
+        websocket.close().await;
+    }
+
+    #[tokio::test]
+    async fn it_should_send_and_receive_json() {
+        let app = Router::new().route("/echo", get(get_echo));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut websocket = server.get_websocket(&"/echo").await;
+        websocket
+            .send_json(&Greeting {
+                name: "Joe".to_string(),
+            })
+            .await;
+        websocket
+            .assert_next_json(&Greeting {
+                name: "Joe".to_string(),
+            })
+            .await;
+        websocket.close().await;
+    }
+
+    #[tokio::test]
+    async fn it_should_return_upgrade_response() {
+        let app = Router::new().route("/echo", get(get_echo));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let websocket = server.get_websocket(&"/echo").await;
+        let upgrade_response = websocket.upgrade_response();
+
+        upgrade_response.assert_status(::http::StatusCode::SWITCHING_PROTOCOLS);
+        assert_eq!(upgrade_response.header("x-echo"), "true");
+    }
+
+    #[tokio::test]
+    async fn it_should_send_query_params_from_request() {
+        let app = Router::new().route("/greeting", get(get_greeting));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut websocket = server
+            .get(&"/greeting")
+            .add_query_param("name", "Joe")
+            .into_websocket()
+            .await;
+
+        websocket.assert_next_text(&"hello Joe").await;
+    }
+
+    #[tokio::test]
+    async fn it_should_receive_close_code_from_server() {
+        let app = Router::new().route("/close", get(get_close));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut websocket = server.get_websocket(&"/close").await;
+        let close_frame = websocket.next_close().await.unwrap();
+
+        assert_eq!(close_frame.reason(), "goodbye");
+        assert_eq!(websocket.maybe_next_message().await, None);
+    }
+
+    #[tokio::test]
+    async fn it_should_assert_close_code_from_server() {
+        let app = Router::new().route("/close", get(get_close));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut websocket = server.get_websocket(&"/close").await;
+        websocket.assert_next_close(4000).await;
+        websocket.close().await;
+    }
+
+    #[tokio::test]
+    #[should_panic]
+    async fn it_should_panic_when_close_code_differs() {
+        let app = Router::new().route("/close", get(get_close));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut websocket = server.get_websocket(&"/close").await;
+        websocket.assert_next_close(1000).await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Expected WebSocket text message, received Binary")]
+    async fn it_should_panic_when_message_is_different_kind() {
+        let app = Router::new().route("/echo", get(get_echo));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut websocket = server.get_websocket(&"/echo").await;
+        websocket.send_binary(Bytes::from_static(&[1, 2, 3])).await;
+        websocket.next_text().await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Timed out after 50ms waiting for next WebSocket message")]
+    async fn it_should_panic_when_message_does_not_arrive_in_time() {
+        let app = Router::new().route("/silent", get(get_silent));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        let mut websocket = server
+            .get_websocket(&"/silent")
+            .await
+            .message_timeout(Duration::from_millis(50));
+        websocket.next_message().await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "WebSocket requires a HTTP transport")]
+    async fn it_should_panic_when_using_mock_transport() {
+        let app = Router::new().route("/echo", get(get_echo));
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get_websocket(&"/echo").await;
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "Expected WebSocket upgrade with status 101 Switching Protocols, received 200 OK, for request GET /not-websocket"
+    )]
+    async fn it_should_panic_when_endpoint_does_not_upgrade() {
+        let app = Router::new().route("/not-websocket", get(|| async { "hello!" }));
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        server.get_websocket(&"/not-websocket").await;
+    }
+}
@@ -1,8 +1,10 @@
 use ::std::net::IpAddr;
+#[cfg(unix)]
+use ::std::path::PathBuf;
 
 /// Transport is for setting which transport mode for the `TestServer`
 /// to use when making requests.
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Transport {
     /// With this transport mode, `TestRequest` will use a mock HTTP
     /// transport.
@@ -31,6 +33,37 @@ pub enum Transport {
         /// **Defaults** to a _random_ port.
         port: Option<u16>,
     },
+
+    /// With this transport mode, a real web server will be spun up
+    /// running on a random port, which only speaks HTTP/2.
+    ///
+    /// Requests are sent over plain text using HTTP/2 with prior knowledge (h2c),
+    /// so no HTTP/1.1 upgrade takes place.
+    Http2,
+
+    /// With this transport mode, a real web server will be spun up
+    /// running on a random port, serving over TLS.
+    ///
+    /// A throwaway certificate authority, and a certificate for `localhost` signed by it,
+    /// are generated when the server starts.
+    /// Requests made using the `TestRequest` will trust that certificate authority.
+    ///
+    /// This requires the `tls` feature.
+    #[cfg(feature = "tls")]
+    Https,
+
+    /// With this transport mode, a real web server will be spun up
+    /// listening on a Unix domain socket.
+    /// Requests made using the `TestRequest` will be sent to that socket.
+    ///
+    /// The socket file is deleted when the `TestServer` is dropped.
+    #[cfg(unix)]
+    UnixSocket {
+        /// Set the path of the socket file to create.
+        ///
+        /// **Defaults** to a new file within the temp directory.
+        path: Option<PathBuf>,
+    },
 }
 
 impl Default for Transport {
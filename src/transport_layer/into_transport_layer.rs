@@ -10,6 +10,13 @@ pub use self::into_make_service::*;
 
 mod router;
 pub use self::router::*;
+
+mod boxed_service;
+pub(crate) use self::boxed_service::*;
+
+mod method_router;
+mod service_fn;
+mod shared;
 use super::TransportLayerBuilder;
 
 ///
@@ -18,6 +25,12 @@ use super::TransportLayerBuilder;
 /// [`IntoMakeService`](::axum::routing::IntoMakeService),
 /// and [`IntoMakeServiceWithConnectInfo`](::axum::extract::connect_info::IntoMakeServiceWithConnectInfo).
 ///
+/// It is also implemented for [`MethodRouter`](::axum::routing::MethodRouter),
+/// services made using [`tower::service_fn`](::tower::service_fn),
+/// and for any other service when wrapped in [`tower::make::Shared`](::tower::make::Shared).
+/// Any other service can be tested using [`TestServer::new_service()`](crate::TestServer::new_service()),
+/// such as a `Router` wrapped in layers using [`tower::ServiceBuilder`](::tower::ServiceBuilder).
+///
 /// Implementing this will allow you to use the `TestServer` against other types.
 ///
 /// **Warning**, this trait may change in a future release.
@@ -0,0 +1,55 @@
+use ::anyhow::Result;
+use ::axum::response::IntoResponse;
+use ::axum::response::Response;
+use ::axum::BoxError;
+use ::http::Request;
+use ::hyper::Body;
+use ::tower::make::Shared;
+use ::tower::util::BoxCloneService;
+use ::tower::util::ServiceExt;
+use ::tower::Service;
+
+use super::IntoTransportLayer;
+use crate::internals::spawn_http_transport_layer;
+use crate::internals::MockTransportLayer;
+use crate::transport_layer::TransportLayer;
+use crate::transport_layer::TransportLayerBuilder;
+
+/// Any service, returning anything implementing `IntoResponse`,
+/// boxed up so it can be served without knowing its type.
+///
+/// The service is served as it is, and is not wrapped within a `Router`.
+/// Errors it returns are returned from sending the request,
+/// rather than turned into a response.
+#[derive(Clone)]
+pub(crate) struct BoxedService(BoxCloneService<Request<Body>, Response, BoxError>);
+
+impl BoxedService {
+    pub(crate) fn new<S>(service: S) -> Self
+    where
+        S: Service<Request<Body>> + Clone + Send + 'static,
+        S::Response: IntoResponse + 'static,
+        S::Error: Into<BoxError> + 'static,
+        S::Future: Send + 'static,
+    {
+        let service = service
+            .map_response(IntoResponse::into_response)
+            .map_err(Into::into);
+
+        Self(BoxCloneService::new(service))
+    }
+}
+
+impl IntoTransportLayer for BoxedService {
+    fn into_http_transport_layer(
+        self,
+        builder: TransportLayerBuilder,
+    ) -> Result<Box<dyn TransportLayer>> {
+        spawn_http_transport_layer(Shared::new(self.0), builder)
+    }
+
+    fn into_mock_transport_layer(self) -> Result<Box<dyn TransportLayer>> {
+        let transport_layer = MockTransportLayer::new(Shared::new(self.0));
+        Ok(Box::new(transport_layer))
+    }
+}
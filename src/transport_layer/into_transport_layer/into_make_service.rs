@@ -1,13 +1,9 @@
-use ::anyhow::Context;
 use ::anyhow::Result;
 use ::axum::routing::IntoMakeService;
 use ::axum::Router;
-use ::axum::Server as AxumServer;
-use ::tokio::spawn;
-use ::url::Url;
 
 use super::IntoTransportLayer;
-use crate::internals::HttpTransportLayer;
+use crate::internals::spawn_http_transport_layer;
 use crate::internals::MockTransportLayer;
 use crate::transport_layer::TransportLayer;
 use crate::transport_layer::TransportLayerBuilder;
@@ -17,26 +13,7 @@ impl IntoTransportLayer for IntoMakeService<Router> {
         self,
         builder: TransportLayerBuilder,
     ) -> Result<Box<dyn TransportLayer>> {
-        let (socket_addr, tcp_listener, maybe_reserved_port) =
-            builder.tcp_listener_with_reserved_port()?;
-
-        let maybe_local_address = tcp_listener.local_addr().ok();
-        let server_builder = AxumServer::from_tcp(tcp_listener)
-            .with_context(|| format!("Failed to create ::axum::Server for TestServer, with address '{maybe_local_address:?}'"))?;
-
-        let server = server_builder.serve(self);
-        let server_handle = spawn(async move {
-            server.await.expect("Expect server to start serving");
-        });
-
-        let server_address = format!("http://{socket_addr}");
-        let server_url: Url = server_address.parse()?;
-
-        Ok(Box::new(HttpTransportLayer::new(
-            server_handle,
-            maybe_reserved_port,
-            server_url,
-        )))
+        spawn_http_transport_layer(self, builder)
     }
 
     fn into_mock_transport_layer(self) -> Result<Box<dyn TransportLayer>> {
@@ -102,6 +79,145 @@ mod test_into_http_transport_layer_for_into_make_service {
     }
 }
 
+#[cfg(feature = "tls")]
+#[cfg(test)]
+mod test_into_https_transport_layer_for_into_make_service {
+    use ::axum::http::Uri;
+    use ::axum::routing::get;
+    use ::axum::routing::IntoMakeService;
+    use ::axum::Router;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn get_ping() -> &'static str {
+        "pong!"
+    }
+
+    async fn get_uri(uri: Uri) -> String {
+        uri.to_string()
+    }
+
+    #[tokio::test]
+    async fn it_should_create_and_test_with_make_into_service() {
+        // Build an application with a route.
+        let app: IntoMakeService<Router> = Router::new()
+            .route("/ping", get(get_ping))
+            .into_make_service();
+
+        // Run the server.
+        let config = TestServerConfig {
+            transport: Some(Transport::Https),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        // Get the request.
+        let response = server.get(&"/ping").await;
+        response.assert_text(&"pong!");
+        assert_eq!(response.request_url().scheme(), "https");
+    }
+
+    #[tokio::test]
+    async fn it_should_make_many_requests_over_the_same_server() {
+        // Build an application with a route.
+        let app: IntoMakeService<Router> = Router::new()
+            .route("/uri", get(get_uri))
+            .into_make_service();
+
+        // Run the server.
+        let config = TestServerConfig {
+            transport: Some(Transport::Https),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        // Get the requests.
+        server.get(&"/uri").await.assert_text(&"/uri");
+        server.get(&"/uri").await.assert_text(&"/uri");
+    }
+}
+
+#[cfg(unix)]
+#[cfg(test)]
+mod test_into_unix_socket_transport_layer_for_into_make_service {
+    use ::axum::routing::get;
+    use ::axum::routing::IntoMakeService;
+    use ::axum::Router;
+
+    use crate::internals::new_unix_socket_path;
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn get_ping() -> &'static str {
+        "pong!"
+    }
+
+    #[tokio::test]
+    async fn it_should_create_and_test_with_make_into_service() {
+        // Build an application with a route.
+        let app: IntoMakeService<Router> = Router::new()
+            .route("/ping", get(get_ping))
+            .into_make_service();
+
+        // Run the server.
+        let config = TestServerConfig {
+            transport: Some(Transport::UnixSocket { path: None }),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        // Get the request.
+        server.get(&"/ping").await.assert_text(&"pong!");
+    }
+
+    #[tokio::test]
+    async fn it_should_serve_on_the_socket_path_given() {
+        // Build an application with a route.
+        let app: IntoMakeService<Router> = Router::new()
+            .route("/ping", get(get_ping))
+            .into_make_service();
+
+        // Run the server.
+        let socket_path = new_unix_socket_path();
+        let config = TestServerConfig {
+            transport: Some(Transport::UnixSocket {
+                path: Some(socket_path.clone()),
+            }),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        assert!(socket_path.exists());
+        server.get(&"/ping").await.assert_text(&"pong!");
+    }
+
+    #[tokio::test]
+    async fn it_should_delete_socket_file_when_dropped() {
+        // Build an application with a route.
+        let app: IntoMakeService<Router> = Router::new()
+            .route("/ping", get(get_ping))
+            .into_make_service();
+
+        // Run the server.
+        let socket_path = new_unix_socket_path();
+        let config = TestServerConfig {
+            transport: Some(Transport::UnixSocket {
+                path: Some(socket_path.clone()),
+            }),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+        server.get(&"/ping").await.assert_text(&"pong!");
+
+        ::std::mem::drop(server);
+
+        assert!(!socket_path.exists());
+    }
+}
+
 #[cfg(test)]
 mod test_into_mock_transport_layer_for_into_make_service {
     use ::axum::extract::State;
@@ -1,48 +1,80 @@
 use ::anyhow::anyhow;
 use ::anyhow::Context;
 use ::anyhow::Result;
+use ::axum::extract::connect_info::Connected;
 use ::axum::extract::connect_info::IntoMakeServiceWithConnectInfo;
+use ::axum::response::Response;
 use ::axum::Router;
 use ::axum::Server as AxumServer;
+use ::http::Request;
 use ::hyper::server::conn::AddrStream;
-use ::tokio::spawn;
+use ::hyper::Body;
+use ::std::convert::Infallible;
+use ::std::future::Future;
+use ::std::pin::Pin;
+use ::std::task::Context as TaskContext;
+use ::std::task::Poll;
+use ::tower::util::ServiceExt;
+use ::tower::Service;
 use ::url::Url;
 
+use super::BoxedService;
 use super::IntoTransportLayer;
 use crate::internals::HttpTransportLayer;
+use crate::internals::ServerTask;
+use crate::transport_layer::MockConnection;
 use crate::transport_layer::TransportLayer;
 use crate::transport_layer::TransportLayerBuilder;
 
+/// When mocked, the connect info is built from a [`MockConnection`](crate::transport_layer::MockConnection).
+/// This uses the address set by [`TestRequest::client_address()`](crate::TestRequest::client_address()),
+/// then [`TestServerConfig::default_client_address`](crate::TestServerConfig::default_client_address),
+/// or [`DEFAULT_MOCK_CLIENT_ADDRESS`](crate::transport_layer::DEFAULT_MOCK_CLIENT_ADDRESS) if neither is set.
+///
+/// This is implemented for `SocketAddr`.
+/// Custom connect info types need to implement `Connected<&MockConnection>` as well.
 impl<C> IntoTransportLayer for IntoMakeServiceWithConnectInfo<Router, C>
 where
-    for<'a> C: axum::extract::connect_info::Connected<&'a AddrStream>,
+    for<'a> C: Connected<&'a AddrStream> + Connected<&'a MockConnection>,
 {
     fn into_http_transport_layer(
         self,
         builder: TransportLayerBuilder,
     ) -> Result<Box<dyn TransportLayer>> {
+        if builder.is_tls() {
+            return Err(anyhow!("`IntoMakeServiceWithConnectInfo` cannot be served over TLS, as its underlying implementation requires a plain TCP connection. Set the `TestServerConfig` to run with a transport of `HttpRandomPort`, or a `HttpIpPort`."));
+        }
+
+        #[cfg(unix)]
+        if builder.maybe_unix_socket_path().is_some() {
+            return Err(anyhow!("`IntoMakeServiceWithConnectInfo` cannot be served over a Unix socket, as its underlying implementation requires a TCP connection. Set the `TestServerConfig` to run with a transport of `HttpRandomPort`, or a `HttpIpPort`."));
+        }
+
         let (socket_addr, tcp_listener, maybe_reserved_port) =
             builder.tcp_listener_with_reserved_port()?;
         let server_builder = AxumServer::from_tcp(tcp_listener)
             .with_context(|| "Failed to create ::axum::Server for TestServer")?;
 
-        let server = server_builder.serve(self);
-        let server_handle = spawn(async move {
-            server.await.expect("Expect server to start serving");
+        let server = server_builder
+            .http2_only(builder.is_http2_only())
+            .serve(self);
+        let server_task = ServerTask::spawn(|graceful_shutdown_signal| {
+            server.with_graceful_shutdown(graceful_shutdown_signal)
         });
 
         let server_address = format!("http://{socket_addr}");
         let server_url: Url = server_address.parse()?;
 
         Ok(Box::new(HttpTransportLayer::new(
-            server_handle,
+            server_task,
             maybe_reserved_port,
             server_url,
+            builder.http_client(),
         )))
     }
 
     fn into_mock_transport_layer(self) -> Result<Box<dyn TransportLayer>> {
-        Err(anyhow!("`IntoMakeServiceWithConnectInfo` cannot be mocked, as it's underlying implementation requires a real connection. Set the `TestServerConfig` to run with a transport of `HttpRandomPort`, or a `HttpIpPort`."))
+        BoxedService::new(MockConnectInfoService(self)).into_mock_transport_layer()
     }
 
     fn into_default_transport(
@@ -53,6 +85,38 @@ where
     }
 }
 
+/// Builds a new service for each request, from the `MockConnection` attached to it.
+/// This is the same as a server would do for each new connection.
+#[derive(Clone)]
+struct MockConnectInfoService<C>(IntoMakeServiceWithConnectInfo<Router, C>);
+
+impl<C> Service<Request<Body>> for MockConnectInfoService<C>
+where
+    for<'a> C: Connected<&'a MockConnection>,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let make_service = self.0.clone();
+        let connection = request
+            .extensions()
+            .get::<MockConnection>()
+            .copied()
+            .unwrap_or_default();
+
+        Box::pin(async move {
+            let service = make_service.oneshot(&connection).await?;
+            service.oneshot(request).await
+        })
+    }
+}
+
 #[cfg(test)]
 mod test_into_http_transport_layer_for_into_make_service_with_connect_info {
     use ::axum::routing::get;
@@ -84,14 +148,36 @@ mod test_into_http_transport_layer_for_into_make_service_with_connect_info {
         // Get the request.
         server.get(&"/ping").await.assert_text(&"pong!");
     }
+
+    #[tokio::test]
+    async fn it_should_create_and_test_with_make_into_service_with_connect_info_over_http2() {
+        // Build an application with a route.
+        let app = Router::new()
+            .route("/ping", get(get_ping))
+            .into_make_service_with_connect_info::<SocketAddr>();
+
+        // Run the server.
+        let config = TestServerConfig {
+            transport: Some(Transport::Http2),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        // Get the request.
+        server.get(&"/ping").await.assert_text(&"pong!");
+    }
 }
 
 #[cfg(test)]
 mod test_into_mock_transport_layer_for_into_make_service_with_connect_info {
+    use ::axum::extract::connect_info::Connected;
+    use ::axum::extract::ConnectInfo;
     use ::axum::routing::get;
     use ::axum::Router;
+    use ::hyper::server::conn::AddrStream;
     use ::std::net::SocketAddr;
 
+    use crate::transport_layer::MockConnection;
     use crate::TestServer;
     use crate::TestServerConfig;
     use crate::Transport;
@@ -100,8 +186,156 @@ mod test_into_mock_transport_layer_for_into_make_service_with_connect_info {
         "pong!"
     }
 
+    async fn get_client_address(ConnectInfo(address): ConnectInfo<SocketAddr>) -> String {
+        format!("{address}")
+    }
+
+    #[tokio::test]
+    async fn it_should_create_and_test_using_mock() {
+        let app = Router::new()
+            .route("/ping", get(get_ping))
+            .into_make_service_with_connect_info::<SocketAddr>();
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).unwrap();
+
+        server.get(&"/ping").await.assert_text(&"pong!");
+    }
+
     #[tokio::test]
-    async fn it_should_panic_when_creating_test_using_mock() {
+    async fn it_should_use_default_client_address_when_none_is_set() {
+        let app = Router::new()
+            .route("/client", get(get_client_address))
+            .into_make_service_with_connect_info::<SocketAddr>();
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).unwrap();
+
+        server.get(&"/client").await.assert_text(&"127.0.0.1:0");
+    }
+
+    #[tokio::test]
+    async fn it_should_use_default_client_address_set_on_server() {
+        let app = Router::new()
+            .route("/client", get(get_client_address))
+            .into_make_service_with_connect_info::<SocketAddr>();
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            default_client_address: Some("198.51.100.2:1234".parse().unwrap()),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).unwrap();
+
+        server
+            .get(&"/client")
+            .await
+            .assert_text(&"198.51.100.2:1234");
+        server
+            .get(&"/client")
+            .client_address("203.0.113.7:4321".parse().unwrap())
+            .await
+            .assert_text(&"203.0.113.7:4321");
+    }
+
+    #[tokio::test]
+    async fn it_should_use_client_address_set_on_request() {
+        let app = Router::new()
+            .route("/client", get(get_client_address))
+            .into_make_service_with_connect_info::<SocketAddr>();
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).unwrap();
+
+        let first_address: SocketAddr = "203.0.113.7:4321".parse().unwrap();
+        let second_address: SocketAddr = "[2001:db8::1]:8080".parse().unwrap();
+
+        server
+            .get(&"/client")
+            .client_address(first_address)
+            .await
+            .assert_text(&"203.0.113.7:4321");
+        server
+            .get(&"/client")
+            .client_address(second_address)
+            .await
+            .assert_text(&"[2001:db8::1]:8080");
+        server.get(&"/client").await.assert_text(&"127.0.0.1:0");
+    }
+
+    #[derive(Clone)]
+    struct ClientPort(u16);
+
+    impl Connected<&AddrStream> for ClientPort {
+        fn connect_info(target: &AddrStream) -> Self {
+            Self(target.remote_addr().port())
+        }
+    }
+
+    impl Connected<&MockConnection> for ClientPort {
+        fn connect_info(target: &MockConnection) -> Self {
+            Self(target.remote_addr().port())
+        }
+    }
+
+    async fn get_client_port(ConnectInfo(ClientPort(port)): ConnectInfo<ClientPort>) -> String {
+        format!("{port}")
+    }
+
+    #[tokio::test]
+    async fn it_should_serve_custom_connect_info_over_http() {
+        let app = Router::new()
+            .route("/port", get(get_client_port))
+            .into_make_service_with_connect_info::<ClientPort>();
+        let server = TestServer::new(app).unwrap();
+
+        let response = server.get(&"/port").await;
+        let port: u16 = response.text().parse().unwrap();
+        assert_ne!(port, 0);
+    }
+
+    #[tokio::test]
+    async fn it_should_mock_custom_connect_info() {
+        let app = Router::new()
+            .route("/port", get(get_client_port))
+            .into_make_service_with_connect_info::<ClientPort>();
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).unwrap();
+
+        server.get(&"/port").await.assert_text(&"0");
+        server
+            .get(&"/port")
+            .client_address("203.0.113.7:4321".parse().unwrap())
+            .await
+            .assert_text(&"4321");
+    }
+}
+
+#[cfg(feature = "tls")]
+#[cfg(test)]
+mod test_into_https_transport_layer_for_into_make_service_with_connect_info {
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::std::net::SocketAddr;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn get_ping() -> &'static str {
+        "pong!"
+    }
+
+    #[tokio::test]
+    async fn it_should_error_when_creating_test_using_https() {
         // Build an application with a route.
         let app = Router::new()
             .route("/ping", get(get_ping))
@@ -109,13 +343,13 @@ mod test_into_mock_transport_layer_for_into_make_service_with_connect_info {
 
         // Build the server.
         let config = TestServerConfig {
-            transport: Some(Transport::MockHttp),
+            transport: Some(Transport::Https),
             ..TestServerConfig::default()
         };
         let result = TestServer::new_with_config(app, config);
         let err = result.unwrap_err();
         let err_msg = format!("{}", err);
 
-        assert_eq!(err_msg, "`IntoMakeServiceWithConnectInfo` cannot be mocked, as it's underlying implementation requires a real connection. Set the `TestServerConfig` to run with a transport of `HttpRandomPort`, or a `HttpIpPort`.");
+        assert_eq!(err_msg, "`IntoMakeServiceWithConnectInfo` cannot be served over TLS, as its underlying implementation requires a plain TCP connection. Set the `TestServerConfig` to run with a transport of `HttpRandomPort`, or a `HttpIpPort`.");
     }
 }
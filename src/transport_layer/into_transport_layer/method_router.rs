@@ -0,0 +1,94 @@
+use ::anyhow::Result;
+use ::axum::routing::MethodRouter;
+
+use super::BoxedService;
+use super::IntoTransportLayer;
+use crate::transport_layer::TransportLayer;
+use crate::transport_layer::TransportLayerBuilder;
+
+impl IntoTransportLayer for MethodRouter<()> {
+    fn into_http_transport_layer(
+        self,
+        builder: TransportLayerBuilder,
+    ) -> Result<Box<dyn TransportLayer>> {
+        BoxedService::new(self).into_http_transport_layer(builder)
+    }
+
+    fn into_mock_transport_layer(self) -> Result<Box<dyn TransportLayer>> {
+        BoxedService::new(self).into_mock_transport_layer()
+    }
+}
+
+#[cfg(test)]
+mod test_into_http_transport_layer_for_method_router {
+    use ::axum::routing::get;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn get_ping() -> &'static str {
+        "pong!"
+    }
+
+    #[tokio::test]
+    async fn it_should_create_and_test_with_method_router() {
+        // Build an application with a route.
+        let app = get(get_ping);
+
+        // Run the server.
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        // Get the request.
+        server.get(&"/ping").await.assert_text(&"pong!");
+    }
+}
+
+#[cfg(test)]
+mod test_into_mock_transport_layer_for_method_router {
+    use ::axum::routing::get;
+    use ::http::StatusCode;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn get_ping() -> &'static str {
+        "pong!"
+    }
+
+    #[tokio::test]
+    async fn it_should_create_and_test_with_method_router() {
+        // Build an application with a route.
+        let app = get(get_ping);
+
+        // Run the server.
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        // Get the request.
+        server.get(&"/ping").await.assert_text(&"pong!");
+    }
+
+    #[tokio::test]
+    async fn it_should_return_method_not_allowed_for_other_methods() {
+        // Build an application with a route.
+        let app = get(get_ping);
+
+        // Run the server.
+        let server = TestServer::new(app).expect("Should create test server");
+
+        // Get the request.
+        server
+            .post(&"/ping")
+            .await
+            .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
@@ -0,0 +1,125 @@
+use ::anyhow::Result;
+use ::axum::response::IntoResponse;
+use ::axum::BoxError;
+use ::http::Request;
+use ::hyper::Body;
+use ::std::future::Future;
+use ::tower::util::ServiceFn;
+
+use super::BoxedService;
+use super::IntoTransportLayer;
+use crate::transport_layer::TransportLayer;
+use crate::transport_layer::TransportLayerBuilder;
+
+impl<F, Fut, R, E> IntoTransportLayer for ServiceFn<F>
+where
+    F: FnMut(Request<Body>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<R, E>> + Send + 'static,
+    R: IntoResponse + Send + 'static,
+    E: Into<BoxError> + Send + 'static,
+{
+    fn into_http_transport_layer(
+        self,
+        builder: TransportLayerBuilder,
+    ) -> Result<Box<dyn TransportLayer>> {
+        BoxedService::new(self).into_http_transport_layer(builder)
+    }
+
+    fn into_mock_transport_layer(self) -> Result<Box<dyn TransportLayer>> {
+        BoxedService::new(self).into_mock_transport_layer()
+    }
+}
+
+#[cfg(test)]
+mod test_into_http_transport_layer_for_service_fn {
+    use ::http::Request;
+    use ::hyper::Body;
+    use ::std::convert::Infallible;
+    use ::tower::service_fn;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn handle_request(request: Request<Body>) -> Result<String, Infallible> {
+        Ok(format!("path is {}", request.uri().path()))
+    }
+
+    #[tokio::test]
+    async fn it_should_create_and_test_with_service_fn() {
+        // Build an application.
+        let app = service_fn(handle_request);
+
+        // Run the server.
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        // Get the request.
+        server.get(&"/ping").await.assert_text(&"path is /ping");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Sending request failed")]
+    async fn it_should_fail_request_when_service_errors() {
+        // Build an application.
+        let app = service_fn(|_: Request<Body>| async {
+            Err::<String, _>(::std::io::Error::other("it went wrong"))
+        });
+
+        // Run the server.
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        // Get the request.
+        let _ = server.get(&"/ping").await;
+    }
+}
+
+#[cfg(test)]
+mod test_into_mock_transport_layer_for_service_fn {
+    use ::http::Request;
+    use ::hyper::Body;
+    use ::std::convert::Infallible;
+    use ::tower::service_fn;
+
+    use crate::TestServer;
+
+    async fn handle_request(request: Request<Body>) -> Result<String, Infallible> {
+        Ok(format!("path is {}", request.uri().path()))
+    }
+
+    async fn handle_request_with_error(_: Request<Body>) -> Result<String, ::std::io::Error> {
+        Err(::std::io::Error::other("it went wrong"))
+    }
+
+    #[tokio::test]
+    async fn it_should_create_and_test_with_service_fn() {
+        // Build an application.
+        let app = service_fn(handle_request);
+
+        // Run the server.
+        let server = TestServer::new(app).expect("Should create test server");
+
+        // Get the request.
+        server.get(&"/ping").await.assert_text(&"path is /ping");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "it went wrong")]
+    async fn it_should_fail_request_when_service_errors() {
+        // Build an application.
+        let app = service_fn(handle_request_with_error);
+
+        // Run the server.
+        let server = TestServer::new(app).expect("Should create test server");
+
+        // Get the request.
+        let _ = server.get(&"/ping").await;
+    }
+}
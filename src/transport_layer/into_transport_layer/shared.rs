@@ -0,0 +1,164 @@
+use ::anyhow::Result;
+use ::axum::response::IntoResponse;
+use ::axum::BoxError;
+use ::http::Request;
+use ::hyper::Body;
+use ::std::future::Future;
+use ::std::pin::Pin;
+use ::std::task::Context;
+use ::std::task::Poll;
+use ::tower::make::Shared;
+use ::tower::util::ServiceExt;
+use ::tower::Service;
+
+use super::BoxedService;
+use super::IntoTransportLayer;
+use crate::transport_layer::TransportLayer;
+use crate::transport_layer::TransportLayerBuilder;
+
+/// This allows any service to be tested,
+/// including those wrapped in layers using [`tower::ServiceBuilder`](::tower::ServiceBuilder).
+///
+/// It is the same as how they would be served using `axum::Server::bind(addr).serve(Shared::new(service))`.
+impl<S> IntoTransportLayer for Shared<S>
+where
+    S: Service<Request<Body>> + Clone + Send + 'static,
+    S::Response: IntoResponse + Send,
+    S::Error: Into<BoxError> + Send,
+    S::Future: Send,
+{
+    fn into_http_transport_layer(
+        self,
+        builder: TransportLayerBuilder,
+    ) -> Result<Box<dyn TransportLayer>> {
+        BoxedService::new(SharedService(self)).into_http_transport_layer(builder)
+    }
+
+    fn into_mock_transport_layer(self) -> Result<Box<dyn TransportLayer>> {
+        BoxedService::new(SharedService(self)).into_mock_transport_layer()
+    }
+}
+
+/// Takes a new service from the `Shared` for each request,
+/// in the same way a server would for each new connection.
+#[derive(Clone)]
+struct SharedService<S>(Shared<S>);
+
+impl<S> Service<Request<Body>> for SharedService<S>
+where
+    S: Service<Request<Body>> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let shared = self.0.clone();
+
+        Box::pin(async move {
+            let service = match shared.oneshot(()).await {
+                Ok(service) => service,
+                Err(never) => match never {},
+            };
+
+            service.oneshot(request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_into_http_transport_layer_for_shared {
+    use ::axum::response::Response;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::http::header::HeaderValue;
+    use ::tower::make::Shared;
+    use ::tower::ServiceBuilder;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn get_ping() -> &'static str {
+        "pong!"
+    }
+
+    fn add_layered_header(mut response: Response) -> Response {
+        let header_value = HeaderValue::from_static("yes");
+        response.headers_mut().insert("x-layered", header_value);
+        response
+    }
+
+    #[tokio::test]
+    async fn it_should_create_and_test_with_layered_service() {
+        // Build an application with a route, wrapped in a layer.
+        let router = Router::new().route("/ping", get(get_ping));
+        let service = ServiceBuilder::new()
+            .map_response(add_layered_header)
+            .service(router);
+        let app = Shared::new(service);
+
+        // Run the server.
+        let config = TestServerConfig {
+            transport: Some(Transport::HttpRandomPort),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        // Get the request.
+        let response = server.get(&"/ping").await;
+        response.assert_text(&"pong!");
+        assert_eq!(response.header("x-layered"), "yes");
+    }
+}
+
+#[cfg(test)]
+mod test_into_mock_transport_layer_for_shared {
+    use ::axum::response::Response;
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use ::http::header::HeaderValue;
+    use ::tower::make::Shared;
+    use ::tower::ServiceBuilder;
+
+    use crate::TestServer;
+    use crate::TestServerConfig;
+    use crate::Transport;
+
+    async fn get_ping() -> &'static str {
+        "pong!"
+    }
+
+    fn add_layered_header(mut response: Response) -> Response {
+        let header_value = HeaderValue::from_static("yes");
+        response.headers_mut().insert("x-layered", header_value);
+        response
+    }
+
+    #[tokio::test]
+    async fn it_should_create_and_test_with_layered_service() {
+        // Build an application with a route, wrapped in a layer.
+        let router = Router::new().route("/ping", get(get_ping));
+        let service = ServiceBuilder::new()
+            .map_response(add_layered_header)
+            .service(router);
+        let app = Shared::new(service);
+
+        // Run the server.
+        let config = TestServerConfig {
+            transport: Some(Transport::MockHttp),
+            ..TestServerConfig::default()
+        };
+        let server = TestServer::new_with_config(app, config).expect("Should create test server");
+
+        // Get the request.
+        let response = server.get(&"/ping").await;
+        response.assert_text(&"pong!");
+        assert_eq!(response.header("x-layered"), "yes");
+    }
+}
@@ -0,0 +1,48 @@
+use ::axum::extract::connect_info::Connected;
+use ::std::net::IpAddr;
+use ::std::net::Ipv4Addr;
+use ::std::net::SocketAddr;
+
+/// The client address used by mocked requests,
+/// when one has not been set with [`TestRequest::client_address()`](crate::TestRequest::client_address()),
+/// or with [`TestServerConfig::default_client_address`](crate::TestServerConfig::default_client_address).
+pub const DEFAULT_MOCK_CLIENT_ADDRESS: SocketAddr =
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+
+///
+/// The stand in for a real connection, when running on the [`Transport::MockHttp`](crate::Transport::MockHttp) transport.
+///
+/// Services built using [`axum::extract::connect_info::IntoMakeServiceWithConnectInfo`]
+/// are given one of these for each request.
+/// It's used to build the [`axum::extract::ConnectInfo`] that handlers receive.
+///
+/// This is implemented for `SocketAddr`. Custom connect info types can be mocked,
+/// by implementing `Connected<&MockConnection>` for them.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MockConnection {
+    remote_addr: SocketAddr,
+}
+
+impl MockConnection {
+    pub(crate) fn new(remote_addr: SocketAddr) -> Self {
+        Self { remote_addr }
+    }
+
+    /// Returns the address of the client making the request.
+    pub fn remote_addr(&self) -> SocketAddr {
+        self.remote_addr
+    }
+}
+
+impl Default for MockConnection {
+    fn default() -> Self {
+        Self::new(DEFAULT_MOCK_CLIENT_ADDRESS)
+    }
+}
+
+impl Connected<&MockConnection> for SocketAddr {
+    fn connect_info(target: &MockConnection) -> Self {
+        target.remote_addr()
+    }
+}
@@ -1,6 +1,9 @@
 mod into_transport_layer;
 pub use self::into_transport_layer::*;
 
+mod mock_connection;
+pub use self::mock_connection::*;
+
 mod transport_layer;
 pub use self::transport_layer::*;
 
@@ -1,16 +1,60 @@
 use ::anyhow::Result;
 use ::async_trait::async_trait;
+use ::axum::body::BoxBody;
 use ::bytes::Bytes;
 use ::http::response::Parts;
 use ::http::Request;
+use ::http::Response;
+use ::hyper::body::to_bytes;
 use ::hyper::Body;
 use ::std::fmt::Debug;
 use ::url::Url;
 
+/// A `TransportLayer` takes requests built by the `TestServer`,
+/// and sends them to the application being tested.
+///
+/// Sending only needs a shared reference,
+/// so many requests can be in flight against the same `TransportLayer` at once.
 #[async_trait]
-pub trait TransportLayer: Debug {
-    async fn send(&mut self, request: Request<Body>) -> Result<(Parts, Bytes)>;
+pub trait TransportLayer: Debug + Send + Sync {
+    /// Sends the request, and reads the whole of the response body.
+    async fn send(&self, request: Request<Body>) -> Result<(Parts, Bytes)> {
+        let response = self.send_streaming(request).await?;
+        let (parts, response_body) = response.into_parts();
+        let response_bytes = to_bytes(response_body).await?;
+
+        Ok((parts, response_bytes))
+    }
+
+    /// Sends the request, and returns once the response head has been received.
+    /// The body is left to be read by the caller.
+    async fn send_streaming(&self, request: Request<Body>) -> Result<Response<BoxBody>>;
+
     fn url<'a>(&'a self) -> Option<&'a Url> {
         None
     }
+
+    /// Returns true if requests are handled within the `TestServer`,
+    /// without going over a real connection.
+    ///
+    /// Some features, such as capturing logs, are only available when mocked.
+    fn is_mock(&self) -> bool {
+        false
+    }
+
+    /// Returns false once the server has stopped,
+    /// either from being shut down, or from crashing.
+    fn is_running(&self) -> bool {
+        true
+    }
+
+    /// Stops the server.
+    /// Any requests sent afterwards will return an error.
+    async fn shutdown(&self) {}
+
+    /// Stops the server from taking new requests,
+    /// and waits for the requests already in flight to finish.
+    async fn graceful_shutdown(&self) {
+        self.shutdown().await
+    }
 }
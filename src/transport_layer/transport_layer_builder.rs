@@ -4,21 +4,118 @@ use ::reserve_port::ReservedPort;
 use ::std::net::IpAddr;
 use ::std::net::SocketAddr;
 use ::std::net::TcpListener;
+#[cfg(unix)]
+use ::std::path::Path;
+#[cfg(unix)]
+use ::std::path::PathBuf;
 
+use crate::internals::HttpClient;
 use crate::internals::StartingTcpSetup;
+#[cfg(feature = "tls")]
+use crate::internals::TlsSetup;
 
 pub struct TransportLayerBuilder {
     ip: Option<IpAddr>,
     port: Option<u16>,
+    is_http2_only: bool,
+
+    #[cfg(feature = "tls")]
+    maybe_tls_setup: Option<TlsSetup>,
+
+    #[cfg(unix)]
+    maybe_unix_socket_path: Option<PathBuf>,
 }
 
 impl TransportLayerBuilder {
     pub(crate) fn new(ip: Option<IpAddr>, port: Option<u16>) -> Self {
-        Self { ip, port }
+        Self {
+            ip,
+            port,
+            is_http2_only: false,
+
+            #[cfg(feature = "tls")]
+            maybe_tls_setup: None,
+
+            #[cfg(unix)]
+            maybe_unix_socket_path: None,
+        }
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn unix_socket(mut self, socket_path: PathBuf) -> Self {
+        self.maybe_unix_socket_path = Some(socket_path);
+        self
+    }
+
+    /// The path of the Unix socket the server should listen on,
+    /// if it is to be served over a Unix socket (rather than TCP).
+    #[cfg(unix)]
+    pub fn maybe_unix_socket_path(&self) -> Option<&Path> {
+        self.maybe_unix_socket_path.as_deref()
+    }
+
+    #[cfg(feature = "tls")]
+    pub(crate) fn tls(mut self, tls_setup: TlsSetup) -> Self {
+        self.maybe_tls_setup = Some(tls_setup);
+        self
+    }
+
+    #[cfg(feature = "tls")]
+    pub(crate) fn maybe_tls_setup(&self) -> Option<&TlsSetup> {
+        self.maybe_tls_setup.as_ref()
+    }
+
+    /// Returns true if the server should be served over TLS.
+    pub fn is_tls(&self) -> bool {
+        #[cfg(feature = "tls")]
+        {
+            self.maybe_tls_setup.is_some()
+        }
+
+        #[cfg(not(feature = "tls"))]
+        {
+            false
+        }
+    }
+
+    /// The URL scheme the server will be reachable on,
+    /// either `http` or `https`.
+    pub fn scheme(&self) -> &'static str {
+        if self.is_tls() {
+            "https"
+        } else {
+            "http"
+        }
+    }
+
+    /// Builds the client for talking to the server this builder sets up.
+    pub(crate) fn http_client(&self) -> HttpClient {
+        #[cfg(feature = "tls")]
+        if let Some(tls_setup) = &self.maybe_tls_setup {
+            return HttpClient::new_https(tls_setup.client_config.clone());
+        }
+
+        #[cfg(unix)]
+        if let Some(socket_path) = &self.maybe_unix_socket_path {
+            return HttpClient::new_unix(socket_path.clone(), self.is_http2_only);
+        }
+
+        HttpClient::new_http(self.is_http2_only)
+    }
+
+    pub(crate) fn http2_only(mut self) -> Self {
+        self.is_http2_only = true;
+        self
+    }
+
+    /// Returns true if the server, and the client talking to it,
+    /// should only speak HTTP/2.
+    pub fn is_http2_only(&self) -> bool {
+        self.is_http2_only
     }
 
     pub(crate) fn tcp_listener_with_reserved_port(
-        self,
+        &self,
     ) -> Result<(SocketAddr, TcpListener, Option<ReservedPort>)> {
         let setup = StartingTcpSetup::new(self.ip, self.port)
             .context("Cannot create socket address for use")?;
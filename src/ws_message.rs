@@ -0,0 +1,123 @@
+use ::bytes::Bytes;
+use ::std::borrow::Cow;
+use ::tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use ::tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use ::tokio_tungstenite::tungstenite::Message;
+
+/// The close code for a normal closure, as listed in
+/// [RFC 6455](https://www.rfc-editor.org/rfc/rfc6455#section-7.4.1).
+pub const WS_CLOSE_CODE_NORMAL: u16 = 1000;
+
+///
+/// A message sent to, or received from, a [`TestWebSocket`](crate::TestWebSocket).
+///
+/// Ping and pong messages are answered automatically,
+/// and so are never returned when reading messages.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WsMessage {
+    Text(String),
+    Binary(Bytes),
+    Ping(Bytes),
+    Pong(Bytes),
+
+    /// Closes the connection, with an optional close code and reason.
+    Close(Option<WsCloseFrame>),
+}
+
+impl WsMessage {
+    pub(crate) fn into_tungstenite(self) -> Message {
+        match self {
+            Self::Text(text) => Message::Text(text),
+            Self::Binary(message_bytes) => Message::Binary(message_bytes.into()),
+            Self::Ping(message_bytes) => Message::Ping(message_bytes.into()),
+            Self::Pong(message_bytes) => Message::Pong(message_bytes.into()),
+            Self::Close(maybe_close_frame) => {
+                Message::Close(maybe_close_frame.map(WsCloseFrame::into_tungstenite))
+            }
+        }
+    }
+
+    pub(crate) fn from_tungstenite(message: Message) -> Self {
+        match message {
+            Message::Text(text) => Self::Text(text),
+            Message::Binary(message_bytes) => Self::Binary(message_bytes.into()),
+            Message::Ping(message_bytes) => Self::Ping(message_bytes.into()),
+            Message::Pong(message_bytes) => Self::Pong(message_bytes.into()),
+            Message::Close(maybe_close_frame) => {
+                Self::Close(maybe_close_frame.map(WsCloseFrame::from_tungstenite))
+            }
+            // Raw frames are only seen when writing, but are kept as binary to be safe.
+            Message::Frame(frame) => Self::Binary(frame.into_data().into()),
+        }
+    }
+}
+
+///
+/// The close code and reason, sent when closing a WebSocket connection.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WsCloseFrame {
+    code: u16,
+    reason: String,
+}
+
+impl WsCloseFrame {
+    pub fn new<R>(code: u16, reason: R) -> Self
+    where
+        R: Into<String>,
+    {
+        Self {
+            code,
+            reason: reason.into(),
+        }
+    }
+
+    /// The close code, such as `1000` for a normal closure.
+    #[must_use]
+    pub fn code(&self) -> u16 {
+        self.code
+    }
+
+    /// The reason given for closing, which may be empty.
+    #[must_use]
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    pub(crate) fn into_tungstenite(self) -> CloseFrame<'static> {
+        CloseFrame {
+            code: CloseCode::from(self.code),
+            reason: Cow::Owned(self.reason),
+        }
+    }
+
+    pub(crate) fn from_tungstenite(close_frame: CloseFrame<'_>) -> Self {
+        Self {
+            code: u16::from(close_frame.code),
+            reason: close_frame.reason.into_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_into_tungstenite {
+    use super::*;
+
+    #[test]
+    fn it_should_round_trip_messages() {
+        let messages = vec![
+            WsMessage::Text("hello".to_string()),
+            WsMessage::Binary(Bytes::from_static(b"\x01\x02")),
+            WsMessage::Ping(Bytes::from_static(b"ping")),
+            WsMessage::Pong(Bytes::from_static(b"pong")),
+            WsMessage::Close(None),
+            WsMessage::Close(Some(WsCloseFrame::new(4000, "goodbye"))),
+        ];
+
+        for message in messages {
+            let round_tripped = WsMessage::from_tungstenite(message.clone().into_tungstenite());
+            assert_eq!(round_tripped, message);
+        }
+    }
+}
@@ -2,3 +2,4 @@
 
 cargo test --example=example-todo
 cargo test "$@"
+cargo test --all-features "$@"